@@ -0,0 +1,227 @@
+//! Audio and subtitle sync adjustment
+//!
+//! Delays are nudged in small steps with OSD feedback on the player, and the
+//! last correction is remembered per media item and restored when it's played
+//! again.
+
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use crate::settings::{config_file_path, read_json, write_json, SettingsState};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+/// Saved offsets file name inside the app config directory
+const SYNC_FILE: &str = "sync_offsets.json";
+
+/// Nudge step when none is configured
+const DEFAULT_STEP_MS: u32 = 50;
+
+/// How long the OSD feedback stays visible
+const OSD_DURATION_MS: u32 = 1500;
+
+/// Saved sync correction for a media item (seconds)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOffsets {
+    pub audio_delay: f64,
+    pub subtitle_delay: f64,
+}
+
+/// Which delay to adjust
+#[derive(Debug, Clone, Copy)]
+enum SyncTarget {
+    Audio,
+    Subtitle,
+}
+
+/// Global sync offset store
+pub struct SyncState {
+    offsets: RwLock<HashMap<String, SyncOffsets>>,
+    path: RwLock<Option<PathBuf>>,
+}
+
+impl SyncState {
+    pub fn new() -> Self {
+        Self {
+            offsets: RwLock::new(HashMap::new()),
+            path: RwLock::new(None),
+        }
+    }
+
+    /// Load saved offsets from the app config directory
+    pub fn load(&self, app: &AppHandle) {
+        let Some(path) = config_file_path(app, SYNC_FILE) else {
+            return;
+        };
+
+        if let Some(offsets) = read_json::<HashMap<String, SyncOffsets>>(&path) {
+            *self.offsets.write() = offsets;
+        }
+        *self.path.write() = Some(path);
+    }
+
+    /// Get the saved offsets for a media key
+    pub fn get(&self, key: &str) -> Option<SyncOffsets> {
+        self.offsets.read().get(key).copied()
+    }
+
+    /// Save offsets for a media key (zero offsets are forgotten)
+    fn set(&self, key: &str, offsets: SyncOffsets) {
+        let mut all = self.offsets.write();
+        if offsets.audio_delay == 0.0 && offsets.subtitle_delay == 0.0 {
+            all.remove(key);
+        } else {
+            all.insert(key.to_string(), offsets);
+        }
+
+        if let Some(path) = self.path.read().as_ref() {
+            if let Err(e) = write_json(path, &*all) {
+                log::warn!("Failed to save sync offsets: {}", e);
+            }
+        }
+    }
+}
+
+impl Default for SyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Key identifying a media item: the Jellyfin item ID for server URLs,
+/// the URL without its query string (which may hold tokens) for other
+/// streams, and the full path for local files
+pub fn media_key(url: &str) -> String {
+    let lower = url.to_ascii_lowercase();
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        // '?' and '#' are ordinary characters in file names
+        return url.to_string();
+    }
+    let without_query = url.split(['?', '#']).next().unwrap_or(url);
+
+    let segments: Vec<&str> = without_query.split('/').collect();
+    for pair in segments.windows(2) {
+        let is_item_segment =
+            pair[0].eq_ignore_ascii_case("videos") || pair[0].eq_ignore_ascii_case("items");
+        if is_item_segment && !pair[1].is_empty() {
+            return format!("jellyfin:{}", pair[1].to_lowercase());
+        }
+    }
+
+    without_query.to_string()
+}
+
+/// Apply the saved correction for `url` before it's loaded (resets to 0 if none)
+pub fn apply_saved(app: &AppHandle, state: &MpvState, url: &str) {
+    let offsets = app
+        .try_state::<SyncState>()
+        .and_then(|sync| sync.get(&media_key(url)))
+        .unwrap_or_default();
+
+    let result = state.with_player(|ipc| {
+        ipc.set_audio_delay(offsets.audio_delay)?;
+        ipc.set_subtitle_delay(offsets.subtitle_delay)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to apply saved sync offsets: {}", e);
+    }
+}
+
+/// Step a delay by `direction` * the configured step, show it on the OSD and save it
+fn nudge(app: &AppHandle, state: &MpvState, target: SyncTarget, direction: i8) -> Result<f64, String> {
+    let step_ms = app
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.get().sync_step_ms)
+        .unwrap_or(DEFAULT_STEP_MS);
+    let step = f64::from(step_ms) / 1000.0 * f64::from(direction.signum());
+
+    let (delay, path) = state
+        .with_player(|ipc| {
+            let current = match target {
+                SyncTarget::Audio => ipc.get_audio_delay()?,
+                SyncTarget::Subtitle => ipc.get_subtitle_delay()?,
+            };
+            // Round to whole ms so repeated steps don't drift
+            let delay = ((current + step) * 1000.0).round() / 1000.0;
+
+            let label = match target {
+                SyncTarget::Audio => {
+                    ipc.set_audio_delay(delay)?;
+                    "Audio delay"
+                }
+                SyncTarget::Subtitle => {
+                    ipc.set_subtitle_delay(delay)?;
+                    "Subtitle delay"
+                }
+            };
+            let _ = ipc.show_text(
+                &format!("{}: {:+.0} ms", label, delay * 1000.0),
+                OSD_DURATION_MS,
+            );
+
+            Ok((delay, ipc.get_path()?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    if let (Some(sync), Some(path)) = (app.try_state::<SyncState>(), path) {
+        let key = media_key(&path);
+        let mut offsets = sync.get(&key).unwrap_or_default();
+        match target {
+            SyncTarget::Audio => offsets.audio_delay = delay,
+            SyncTarget::Subtitle => offsets.subtitle_delay = delay,
+        }
+        sync.set(&key, offsets);
+    }
+
+    Ok(delay)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Step the audio delay (+1 later, -1 earlier, 0 = just show it). Returns the new delay in seconds.
+#[tauri::command]
+pub fn nudge_audio_sync(app: AppHandle, state: State<MpvState>, direction: i8) -> CommandResult<f64> {
+    match nudge(&app, &state, SyncTarget::Audio, direction) {
+        Ok(delay) => CommandResult::ok(delay),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Step the subtitle delay (+1 later, -1 earlier, 0 = just show it). Returns the new delay in seconds.
+#[tauri::command]
+pub fn nudge_subtitle_sync(
+    app: AppHandle,
+    state: State<MpvState>,
+    direction: i8,
+) -> CommandResult<f64> {
+    match nudge(&app, &state, SyncTarget::Subtitle, direction) {
+        Ok(delay) => CommandResult::ok(delay),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Check a sync nudge step (1-1000 ms)
+pub(crate) fn validate_sync_step(step_ms: u32) -> Result<(), String> {
+    if !(1..=1000).contains(&step_ms) {
+        return Err(format!("Invalid sync step: {} ms (expected 1-1000)", step_ms));
+    }
+    Ok(())
+}
+
+/// Set the sync nudge step in milliseconds
+#[tauri::command]
+pub fn set_sync_step(state: State<SettingsState>, step_ms: u32) -> CommandResult<u32> {
+    if let Err(e) = validate_sync_step(step_ms) {
+        return CommandResult::err(e);
+    }
+
+    match state.update(|s| s.sync_step_ms = Some(step_ms)) {
+        Ok(_) => CommandResult::ok(step_ms),
+        Err(e) => CommandResult::err(e),
+    }
+}
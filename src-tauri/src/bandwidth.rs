@@ -0,0 +1,271 @@
+//! LAN throughput test for cast targets
+//!
+//! Two ways to measure, depending on what the target is:
+//!
+//! - An `http://` URL (a receiver app or server on the target's machine): the
+//!   backend downloads from it for a few seconds and times the transfer.
+//!   There's no TLS client, so `https://` targets are rejected.
+//! - A bare IP or hostname (a TV that can only pull): a generated payload is
+//!   offered on the streaming server and `bandwidth-test-ready` is emitted with
+//!   its URL. The frontend points the target at it and the bytes served are
+//!   timed once the transfer starts.
+//!
+//! Either test is short and can be stopped with `cancel_bandwidth_test`.
+
+use crate::commands::{ensure_stream_server, CommandResult, StreamingState};
+use crate::net;
+use serde::Serialize;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Default measurement window
+const DEFAULT_DURATION_SECS: u64 = 5;
+
+/// Longest allowed measurement window
+const MAX_DURATION_SECS: u64 = 15;
+
+/// How long a pull test waits for the target to start downloading
+const PULL_START_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Connect/read timeout for download tests
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often a pull test checks the served byte count
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Event payload for `bandwidth-test-ready`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthTestReady {
+    pub target: String,
+    /// URL the target should load
+    pub url: String,
+}
+
+/// Measured throughput
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthResult {
+    /// "download" (we fetched from the target) or "pull" (the target fetched from us)
+    pub method: &'static str,
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+    pub mbps: f64,
+    /// Whether `mbps` covers the required bitrate (None if none was given)
+    pub sufficient: Option<bool>,
+    /// The test was cancelled; the figures cover the part that ran
+    pub cancelled: bool,
+}
+
+/// Bandwidth test state
+pub struct BandwidthState {
+    running: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+impl BandwidthState {
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Default for BandwidthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Build a result from a byte count and the time it took (Mbps rounded to 0.1)
+fn result(
+    method: &'static str,
+    bytes: u64,
+    elapsed: Duration,
+    cancelled: bool,
+) -> BandwidthResult {
+    let secs = elapsed.as_secs_f64().max(0.001);
+    BandwidthResult {
+        method,
+        bytes,
+        elapsed_ms: elapsed.as_millis() as u64,
+        mbps: (bytes as f64 * 8.0 / secs / 1_000_000.0 * 10.0).round() / 10.0,
+        sufficient: None,
+        cancelled,
+    }
+}
+
+/// Download from an `http://` URL for up to `duration` and time it
+fn measure_download(
+    url: &str,
+    duration: Duration,
+    cancelled: &AtomicBool,
+) -> Result<BandwidthResult, String> {
+    if url.starts_with("https://") {
+        return Err("https not supported for bandwidth test".to_string());
+    }
+    let mut stream = net::send_get(url, &[], IO_TIMEOUT)?;
+
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut header = Vec::new();
+    let mut bytes = 0u64;
+    let mut started: Option<Instant> = None;
+
+    loop {
+        if cancelled.load(Ordering::SeqCst) || started.is_some_and(|s| s.elapsed() >= duration) {
+            break;
+        }
+        let n = match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => return Err(format!("Download failed: {}", e)),
+        };
+
+        if started.is_some() {
+            bytes += n as u64;
+            continue;
+        }
+
+        // Time the body only, starting once the headers are in
+        header.extend_from_slice(&buffer[..n]);
+        if let Some(end) = header.windows(4).position(|w| w == b"\r\n\r\n") {
+            net::check_status(&String::from_utf8_lossy(&header[..end]))?;
+            bytes = (header.len() - end - 4) as u64;
+            started = Some(Instant::now());
+        }
+    }
+
+    let elapsed = started.map(|s| s.elapsed()).unwrap_or_default();
+    if bytes == 0 {
+        return Err("The target didn't send any data".to_string());
+    }
+    Ok(result("download", bytes, elapsed, cancelled.load(Ordering::SeqCst)))
+}
+
+/// Offer a payload on the streaming server and time the target pulling it
+fn measure_pull(
+    app: &AppHandle,
+    target: &str,
+    duration: Duration,
+    cancelled: &AtomicBool,
+) -> Result<BandwidthResult, String> {
+    ensure_stream_server(app)?;
+    let streaming = app.state::<StreamingState>();
+    let (id, url, bytes_served) = streaming
+        .0
+        .lock()
+        .start_speed_test()
+        .ok_or_else(|| "Streaming server URL not available".to_string())?;
+
+    let _ = app.emit(
+        "bandwidth-test-ready",
+        BandwidthTestReady {
+            target: target.to_string(),
+            url,
+        },
+    );
+
+    // Wait for the first bytes, then measure for the full window
+    let waiting = Instant::now();
+    let mut started: Option<(Instant, u64)> = None;
+    let outcome = loop {
+        if cancelled.load(Ordering::SeqCst) {
+            break Ok(());
+        }
+        let served = bytes_served.load(Ordering::Relaxed);
+        match started {
+            None if served > 0 => started = Some((Instant::now(), served)),
+            None if waiting.elapsed() >= PULL_START_TIMEOUT => {
+                break Err(format!(
+                    "{} didn't start downloading the test payload within {} seconds",
+                    target,
+                    PULL_START_TIMEOUT.as_secs()
+                ));
+            }
+            Some((start, _)) if start.elapsed() >= duration => break Ok(()),
+            _ => {}
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    streaming.0.lock().end_speed_test(&id);
+    outcome?;
+
+    let Some((start, initial)) = started else {
+        return Err("Bandwidth test was cancelled before the target started".to_string());
+    };
+    let bytes = bytes_served.load(Ordering::Relaxed) - initial;
+    Ok(result("pull", bytes, start.elapsed(), cancelled.load(Ordering::SeqCst)))
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Measure LAN throughput to a cast target, in Mbps
+///
+/// `target` is either an `http://` URL to download from or the target's IP
+/// (see the module docs). Pass the file's bitrate as `required_mbps` to get a
+/// verdict on whether the network can likely sustain it.
+#[tauri::command]
+pub async fn measure_cast_bandwidth(
+    app: AppHandle,
+    target: String,
+    duration_secs: Option<u64>,
+    required_mbps: Option<f64>,
+) -> CommandResult<BandwidthResult> {
+    let state = app.state::<BandwidthState>();
+    if state.running.swap(true, Ordering::SeqCst) {
+        return CommandResult::err("A bandwidth test is already running");
+    }
+    state.cancelled.store(false, Ordering::SeqCst);
+
+    let duration = Duration::from_secs(
+        duration_secs
+            .unwrap_or(DEFAULT_DURATION_SECS)
+            .clamp(1, MAX_DURATION_SECS),
+    );
+
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn_blocking(move || {
+        let cancelled = &handle.state::<BandwidthState>().cancelled;
+        let target = target.trim();
+        if target.starts_with("http://") || target.starts_with("https://") {
+            measure_download(target, duration, cancelled)
+        } else {
+            measure_pull(&handle, target, duration, cancelled)
+        }
+    })
+    .await;
+
+    state.running.store(false, Ordering::SeqCst);
+
+    match task {
+        Ok(Ok(mut measured)) => {
+            measured.sufficient = required_mbps.map(|required| measured.mbps >= required);
+            log::info!(
+                "Cast bandwidth ({}): {:.1} Mbps over {} ms",
+                measured.method,
+                measured.mbps,
+                measured.elapsed_ms
+            );
+            CommandResult::ok(measured)
+        }
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Bandwidth test failed: {}", e)),
+    }
+}
+
+/// Stop a running bandwidth test early
+#[tauri::command]
+pub fn cancel_bandwidth_test(state: State<BandwidthState>) -> CommandResult<bool> {
+    let running = state.running.load(Ordering::SeqCst);
+    if running {
+        state.cancelled.store(true, Ordering::SeqCst);
+    }
+    CommandResult::ok(running)
+}
@@ -0,0 +1,129 @@
+//! Buffering strategy and cache refill events
+//!
+//! A strategy is a preset of mpv's demuxer readahead and cache-pause options.
+//! While one is set, the player monitor's poll checks `paused-for-cache` and emits
+//! `buffering-changed` when mpv auto-pauses to refill the cache (and as the
+//! refill progresses), so the UI can show a buffering indicator instead of
+//! looking frozen.
+
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use crate::player_monitor::PlayerSnapshot;
+use crate::settings::{BufferingStrategy, SettingsState};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Event payload for `buffering-changed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferingEvent {
+    /// mpv paused playback to refill the cache
+    pub paused_for_cache: bool,
+    /// Refill progress (0-100), if mpv reports it
+    pub percent: Option<i64>,
+}
+
+/// Buffering watcher state
+pub struct BufferingState {
+    /// Last emitted state, so only changes are sent
+    last: Mutex<Option<BufferingEvent>>,
+}
+
+impl BufferingState {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for BufferingState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn buffering_strategy(app: &AppHandle) -> Option<BufferingStrategy> {
+    app.try_state::<SettingsState>()
+        .and_then(|settings| settings.get().buffering_strategy)
+}
+
+/// Compare the cache state with the last one. Returns the event to emit if it changed.
+fn check_buffering(snapshot: &PlayerSnapshot, state: &BufferingState) -> Option<BufferingEvent> {
+    let paused_for_cache = snapshot.paused_for_cache;
+    let event = BufferingEvent {
+        paused_for_cache,
+        // Progress only matters while refilling; ignoring it otherwise
+        // avoids an event every poll during normal playback
+        percent: snapshot.cache_buffering.filter(|_| paused_for_cache),
+    };
+
+    let mut last = state.last.lock();
+    if last.as_ref() == Some(&event) {
+        return None;
+    }
+    let first = last.is_none();
+    *last = Some(event.clone());
+
+    // Nothing to report until mpv has paused at least once
+    if first && !event.paused_for_cache {
+        return None;
+    }
+    Some(event)
+}
+
+/// Buffering check for the player monitor's poll (no-op without a strategy)
+pub(crate) fn check(app: &AppHandle, snapshot: &PlayerSnapshot) {
+    let state = app.state::<BufferingState>();
+    if buffering_strategy(app).is_none() {
+        // Start from a clean baseline when a strategy is set again
+        *state.last.lock() = None;
+        return;
+    }
+    if let Some(event) = check_buffering(snapshot, &state) {
+        if event.paused_for_cache {
+            log::debug!("Paused for cache ({:?}%)", event.percent);
+        }
+        let _ = app.emit("buffering-changed", event);
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Set the buffering strategy (None = leave mpv's defaults on next start)
+///
+/// Applied to the running player right away and emits `buffering-changed`
+/// while mpv pauses to refill its cache.
+#[tauri::command]
+pub fn set_buffering_strategy(
+    app: AppHandle,
+    state: State<MpvState>,
+    strategy: Option<BufferingStrategy>,
+) -> CommandResult<Option<BufferingStrategy>> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available".to_string());
+    };
+    if let Err(e) = settings.update(|s| s.buffering_strategy = strategy) {
+        return CommandResult::err(e);
+    }
+
+    if let Some(strategy) = strategy {
+        if state.is_initialized() {
+            if let Err(e) = state.set_buffering_strategy(strategy) {
+                return CommandResult::err(e.to_string());
+            }
+        }
+    }
+
+    log::info!("Buffering strategy set to {:?}", strategy);
+    CommandResult::ok(strategy)
+}
+
+/// Get the saved buffering strategy
+#[tauri::command]
+pub fn get_buffering_strategy(app: AppHandle) -> CommandResult<Option<BufferingStrategy>> {
+    CommandResult::ok(buffering_strategy(&app))
+}
@@ -0,0 +1,365 @@
+//! Cast session tracking
+//!
+//! Playback on a TV is started by the frontend through the Jellyfin sessions
+//! API. This module remembers which target is being cast to so local
+//! playback commands can be mirrored to it.
+//!
+//! `quick_cast` does the whole "cast this file to that TV" flow in one call:
+//! it registers the file with the streaming server, reads the device's
+//! description from its discovery location to find how to control it,
+//! pushes the stream URL over DLNA, records the session and emits
+//! `cast-play`. Chromecasts are refused; see the `dlna` module.
+//! HDR files get a `cast-hdr-warning`, since neither direct nor transcoded
+//! streams are tone-mapped and SDR TVs show them washed out.
+//!
+//! Ending or replacing a session removes its stream from the streaming
+//! server and stops a DLNA target the backend started.
+//!
+//! Mirroring is best-effort: when local state changes, transport commands
+//! are sent straight to a DLNA target (sessions with a `control_url`) or
+//! forwarded as `cast-sync` events for the frontend to relay to a Jellyfin
+//! session. The TV follows within a second or two but perfect frame sync
+//! isn't guaranteed. Changes made directly in the mpv window (OSC, keyboard)
+//! are not seen by the backend and are not mirrored.
+
+use crate::commands::{
+    ensure_stream_server, register_file_stream, CommandResult, StreamInfo, StreamingState,
+};
+use crate::dlna;
+use crate::probe;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Active cast session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastSession {
+    /// Jellyfin session ID or discovered device ID of the cast target
+    pub target_id: String,
+    /// Display name of the target device
+    pub target_name: Option<String>,
+    /// Streaming server stream ID, if casting a local file
+    pub stream_id: Option<String>,
+    /// Keep local mpv playing and mirror transport commands to the target
+    pub mirror: bool,
+    /// AVTransport control URL of a DLNA target driven by the backend
+    #[serde(default)]
+    pub control_url: Option<String>,
+    /// Unix timestamp (seconds) when the session started
+    #[serde(default)]
+    pub started_at: u64,
+}
+
+/// Transport command to forward to the cast target
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "command")]
+pub enum CastTransport {
+    Pause,
+    Unpause,
+    Seek { position: f64 },
+    Stop,
+}
+
+/// Event payload for `cast-sync`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastSyncEvent {
+    pub target_id: String,
+    pub transport: CastTransport,
+}
+
+/// Event payload for `cast-play`: playback of `stream_url` started on the target
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastPlayEvent {
+    pub target_id: String,
+    pub target_name: Option<String>,
+    pub stream_id: String,
+    pub stream_url: String,
+}
+
+/// Event payload for `cast-hdr-warning`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastHdrWarning {
+    pub target_id: String,
+    pub stream_id: String,
+    /// HDR transfer characteristic, e.g. "smpte2084"
+    pub transfer: String,
+    pub message: String,
+}
+
+/// What `quick_cast` started
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickCast {
+    pub session: CastSession,
+    pub stream: StreamInfo,
+}
+
+/// Global cast state
+pub struct CastState {
+    pub session: Mutex<Option<CastSession>>,
+}
+
+impl CastState {
+    pub fn new() -> Self {
+        Self {
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Get the current mirror session, if any
+    pub fn mirror_session(&self) -> Option<CastSession> {
+        self.session.lock().clone().filter(|s| s.mirror)
+    }
+}
+
+impl Default for CastState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Send a transport command to a DLNA target in the background
+fn send_dlna_transport(control_url: String, transport: CastTransport) {
+    std::thread::spawn(move || {
+        let result = match transport {
+            CastTransport::Pause => dlna::pause(&control_url),
+            CastTransport::Unpause => dlna::resume(&control_url),
+            CastTransport::Seek { position } => dlna::seek(&control_url, position),
+            CastTransport::Stop => dlna::stop(&control_url),
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to send {:?} to the DLNA target: {}", transport, e);
+        }
+    });
+}
+
+/// Forward a local transport change to the mirrored cast target (no-op when not mirroring)
+pub fn sync_mirror(app: &AppHandle, transport: CastTransport) {
+    let Some(state) = app.try_state::<CastState>() else {
+        return;
+    };
+
+    if let Some(session) = state.mirror_session() {
+        log::debug!("Mirroring {:?} to {}", transport, session.target_id);
+        if let Some(control_url) = session.control_url {
+            send_dlna_transport(control_url, transport);
+            return;
+        }
+        let _ = app.emit(
+            "cast-sync",
+            CastSyncEvent {
+                target_id: session.target_id,
+                transport,
+            },
+        );
+    }
+}
+
+/// Current Unix time in seconds
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Clean up after a session that ended or was replaced by `next`
+///
+/// Tells the target to stop unless `next` plays on the same target, and
+/// removes the session's stream unless `next` keeps using it.
+fn release_session(app: &AppHandle, session: &CastSession, next: Option<&CastSession>) {
+    let same_target = next.is_some_and(|next| next.target_id == session.target_id);
+    if !same_target {
+        log::info!("Ending cast session to {}", session.target_id);
+        match session.control_url.clone() {
+            Some(control_url) => send_dlna_transport(control_url, CastTransport::Stop),
+            None => {
+                let _ = app.emit(
+                    "cast-sync",
+                    CastSyncEvent {
+                        target_id: session.target_id.clone(),
+                        transport: CastTransport::Stop,
+                    },
+                );
+            }
+        }
+    }
+
+    let Some(stream_id) = &session.stream_id else {
+        return;
+    };
+    if next.is_some_and(|next| next.stream_id.as_ref() == Some(stream_id)) {
+        return;
+    }
+    if let Some(streaming) = app.try_state::<StreamingState>() {
+        streaming.0.lock().remove_stream(stream_id);
+    }
+}
+
+/// Make `session` the current one, cleaning up the session it replaces
+fn replace_session(app: &AppHandle, session: &CastSession) {
+    let previous = app.state::<CastState>().session.lock().replace(session.clone());
+    if let Some(previous) = previous {
+        release_session(app, &previous, Some(session));
+    }
+}
+
+/// End any cast session, tell the target to stop and remove its stream.
+/// Returns the ended session.
+pub fn end_session(app: &AppHandle) -> Option<CastSession> {
+    let state = app.try_state::<CastState>()?;
+    let session = state.session.lock().take()?;
+    release_session(app, &session, None);
+    Some(session)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Start a mirror session: local playback continues and is mirrored to the target
+///
+/// Pass the AVTransport `control_url` of a DLNA target to have transport
+/// commands sent to it directly; without one they're emitted as `cast-sync`.
+#[tauri::command]
+pub fn start_mirror_session(
+    app: AppHandle,
+    target_id: String,
+    target_name: Option<String>,
+    stream_id: Option<String>,
+    control_url: Option<String>,
+) -> CommandResult<CastSession> {
+    let session = CastSession {
+        target_id,
+        target_name,
+        stream_id,
+        mirror: true,
+        control_url: control_url.filter(|url| !url.trim().is_empty()),
+        started_at: unix_now(),
+    };
+
+    log::info!("Starting mirror session to {}", session.target_id);
+    replace_session(&app, &session);
+    CommandResult::ok(session)
+}
+
+/// Cast a local file to a target in one step
+///
+/// `device_location` is the device description URL found by discovery
+/// (SSDP `LOCATION`). The device's protocol is read from it: DLNA renderers
+/// are supported, Chromecasts fail with "Chromecast not supported".
+///
+/// Starts the streaming server if needed, registers the file, loads its
+/// stream URL on the renderer and starts playback, then replaces any current
+/// cast session and emits `cast-play`. Local mpv playback is left alone.
+///
+/// Runs off the main thread since the device calls can take a few seconds.
+#[tauri::command]
+pub async fn quick_cast(
+    app: AppHandle,
+    device_id: String,
+    file_path: String,
+    device_location: String,
+    device_name: Option<String>,
+) -> CommandResult<QuickCast> {
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        cast_file(&app, device_id, &file_path, &device_location, device_name)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(cast)) => CommandResult::ok(cast),
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Cast task failed: {}", e)),
+    }
+}
+
+/// Register a file, push it to a DLNA renderer and record the session
+fn cast_file(
+    app: &AppHandle,
+    device_id: String,
+    file_path: &str,
+    device_location: &str,
+    device_name: Option<String>,
+) -> Result<QuickCast, String> {
+    let control_url = dlna::find_control_url(device_location.trim())?;
+
+    ensure_stream_server(app)?;
+    let streaming = app.state::<StreamingState>();
+    let stream = register_file_stream(&streaming, file_path, None)?;
+
+    if let Err(e) = dlna::play(&control_url, &stream.stream_url) {
+        streaming.0.lock().remove_stream(&stream.stream_id);
+        return Err(format!("Couldn't start playback on the device: {}", e));
+    }
+
+    let session = CastSession {
+        target_id: device_id,
+        target_name: device_name,
+        stream_id: Some(stream.stream_id.clone()),
+        mirror: false,
+        control_url: Some(control_url),
+        started_at: unix_now(),
+    };
+    replace_session(app, &session);
+
+    log::info!("Casting {} to {}", file_path, session.target_id);
+    let _ = app.emit(
+        "cast-play",
+        CastPlayEvent {
+            target_id: session.target_id.clone(),
+            target_name: session.target_name.clone(),
+            stream_id: stream.stream_id.clone(),
+            stream_url: stream.stream_url.clone(),
+        },
+    );
+
+    warn_if_hdr(app, &session.target_id, &stream.stream_id, file_path);
+    Ok(QuickCast { session, stream })
+}
+
+/// Probe a cast file in the background and emit `cast-hdr-warning` if it's HDR
+fn warn_if_hdr(app: &AppHandle, target_id: &str, stream_id: &str, file_path: &str) {
+    let app = app.clone();
+    let target_id = target_id.to_string();
+    let stream_id = stream_id.to_string();
+    let file_path = file_path.to_string();
+
+    std::thread::spawn(move || match probe::hdr_transfer(Path::new(&file_path)) {
+        Ok(Some(transfer)) => {
+            log::info!("Casting HDR ({}) content: {}", transfer, file_path);
+            let _ = app.emit(
+                "cast-hdr-warning",
+                CastHdrWarning {
+                    target_id,
+                    stream_id,
+                    transfer,
+                    message: "This video is HDR and is sent without tone mapping. Colors \
+                              will look washed out unless the TV supports HDR."
+                        .to_string(),
+                },
+            );
+        }
+        Ok(None) => {}
+        Err(e) => log::debug!("Skipped HDR check for {}: {}", file_path, e),
+    });
+}
+
+/// End the current cast/mirror session, stopping the target and removing its stream
+#[tauri::command]
+pub fn stop_mirror_session(app: AppHandle) -> CommandResult<()> {
+    end_session(&app);
+    CommandResult::ok_empty()
+}
+
+/// Get the current cast session
+#[tauri::command]
+pub fn get_cast_session(state: State<CastState>) -> CommandResult<Option<CastSession>> {
+    CommandResult::ok(state.session.lock().clone())
+}
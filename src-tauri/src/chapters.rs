@@ -0,0 +1,180 @@
+//! Chapters and intro/credits auto-skip
+//!
+//! When auto-skip is enabled the player monitor's poll checks the current
+//! chapter and jumps past chapters whose titles match the configured
+//! patterns, emitting `chapter-skipped`. Each chapter is skipped at most once
+//! per file, so seeking back into an intro plays it.
+
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use crate::mpv_ipc::Chapter;
+use crate::player_monitor::PlayerSnapshot;
+use crate::settings::{AutoSkipSettings, SettingsState};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Event payload for `chapter-skipped`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterSkippedEvent {
+    pub title: String,
+    /// Skipped chapter index
+    pub from: i64,
+    /// Chapter jumped to (None = end of file)
+    pub to: Option<i64>,
+}
+
+/// Auto-skip watcher state
+pub struct AutoSkipState {
+    /// Skipping turned off for the current file only
+    session_disabled: AtomicBool,
+    /// File the watcher last saw and the chapters already skipped in it
+    current: Mutex<(Option<String>, HashSet<i64>)>,
+}
+
+impl AutoSkipState {
+    pub fn new() -> Self {
+        Self {
+            session_disabled: AtomicBool::new(false),
+            current: Mutex::new((None, HashSet::new())),
+        }
+    }
+}
+
+impl Default for AutoSkipState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn auto_skip_settings(app: &AppHandle) -> AutoSkipSettings {
+    app.try_state::<SettingsState>()
+        .map(|settings| settings.get().auto_skip)
+        .unwrap_or_default()
+}
+
+/// Check a chapter title against the skip patterns (case-insensitive substring)
+fn matches_pattern(title: &str, patterns: &[String]) -> bool {
+    let title = title.to_lowercase();
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .any(|p| title.contains(&p.trim().to_lowercase()))
+}
+
+/// Skip the current chapter if it matches. Returns the event to emit.
+fn check_chapter(
+    mpv: &MpvState,
+    state: &AutoSkipState,
+    settings: &AutoSkipSettings,
+    snapshot: &PlayerSnapshot,
+) -> Option<ChapterSkippedEvent> {
+    let mut current = state.current.lock();
+    if current.0 != snapshot.path {
+        // New file: forget skipped chapters and the per-session override
+        *current = (snapshot.path.clone(), HashSet::new());
+        state.session_disabled.store(false, Ordering::SeqCst);
+    }
+
+    if snapshot.path.is_none() || state.session_disabled.load(Ordering::SeqCst) {
+        return None;
+    }
+
+    let index = snapshot.chapter?;
+    if current.1.contains(&index) {
+        return None;
+    }
+
+    let chapters: Vec<Chapter> = mpv.get_chapters().ok()?;
+    let title = chapters.get(index as usize)?.title.clone()?;
+    if !matches_pattern(&title, &settings.patterns) {
+        return None;
+    }
+
+    current.1.insert(index);
+    let next = index + 1;
+    let to = if (next as usize) < chapters.len() {
+        mpv.set_chapter(next).ok()?;
+        Some(next)
+    } else {
+        mpv.seek_to_end().ok()?;
+        None
+    };
+
+    log::info!("Auto-skipped chapter {} ({})", index, title);
+    Some(ChapterSkippedEvent {
+        title,
+        from: index,
+        to,
+    })
+}
+
+/// Auto-skip check for the player monitor's poll (no-op while disabled)
+pub(crate) fn check(app: &AppHandle, mpv: &MpvState, snapshot: &PlayerSnapshot) {
+    let settings = auto_skip_settings(app);
+    if !settings.enabled {
+        return;
+    }
+    let state = app.state::<AutoSkipState>();
+    if let Some(event) = check_chapter(mpv, &state, &settings, snapshot) {
+        let _ = app.emit("chapter-skipped", event);
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Get the chapters of the loaded file
+#[tauri::command]
+pub fn get_chapters(state: State<MpvState>) -> CommandResult<Vec<Chapter>> {
+    match state.get_chapters() {
+        Ok(chapters) => CommandResult::ok(chapters),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Configure automatic skipping of chapters whose titles match `patterns`
+#[tauri::command]
+pub fn set_auto_skip(
+    app: AppHandle,
+    patterns: Vec<String>,
+    enabled: bool,
+) -> CommandResult<AutoSkipSettings> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available".to_string());
+    };
+
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    match settings.update(|s| s.auto_skip = AutoSkipSettings { enabled, patterns }) {
+        Ok(updated) => CommandResult::ok(updated.auto_skip),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Turn auto-skip off (or back on) for the current file only
+#[tauri::command]
+pub fn set_auto_skip_for_session(
+    state: State<AutoSkipState>,
+    mpv: State<MpvState>,
+    enabled: bool,
+) -> CommandResult<bool> {
+    let path = mpv.get_path().ok().flatten();
+
+    // Tie the override to the loaded file so the watcher doesn't reset it
+    let mut current = state.current.lock();
+    if current.0 != path {
+        *current = (path, HashSet::new());
+    }
+    state.session_disabled.store(!enabled, Ordering::SeqCst);
+
+    CommandResult::ok(enabled)
+}
@@ -3,13 +3,28 @@
 //! These commands are exposed to the frontend for controlling video playback
 //! and HTTP streaming for Cast to TV functionality.
 
-use crate::mpv::MpvState;
-use crate::mpv_ipc::PlaybackState;
-use crate::streaming::StreamingServer;
+use crate::av_sync;
+use crate::cast::{self, CastTransport};
+use crate::mpv::{MpvError, MpvState};
+use crate::mpv_ipc::{
+    self, CurrentMedia, HwdecFallback, IpcLogEntry, MpvCapabilities, MpvSpawnOptions, MpvTrack,
+    MpvVersion, OrphanedPlayer, PlaybackState, PlayerStatus, ProgressDetail, VideoOutput,
+    DEFAULT_OSD_DURATION_MS, DEFAULT_OSD_LEVEL, DEFAULT_TITLE, FORCE_FORMATS,
+    MIN_SUPPORTED_VERSION,
+};
+use crate::{player_monitor, recent, session, source_profiles};
+use crate::settings::{
+    AppSettings, AudioOnlyBehavior, KeepOpen, MotionSmoothing, OscSettings, QualityProfile,
+    ScreenshotOptions, SettingsState, SourceCleanup, SourceProfile, SubtitleViewing,
+    ViewingDistance,
+};
+use crate::streaming::{
+    self, BufferUsage, IpRange, ServerOptions, StreamMode, StreamSummary, StreamingServer,
+};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Response for command results
 #[derive(Debug, Serialize)]
@@ -48,112 +63,847 @@ impl CommandResult<()> {
 }
 
 /// Options for playing a video
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct PlayOptions {
+    /// Optional only where the URL is passed separately (`schedule_playback`)
+    #[serde(default)]
     pub url: String,
     pub start_position: Option<f64>,
     pub auth_token: Option<String>,
+    /// Jellyfin media type ("Audio", "Video"); guessed from the URL if omitted
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// HTTP User-Agent for this load (overrides the saved setting)
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// HTTP Referer for this load (overrides the saved setting)
+    #[serde(default)]
+    pub referrer: Option<String>,
+    /// Start position as a timestamp ("1:23:45", "83:10", "12.5"); alternative to `start_position`
+    #[serde(default)]
+    pub start_timestamp: Option<String>,
+    /// FFmpeg format to open the file as, for misdetected files (overrides `set_force_format`)
+    #[serde(default)]
+    pub force_format: Option<String>,
+    /// Source profile to apply (detected from the URL if omitted)
+    #[serde(default)]
+    pub profile: Option<SourceProfile>,
+    /// Open the file paused; only this file, later loads play as usual
+    #[serde(default)]
+    pub start_paused: bool,
+}
+
+/// Parse a `SS`, `MM:SS` or `HH:MM:SS` timestamp (fractional seconds allowed) into seconds
+pub fn parse_timestamp(timestamp: &str) -> Result<f64, String> {
+    let invalid = || {
+        format!(
+            "Invalid timestamp {:?} (expected SS, MM:SS or HH:MM:SS, e.g. 1:23:45.5)",
+            timestamp
+        )
+    };
+
+    let parts: Vec<&str> = timestamp.trim().split(':').collect();
+    if parts.len() > 3 || parts.iter().any(|p| p.is_empty()) {
+        return Err(invalid());
+    }
+
+    let (seconds, units) = parts.split_last().ok_or_else(invalid)?;
+    let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+    if !seconds.is_finite() || seconds < 0.0 || (!units.is_empty() && seconds >= 60.0) {
+        return Err(invalid());
+    }
+
+    let mut total = 0.0;
+    for (index, unit) in units.iter().enumerate() {
+        if !unit.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let value: u64 = unit.parse().map_err(|_| invalid())?;
+        // Minutes after hours must be below 60; the leading unit can be anything
+        if index > 0 && value >= 60 {
+            return Err(invalid());
+        }
+        total = total * 60.0 + value as f64;
+    }
+
+    Ok(total * 60.0 + seconds)
+}
+
+/// mpv version and capability report
+#[derive(Debug, Serialize)]
+pub struct MpvVersionInfo {
+    pub version: Option<MpvVersion>,
+    pub is_outdated: bool,
+    pub min_supported: String,
+    pub capabilities: MpvCapabilities,
+}
+
+impl MpvVersionInfo {
+    fn from_state(state: &MpvState) -> Result<Self, MpvError> {
+        let version = state.version()?;
+        Ok(Self {
+            is_outdated: version.as_ref().is_some_and(MpvVersion::is_outdated),
+            min_supported: format!("{}.{}", MIN_SUPPORTED_VERSION.0, MIN_SUPPORTED_VERSION.1),
+            capabilities: state.capabilities()?,
+            version,
+        })
+    }
+}
+
+/// A backend feature and whether it's usable right now
+#[derive(Debug, Serialize)]
+pub struct PlayerFeature {
+    pub name: &'static str,
+    pub available: bool,
+}
+
+/// What this backend and the detected mpv support, for UIs that adapt to both
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerCapabilities {
+    /// HubRemote backend version
+    pub backend_version: &'static str,
+    /// Target OS, e.g. "windows"
+    pub platform: &'static str,
+    /// None until the player has been started
+    pub mpv: Option<MpvVersionInfo>,
+    pub features: Vec<PlayerFeature>,
+    pub wrapped_properties: &'static [&'static str],
+    pub observed_properties: &'static [&'static str],
+}
+
+impl PlayerCapabilities {
+    fn from_state(state: &MpvState) -> Self {
+        let mpv = MpvVersionInfo::from_state(state).ok();
+        let caps = mpv
+            .as_ref()
+            .map(|info| info.capabilities)
+            .unwrap_or_default();
+
+        // Version-independent features work with any mpv the player can start
+        let features = [
+            ("chapters", true),
+            ("tracks", true),
+            ("trackPreferences", true),
+            ("screenshots", true),
+            ("statsOverlay", true),
+            ("nightMode", true),
+            ("motionSmoothing", true),
+            ("audioOnly", true),
+            ("softwareDecodeFallback", true),
+            ("gpuNext", caps.gpu_next),
+            ("hwdecAutoSafe", caps.hwdec_auto_safe),
+            ("playlistInsert", caps.loadfile_index),
+            ("firewallCheck", cfg!(windows)),
+        ]
+        .into_iter()
+        .map(|(name, available)| PlayerFeature { name, available })
+        .collect();
+
+        Self {
+            backend_version: env!("CARGO_PKG_VERSION"),
+            platform: std::env::consts::OS,
+            mpv,
+            features,
+            wrapped_properties: mpv_ipc::WRAPPED_PROPERTIES,
+            observed_properties: mpv_ipc::OBSERVED_PROPERTIES,
+        }
+    }
+}
+
+/// mpv `tone-mapping` curves accepted by `set_tone_mapping`
+const TONE_MAPPING_MODES: &[&str] = &[
+    "auto", "clip", "mobius", "reinhard", "hable", "gamma", "linear", "spline", "bt.2390",
+    "bt.2446a", "st2094-40", "st2094-10",
+];
+
+/// Screenshot formats accepted by `set_screenshot_options`
+const SCREENSHOT_FORMATS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// Check player settings from an imported configuration with the same rules
+/// as their individual setters
+pub(crate) fn validate_player_settings(settings: &AppSettings) -> Result<(), String> {
+    validate_screenshot_format(&settings.screenshot.format)?;
+    if let Some(level) = settings.osd_level {
+        validate_osd_level(level)?;
+    }
+    if let Some(ms) = settings.osd_duration_ms {
+        validate_osd_duration(ms)?;
+    }
+    if let Some(layout) = settings.audio_channels.as_deref() {
+        validate_audio_channels(layout)?;
+    }
+    if let Some(mode) = settings.tone_mapping.as_deref() {
+        validate_tone_mapping(mode)?;
+    }
+    if let Some(name) = settings.video_output.as_deref() {
+        validate_video_output(name)?;
+    }
+    if let Some(template) = settings.title_format.as_deref() {
+        validate_title_format(template)?;
+    }
+    Ok(())
+}
+
+/// Apply persisted player settings to a freshly spawned mpv
+pub(crate) fn apply_player_settings(app: &AppHandle, state: &MpvState) {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return;
+    };
+    let settings = settings.get();
+    // A new player starts from mpv's defaults
+    source_profiles::reset(app);
+
+    // Saved before an mpv downgrade; fall back instead of leaving no video
+    if let Some(Err(e)) = settings
+        .video_output
+        .as_deref()
+        .map(|name| check_vo_capability(state, name))
+    {
+        log::warn!("{}, using {}", e, mpv_ipc::DEFAULT_VO);
+        if let Err(e) = state.set_video_output(mpv_ipc::DEFAULT_VO) {
+            log::warn!("Failed to switch video output: {}", e);
+        }
+    }
+
+    if let Err(e) = state.set_screenshot_options(&settings.screenshot) {
+        log::warn!("Failed to apply screenshot options: {}", e);
+    }
+    if settings.osc.enabled {
+        if let Err(e) = state.set_osc_autohide(true, settings.osc.hide_delay_ms) {
+            log::warn!("Failed to apply OSC settings: {}", e);
+        }
+    }
+    if let Some(profile) = settings.quality_profile {
+        if let Err(e) = state.set_quality_profile(profile) {
+            log::warn!("Failed to apply quality profile {:?}: {}", profile, e);
+        }
+    }
+    if !settings.track_preferences.is_empty() {
+        if let Err(e) = state.set_track_languages(&settings.track_preferences) {
+            log::warn!("Failed to apply track languages: {}", e);
+        }
+    }
+    if let Some(strategy) = settings.buffering_strategy {
+        if let Err(e) = state.set_buffering_strategy(strategy) {
+            log::warn!("Failed to apply buffering strategy {:?}: {}", strategy, e);
+        }
+    }
+    if let Some(viewing) = settings.subtitle_viewing {
+        if let Err(e) = state.set_subtitle_viewing(viewing) {
+            log::warn!("Failed to apply subtitle size: {}", e);
+        }
+    }
+    if let Some(mode) = settings.tone_mapping.as_deref() {
+        if let Err(e) = state.set_tone_mapping(mode) {
+            log::warn!("Failed to apply tone mapping {}: {}", mode, e);
+        }
+    }
+    if settings.night_mode {
+        if let Err(e) = state.set_night_mode(true) {
+            log::warn!("Failed to apply night mode: {}", e);
+        }
+    }
+    if settings.motion_smoothing != MotionSmoothing::Off {
+        if let Err(e) = state.set_motion_smoothing(settings.motion_smoothing) {
+            log::warn!("Failed to apply motion smoothing: {}", e);
+        }
+    }
+    if settings.source_cleanup != SourceCleanup::Off {
+        if let Err(e) = state.set_source_cleanup(settings.source_cleanup) {
+            log::warn!("Failed to apply source cleanup: {}", e);
+        }
+    }
+    if !settings.software_decode_codecs.is_empty() {
+        if let Err(e) = state.set_software_decode_codecs(&settings.software_decode_codecs) {
+            log::warn!("Failed to apply software decode codecs: {}", e);
+        }
+    }
+    if let Some(layout) = settings.audio_channels.as_deref() {
+        if let Err(e) = state.set_audio_channels(layout) {
+            log::warn!("Failed to apply audio channels {}: {}", layout, e);
+        }
+    }
+    if let Some(last) = settings.last_volume.filter(|_| !settings.reset_volume_on_start) {
+        let result = state
+            .set_volume(last.volume)
+            .and_then(|_| state.set_mute(last.muted));
+        if let Err(e) = result {
+            log::warn!("Failed to restore volume {:?}: {}", last, e);
+        }
+    }
+}
+
+/// Build mpv command-line options from persisted settings
+fn spawn_options(app: &AppHandle) -> MpvSpawnOptions {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return MpvSpawnOptions::default();
+    };
+    let settings = settings.get();
+
+    MpvSpawnOptions {
+        osc: settings.osc.enabled,
+        keep_open: settings.keep_open.as_str(),
+        ontop: settings.always_on_top,
+        cache_dir: settings.mpv_cache_dir,
+        cache_on_disk: settings.mpv_cache_on_disk,
+        vo: settings.video_output,
+        osd_level: settings.osd_level.unwrap_or(DEFAULT_OSD_LEVEL),
+        osd_duration_ms: settings.osd_duration_ms.unwrap_or(DEFAULT_OSD_DURATION_MS),
+    }
+}
+
+/// Initialize the player if needed and warn the frontend about an outdated mpv
+pub(crate) fn ensure_player(app: &AppHandle, state: &MpvState) -> Result<(), MpvError> {
+    if state.is_initialized() {
+        return Ok(());
+    }
+
+    state.init(&spawn_options(app))?;
+    apply_player_settings(app, state);
+
+    if let Ok(info) = MpvVersionInfo::from_state(state) {
+        if info.is_outdated {
+            let _ = app.emit("mpv-outdated", &info);
+        }
+    }
+
+    Ok(())
+}
+
+fn audio_only_behavior(app: &AppHandle) -> AudioOnlyBehavior {
+    app.try_state::<SettingsState>()
+        .map(|settings| settings.get().audio_only_behavior)
+        .unwrap_or_default()
+}
+
+/// Apply the audio-only or video layout for the file about to be played
+///
+/// A guess from the media type or extension; corrected by
+/// `apply_loaded_media_layout` once the track list is in.
+fn apply_media_layout(app: &AppHandle, state: &MpvState, url: &str, media_type: Option<&str>) {
+    let is_audio = match media_type {
+        Some(media_type) => media_type.eq_ignore_ascii_case("audio"),
+        None => streaming::is_audio_path(url),
+    };
+
+    if let Err(e) = state.apply_media_layout(is_audio, audio_only_behavior(app), false) {
+        log::warn!("Failed to apply media layout: {}", e);
+    }
+}
+
+/// Apply the layout that matches a loaded file's tracks
+///
+/// Files without a video track (embedded cover art doesn't count) get the
+/// audio-only layout, whatever their media type or extension said.
+pub(crate) fn apply_loaded_media_layout(app: &AppHandle, state: &MpvState, tracks: &[MpvTrack]) {
+    let has_video = tracks
+        .iter()
+        .any(|track| track.kind == "video" && !track.albumart);
+    let has_audio = tracks.iter().any(|track| track.kind == "audio");
+
+    if let Err(e) = state.apply_media_layout(!has_video, audio_only_behavior(app), has_audio) {
+        log::warn!("Failed to apply media layout: {}", e);
+    }
+}
+
+/// Set User-Agent/Referer before a load: explicit values win, then saved settings, then mpv defaults
+fn apply_http_identity(
+    app: &AppHandle,
+    state: &MpvState,
+    user_agent: Option<&str>,
+    referrer: Option<&str>,
+) {
+    let settings = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get())
+        .unwrap_or_default();
+
+    let user_agent = user_agent.or(settings.http_user_agent.as_deref());
+    let referrer = referrer.or(settings.http_referrer.as_deref());
+
+    if let Err(e) = state.set_http_identity(user_agent, referrer) {
+        log::warn!("Failed to set HTTP headers: {}", e);
+    }
+}
+
+/// Check a format against the known forceable formats
+fn validate_force_format(format: &str) -> Result<String, String> {
+    let format = format.trim().to_lowercase();
+    if FORCE_FORMATS.contains(&format.as_str()) {
+        Ok(format)
+    } else {
+        Err(format!(
+            "Unknown format {:?} (expected one of: {})",
+            format,
+            FORCE_FORMATS.join(", ")
+        ))
+    }
+}
+
+/// Force the demuxer format for this load, or clear a previously forced one
+fn apply_force_format(state: &MpvState, format: Option<&str>) -> Result<(), String> {
+    let format = match format {
+        Some(format) => Some(validate_force_format(format)?),
+        None => state.force_format(),
+    };
+    if let Some(format) = &format {
+        log::info!("Forcing {} demuxer format", format);
+    }
+
+    state
+        .apply_force_format(format.as_deref())
+        .map_err(|e| format!("Failed to set demuxer format: {}", e))
+}
+
+/// Apply the saved window title template (or the default title)
+pub(crate) fn apply_title_format(app: &AppHandle, state: &MpvState) {
+    let template = app
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.get().title_format)
+        .unwrap_or_else(|| DEFAULT_TITLE.to_string());
+
+    if let Err(e) = state.set_title(&template) {
+        log::warn!("Failed to set window title: {}", e);
+    }
+}
+
+/// Loosely check an mpv property-expansion template: balanced `${...}` and no newlines
+fn validate_title_format(template: &str) -> Result<(), String> {
+    if template.contains(['\n', '\r']) {
+        return Err("Title format can't contain line breaks".to_string());
+    }
+
+    let mut depth = 0usize;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // "$$" is a literal dollar sign
+            '$' if chars.peek() == Some(&'$') => {
+                chars.next();
+            }
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                depth += 1;
+            }
+            '}' if depth > 0 => depth -= 1,
+            _ => {}
+        }
+    }
+
+    if depth > 0 {
+        return Err(format!("Unclosed ${{ in title format: {}", template));
+    }
+    Ok(())
 }
 
 /// Initialize the MPV player (fullscreen with OSC)
 #[tauri::command]
-pub fn init_player(state: State<MpvState>) -> CommandResult<()> {
-    match state.init() {
+pub fn init_player(app: AppHandle, state: State<MpvState>) -> CommandResult<()> {
+    match ensure_player(&app, &state) {
         Ok(_) => CommandResult::ok_empty(),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
+/// Kill mpv processes left running by HubRemote instances that crashed
+///
+/// Returns the processes found; players of running instances are untouched.
+#[tauri::command]
+pub fn cleanup_orphaned_players() -> CommandResult<Vec<OrphanedPlayer>> {
+    match mpv_ipc::cleanup_orphaned_players() {
+        Ok(orphans) => CommandResult::ok(orphans),
+        Err(e) => CommandResult::err(format!("Failed to list processes: {}", e)),
+    }
+}
+
+/// Get the installed mpv version and the features it supports
+#[tauri::command]
+pub fn get_mpv_version(state: State<MpvState>) -> CommandResult<MpvVersionInfo> {
+    match MpvVersionInfo::from_state(&state) {
+        Ok(info) => CommandResult::ok(info),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// List the features and mpv properties this backend supports
+///
+/// Version-gated features are reported unavailable until the player has
+/// been started and its mpv version detected.
+#[tauri::command]
+pub fn get_player_capabilities(state: State<MpvState>) -> CommandResult<PlayerCapabilities> {
+    CommandResult::ok(PlayerCapabilities::from_state(&state))
+}
+
 /// Play a video from URL
 #[tauri::command]
-pub fn play_video(state: State<MpvState>, url: String) -> CommandResult<()> {
+pub fn play_video(app: AppHandle, state: State<MpvState>, url: String) -> CommandResult<()> {
     // Initialize if needed
-    if let Err(e) = state.init() {
+    if let Err(e) = ensure_player(&app, &state) {
         return CommandResult::err(format!("Failed to initialize player: {}", e));
     }
 
+    apply_media_layout(&app, &state, &url, None);
+    apply_http_identity(&app, &state, None, None);
+    apply_title_format(&app, &state);
+    av_sync::apply_saved(&app, &state, &url);
+
     match state.load_file(&url) {
-        Ok(_) => CommandResult::ok_empty(),
+        Ok(_) => {
+            recent::record_play(&app, &url, None);
+            CommandResult::ok_empty()
+        }
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Play a video with options (start position, auth headers)
 #[tauri::command]
-pub fn play_video_with_options(state: State<MpvState>, options: PlayOptions) -> CommandResult<()> {
-    // Initialize if needed
-    if let Err(e) = state.init() {
-        return CommandResult::err(format!("Failed to initialize player: {}", e));
+pub fn play_video_with_options(
+    app: AppHandle,
+    state: State<MpvState>,
+    options: PlayOptions,
+) -> CommandResult<()> {
+    match play_with_options(&app, &state, &options) {
+        Ok(_) => CommandResult::ok_empty(),
+        Err(e) => CommandResult::err(e),
     }
+}
+
+/// Initialize the player if needed and load a file with options.
+/// Returns the start position actually used.
+pub(crate) fn play_with_options(
+    app: &AppHandle,
+    state: &MpvState,
+    options: &PlayOptions,
+) -> Result<Option<f64>, String> {
+    let start_position = match (options.start_position, options.start_timestamp.as_deref()) {
+        (Some(_), Some(_)) => {
+            return Err("Pass either start_position or start_timestamp, not both".to_string())
+        }
+        // Rewind a little before the saved resume point if configured
+        (Some(position), None) => Some(
+            app.try_state::<SettingsState>()
+                .map_or(position, |settings| settings.get().resume_position(position)),
+        ),
+        // Timestamps come from links or user input and are used exactly
+        (None, Some(timestamp)) => Some(parse_timestamp(timestamp)?),
+        (None, None) => None,
+    };
+
+    // Initialize if needed
+    ensure_player(app, state).map_err(|e| format!("Failed to initialize player: {}", e))?;
+
+    apply_media_layout(app, state, &options.url, options.media_type.as_deref());
+    apply_http_identity(
+        app,
+        state,
+        options.user_agent.as_deref(),
+        options.referrer.as_deref(),
+    );
+    apply_title_format(app, state);
+    apply_force_format(state, options.force_format.as_deref())?;
+    source_profiles::apply(app, state, &options.url, options.profile);
+    av_sync::apply_saved(app, state, &options.url);
 
     let headers: Option<Vec<(&str, &str)>> = options.auth_token.as_ref().map(|token| {
         vec![("X-Emby-Token", token.as_str())]
     });
 
-    match state.load_file_with_options(
-        &options.url,
-        options.start_position,
-        headers.as_deref(),
-    ) {
-        Ok(_) => CommandResult::ok_empty(),
+    state
+        .load_file_with_options(
+            &options.url,
+            start_position,
+            headers.as_deref(),
+            options.start_paused,
+        )
+        .map_err(|e| e.to_string())?;
+
+    recent::record_play(app, &options.url, start_position);
+    Ok(start_position)
+}
+
+/// Demuxer format forcing state
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceFormat {
+    /// Format forced for the following loads (None = autodetect)
+    pub format: Option<String>,
+    pub available: &'static [&'static str],
+}
+
+/// Open the following files as `format` instead of autodetecting it
+///
+/// A workaround for files mpv misdetects, e.g. a `.ts` that's raw H.264.
+/// Applies to every load until cleared with None and isn't saved;
+/// `PlayOptions.force_format` overrides it for a single load.
+#[tauri::command]
+pub fn set_force_format(
+    state: State<MpvState>,
+    format: Option<String>,
+) -> CommandResult<ForceFormat> {
+    let format = match format.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+        Some(format) => match validate_force_format(format) {
+            Ok(format) => Some(format),
+            Err(e) => return CommandResult::err(e),
+        },
+        None => None,
+    };
+
+    state.set_force_format(format.clone());
+    CommandResult::ok(ForceFormat {
+        format,
+        available: FORCE_FORMATS,
+    })
+}
+
+/// Get the forced format and the formats that can be forced
+#[tauri::command]
+pub fn get_force_format(state: State<MpvState>) -> CommandResult<ForceFormat> {
+    CommandResult::ok(ForceFormat {
+        format: state.force_format(),
+        available: FORCE_FORMATS,
+    })
+}
+
+/// Video output selection
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoOutputs {
+    /// Saved choice (None = the default, gpu)
+    pub selected: Option<String>,
+    pub available: Vec<VideoOutput>,
+}
+
+/// List the video outputs the installed mpv supports, and the saved choice
+#[tauri::command]
+pub fn get_available_vos(app: AppHandle) -> CommandResult<VideoOutputs> {
+    match mpv_ipc::list_video_outputs() {
+        Ok(available) => CommandResult::ok(VideoOutputs {
+            selected: app
+                .try_state::<SettingsState>()
+                .and_then(|settings| settings.get().video_output),
+            available,
+        }),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Check a video output against what the installed mpv supports
+fn validate_video_output(name: &str) -> Result<(), String> {
+    let available = mpv_ipc::list_video_outputs().map_err(|e| e.to_string())?;
+    if !available.iter().any(|vo| vo.name == name) {
+        return Err(format!("Video output not supported by mpv: {}", name));
+    }
+    Ok(())
+}
+
+/// Check that the running mpv (if any) is new enough for a video output
+fn check_vo_capability(state: &MpvState, name: &str) -> Result<(), String> {
+    let too_old = state.capabilities().is_ok_and(|caps| !caps.gpu_next);
+    if name == "gpu-next" && too_old {
+        return Err("gpu-next needs mpv 0.35 or newer".to_string());
+    }
+    Ok(())
+}
+
+/// Choose the video output driver (None = gpu), e.g. "gpu-next" for better HDR
+///
+/// Checked against what the installed mpv supports. Saved and used from the
+/// next player start; a running player keeps its current output.
+#[tauri::command]
+pub fn set_vo(
+    app: AppHandle,
+    state: State<MpvState>,
+    name: Option<String>,
+) -> CommandResult<Option<String>> {
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let checked = name.as_deref().map(|name| {
+        validate_video_output(name).and_then(|_| check_vo_capability(&state, name))
+    });
+    if let Some(Err(e)) = checked {
+        return CommandResult::err(e);
+    }
+
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available");
+    };
+    if let Err(e) = settings.update(|s| s.video_output = name.clone()) {
+        return CommandResult::err(e);
+    }
+
+    log::info!("Video output set to {}", name.as_deref().unwrap_or(mpv_ipc::DEFAULT_VO));
+    CommandResult::ok(name)
+}
+
+/// Reload the current file at the current position (picks up new sidecars or remuxes)
+#[tauri::command]
+pub fn reload_current(state: State<MpvState>) -> CommandResult<f64> {
+    match state.reload_current() {
+        Ok(position) => CommandResult::ok(position),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Pause video playback
 #[tauri::command]
-pub fn pause_video(state: State<MpvState>) -> CommandResult<()> {
+pub fn pause_video(app: AppHandle, state: State<MpvState>) -> CommandResult<()> {
     match state.pause() {
-        Ok(_) => CommandResult::ok_empty(),
+        Ok(_) => {
+            recent::save_progress(&app);
+            cast::sync_mirror(&app, CastTransport::Pause);
+            CommandResult::ok_empty()
+        }
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Resume video playback
 #[tauri::command]
-pub fn resume_video(state: State<MpvState>) -> CommandResult<()> {
+pub fn resume_video(app: AppHandle, state: State<MpvState>) -> CommandResult<()> {
     match state.play() {
-        Ok(_) => CommandResult::ok_empty(),
+        Ok(_) => {
+            cast::sync_mirror(&app, CastTransport::Unpause);
+            CommandResult::ok_empty()
+        }
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Toggle play/pause
 #[tauri::command]
-pub fn toggle_playback(state: State<MpvState>) -> CommandResult<bool> {
+pub fn toggle_playback(app: AppHandle, state: State<MpvState>) -> CommandResult<bool> {
     match state.toggle_pause() {
-        Ok(is_paused) => CommandResult::ok(is_paused),
+        Ok(is_paused) => {
+            let transport = if is_paused {
+                CastTransport::Pause
+            } else {
+                CastTransport::Unpause
+            };
+            cast::sync_mirror(&app, transport);
+            CommandResult::ok(is_paused)
+        }
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Stop video playback
 #[tauri::command]
-pub fn stop_video(state: State<MpvState>) -> CommandResult<()> {
+pub fn stop_video(app: AppHandle, state: State<MpvState>) -> CommandResult<()> {
+    recent::save_progress(&app);
+    session::clear(&app);
+
     match state.stop() {
-        Ok(_) => CommandResult::ok_empty(),
+        Ok(_) => {
+            cast::sync_mirror(&app, CastTransport::Stop);
+            CommandResult::ok_empty()
+        }
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Seek to a specific position in seconds
 #[tauri::command]
-pub fn seek_video(state: State<MpvState>, position: f64) -> CommandResult<()> {
+pub fn seek_video(app: AppHandle, state: State<MpvState>, position: f64) -> CommandResult<()> {
     match state.seek(position) {
-        Ok(_) => CommandResult::ok_empty(),
+        Ok(_) => {
+            show_seek_osd_if_enabled(&app, &state);
+            cast::sync_mirror(&app, CastTransport::Seek { position });
+            CommandResult::ok_empty()
+        }
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
 /// Seek relative to current position
 #[tauri::command]
-pub fn seek_video_relative(state: State<MpvState>, offset: f64) -> CommandResult<()> {
+pub fn seek_video_relative(
+    app: AppHandle,
+    state: State<MpvState>,
+    offset: f64,
+) -> CommandResult<()> {
     match state.seek_relative(offset) {
+        Ok(_) => {
+            show_seek_osd_if_enabled(&app, &state);
+            // Mirror the resulting absolute position so the target can't drift further
+            if let Ok(position) = state.get_position() {
+                cast::sync_mirror(&app, CastTransport::Seek { position });
+            }
+            CommandResult::ok_empty()
+        }
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Show the OSD progress bar after a remote seek if `seek_osd` is on
+fn show_seek_osd_if_enabled(app: &AppHandle, state: &MpvState) {
+    let enabled = app
+        .try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().seek_osd);
+    if !enabled {
+        return;
+    }
+    // Seeks over IPC don't show mpv's OSD on their own
+    if let Err(e) = state.with_player(|ipc| ipc.show_progress()) {
+        log::debug!("Failed to show seek OSD: {}", e);
+    }
+}
+
+/// Show the progress bar on the player's OSD, seeking to `position` first if given
+///
+/// For scrubbing from the remote: the viewer sees where playback is going.
+#[tauri::command]
+pub fn show_seek_osd(
+    app: AppHandle,
+    state: State<MpvState>,
+    position: Option<f64>,
+) -> CommandResult<()> {
+    if let Some(position) = position {
+        if let Err(e) = state.seek(position) {
+            return CommandResult::err(e.to_string());
+        }
+        cast::sync_mirror(&app, CastTransport::Seek { position });
+    }
+
+    match state.with_player(|ipc| ipc.show_progress()) {
         Ok(_) => CommandResult::ok_empty(),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
+/// Enable/disable showing the OSD progress bar when seeking from the remote
+#[tauri::command]
+pub fn set_seek_osd(app: AppHandle, enabled: bool) -> CommandResult<bool> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available");
+    };
+    match settings.update(|s| s.seek_osd = enabled) {
+        Ok(_) => CommandResult::ok(enabled),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get whether remote seeks show the OSD progress bar
+#[tauri::command]
+pub fn get_seek_osd(app: AppHandle) -> CommandResult<bool> {
+    let enabled = app
+        .try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().seek_osd);
+    CommandResult::ok(enabled)
+}
+
+/// Check if the loaded content is seekable, so the UI can hide the scrubber
+/// for live streams and piped sources
+#[tauri::command]
+pub fn get_seekable(state: State<MpvState>) -> CommandResult<bool> {
+    match state.is_seekable() {
+        Ok(seekable) => CommandResult::ok(seekable),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
 /// Set volume (0-100)
 #[tauri::command]
 pub fn set_volume(state: State<MpvState>, volume: i64) -> CommandResult<()> {
@@ -199,6 +949,26 @@ pub fn get_playback_state(state: State<MpvState>) -> CommandResult<PlaybackState
     }
 }
 
+/// Get the player status: uninitialized, idle, playing, paused, ended or error
+///
+/// Tells "stopped" apart from "never played" and "finished", which
+/// `get_playback_state` can't.
+#[tauri::command]
+pub fn get_player_status(state: State<MpvState>) -> CommandResult<PlayerStatus> {
+    CommandResult::ok(state.status())
+}
+
+/// Get now-playing details: full path, title, file tags and playlist entry
+///
+/// `metadata` is empty for files without tags.
+#[tauri::command]
+pub fn get_current_media(state: State<MpvState>) -> CommandResult<CurrentMedia> {
+    match state.get_current_media() {
+        Ok(media) => CommandResult::ok(media),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
 /// Get current playback position
 #[tauri::command]
 pub fn get_position(state: State<MpvState>) -> CommandResult<f64> {
@@ -217,6 +987,18 @@ pub fn get_duration(state: State<MpvState>) -> CommandResult<f64> {
     }
 }
 
+/// Get position, duration, remaining time and percentage
+///
+/// `remaining` is at the current playback speed. `remaining` and `percent`
+/// are None when the duration is unknown (live streams).
+#[tauri::command]
+pub fn get_progress_detail(state: State<MpvState>) -> CommandResult<ProgressDetail> {
+    match state.get_progress_detail() {
+        Ok(progress) => CommandResult::ok(progress),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
 /// Set audio track by index
 #[tauri::command]
 pub fn set_audio_track(state: State<MpvState>, index: i64) -> CommandResult<()> {
@@ -235,49 +1017,1128 @@ pub fn set_subtitle_track(state: State<MpvState>, index: i64) -> CommandResult<(
     }
 }
 
-/// Set playback speed
+/// Switch to the next audio track, for single-button remotes
+///
+/// Returns the selected track so the UI can show its title/language.
 #[tauri::command]
-pub fn set_playback_speed(state: State<MpvState>, speed: f64) -> CommandResult<()> {
-    match state.set_speed(speed) {
-        Ok(_) => CommandResult::ok_empty(),
+pub fn cycle_audio_track(state: State<MpvState>) -> CommandResult<Option<MpvTrack>> {
+    match state.cycle_audio_track() {
+        Ok(track) => CommandResult::ok(track),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
-/// Destroy the player
+/// Switch to the next subtitle track; after the last one subtitles turn off
+/// (returns None)
 #[tauri::command]
-pub fn destroy_player(state: State<MpvState>) -> CommandResult<()> {
-    state.destroy();
-    CommandResult::ok_empty()
+pub fn cycle_subtitle_track(state: State<MpvState>) -> CommandResult<Option<MpvTrack>> {
+    match state.cycle_subtitle_track() {
+        Ok(track) => CommandResult::ok(track),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
 }
 
-/// Toggle fullscreen mode
+/// Hide or show subtitles without deselecting the track, unlike
+/// `set_subtitle_track(0)`. Returns the resulting visibility.
 #[tauri::command]
-pub fn toggle_fullscreen(state: State<MpvState>) -> CommandResult<()> {
-    match state.toggle_fullscreen() {
-        Ok(_) => CommandResult::ok_empty(),
+pub fn set_subtitle_visibility(state: State<MpvState>, visible: bool) -> CommandResult<bool> {
+    let result = state
+        .set_subtitle_visibility(visible)
+        .and_then(|_| state.get_subtitle_visibility());
+    match result {
+        Ok(visible) => CommandResult::ok(visible),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
-/// Set fullscreen mode
+/// Check if subtitles are shown
 #[tauri::command]
-pub fn set_fullscreen(state: State<MpvState>, fullscreen: bool) -> CommandResult<()> {
+pub fn get_subtitle_visibility(state: State<MpvState>) -> CommandResult<bool> {
+    match state.get_subtitle_visibility() {
+        Ok(visible) => CommandResult::ok(visible),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Set playback speed
+#[tauri::command]
+pub fn set_playback_speed(state: State<MpvState>, speed: f64) -> CommandResult<()> {
+    match state.set_speed(speed) {
+        Ok(_) => CommandResult::ok_empty(),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Destroy the player
+#[tauri::command]
+pub fn destroy_player(app: AppHandle, state: State<MpvState>) -> CommandResult<()> {
+    recent::save_progress(&app);
+    player_monitor::remember_volume(&app, &state);
+    state.destroy();
+    CommandResult::ok_empty()
+}
+
+/// Toggle fullscreen mode
+#[tauri::command]
+pub fn toggle_fullscreen(state: State<MpvState>) -> CommandResult<()> {
+    match state.toggle_fullscreen() {
+        Ok(_) => CommandResult::ok_empty(),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Set fullscreen mode
+#[tauri::command]
+pub fn set_fullscreen(state: State<MpvState>, fullscreen: bool) -> CommandResult<()> {
     match state.set_fullscreen(fullscreen) {
         Ok(_) => CommandResult::ok_empty(),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
-/// Check if player is fullscreen
+/// Check if player is fullscreen
+#[tauri::command]
+pub fn is_fullscreen(state: State<MpvState>) -> CommandResult<bool> {
+    match state.is_fullscreen() {
+        Ok(fs) => CommandResult::ok(fs),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Turn video output off to save power while listening, or back on
+///
+/// `minimize_window` (default false) also minimizes the player window while
+/// audio-only. Returns whether audio-only is now active.
+#[tauri::command]
+pub fn set_audio_only(
+    state: State<MpvState>,
+    enabled: bool,
+    minimize_window: Option<bool>,
+) -> CommandResult<bool> {
+    match state.set_audio_only(enabled, minimize_window.unwrap_or(false)) {
+        Ok(_) => CommandResult::ok(state.is_audio_only()),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Check whether video output is turned off by `set_audio_only`
+#[tauri::command]
+pub fn is_audio_only(state: State<MpvState>) -> CommandResult<bool> {
+    CommandResult::ok(state.is_audio_only())
+}
+
+/// Apply the saved always-on-top flag to the main window
+pub fn apply_always_on_top(app: &AppHandle) {
+    let enabled = app
+        .try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().always_on_top);
+
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.set_always_on_top(enabled) {
+            log::warn!("Failed to set always on top: {}", e);
+        }
+    }
+}
+
+/// Keep the main window and the mpv window above other windows
+///
+/// The mpv window picks the flag up at spawn if the player isn't running yet.
+#[tauri::command]
+pub fn set_always_on_top(
+    app: AppHandle,
+    state: State<MpvState>,
+    enabled: bool,
+) -> CommandResult<bool> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.always_on_top = enabled) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.set_always_on_top(enabled) {
+            return CommandResult::err(format!("Failed to set always on top: {}", e));
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_ontop(enabled) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(enabled)
+}
+
+/// Get whether the windows are kept above other windows
+#[tauri::command]
+pub fn get_always_on_top(app: AppHandle) -> CommandResult<bool> {
+    let enabled = app
+        .try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().always_on_top);
+    CommandResult::ok(enabled)
+}
+
+/// A connected display (physical pixels)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+    /// The main window is currently on this display
+    pub is_current: bool,
+}
+
+/// Monitors have no stable ID; match on name and position
+fn same_monitor(a: &tauri::Monitor, b: &tauri::Monitor) -> bool {
+    a.name() == b.name() && a.position() == b.position()
+}
+
+fn monitor_info(
+    index: usize,
+    monitor: &tauri::Monitor,
+    primary: Option<&tauri::Monitor>,
+    is_current: bool,
+) -> MonitorInfo {
+    MonitorInfo {
+        index,
+        name: monitor.name().cloned(),
+        width: monitor.size().width,
+        height: monitor.size().height,
+        x: monitor.position().x,
+        y: monitor.position().y,
+        scale_factor: monitor.scale_factor(),
+        is_primary: primary.is_some_and(|p| same_monitor(p, monitor)),
+        is_current,
+    }
+}
+
+/// List connected displays in the order `move_window_to_monitor` expects
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> CommandResult<Vec<MonitorInfo>> {
+    let Some(window) = app.get_webview_window("main") else {
+        return CommandResult::err("Main window not found");
+    };
+    let monitors = match window.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => return CommandResult::err(format!("Failed to list monitors: {}", e)),
+    };
+
+    let primary = window.primary_monitor().ok().flatten();
+    let current = window.current_monitor().ok().flatten();
+
+    let list = monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let is_current = current.as_ref().is_some_and(|c| same_monitor(c, monitor));
+            monitor_info(index, monitor, primary.as_ref(), is_current)
+        })
+        .collect();
+    CommandResult::ok(list)
+}
+
+/// Move the main window to the display at `index` (from `list_monitors`),
+/// centred on it
+///
+/// A maximized window is restored, moved and maximized again so it fills the
+/// new display.
+#[tauri::command]
+pub fn move_window_to_monitor(app: AppHandle, index: usize) -> CommandResult<MonitorInfo> {
+    let Some(window) = app.get_webview_window("main") else {
+        return CommandResult::err("Main window not found");
+    };
+    let monitors = match window.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => return CommandResult::err(format!("Failed to list monitors: {}", e)),
+    };
+    let Some(monitor) = monitors.get(index) else {
+        return CommandResult::err(format!(
+            "Invalid monitor index {} ({} connected)",
+            index,
+            monitors.len()
+        ));
+    };
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        let _ = window.unmaximize();
+    }
+
+    // Clamp to the display so an oversized window still starts on it
+    let size = match window.outer_size() {
+        Ok(size) => size,
+        Err(e) => return CommandResult::err(format!("Failed to get window size: {}", e)),
+    };
+    let area = monitor.size();
+    let origin = monitor.position();
+    let x = origin.x + (area.width.saturating_sub(size.width) / 2) as i32;
+    let y = origin.y + (area.height.saturating_sub(size.height) / 2) as i32;
+
+    if let Err(e) = window.set_position(tauri::PhysicalPosition::new(x, y)) {
+        return CommandResult::err(format!("Failed to move window: {}", e));
+    }
+    if maximized {
+        let _ = window.maximize();
+    }
+
+    log::info!("Moved main window to monitor {} ({:?})", index, monitor.name());
+    let primary = window.primary_monitor().ok().flatten();
+    CommandResult::ok(monitor_info(index, monitor, primary.as_ref(), true))
+}
+
+fn validate_screenshot_format(format: &str) -> Result<(), String> {
+    if SCREENSHOT_FORMATS.contains(&format) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unsupported screenshot format '{}' (expected one of: {})",
+            format,
+            SCREENSHOT_FORMATS.join(", ")
+        ))
+    }
+}
+
+/// Set screenshot format (png/jpg/jpeg/webp), output directory and filename template
+#[tauri::command]
+pub fn set_screenshot_options(
+    app: AppHandle,
+    state: State<MpvState>,
+    format: String,
+    directory: Option<String>,
+    template: Option<String>,
+) -> CommandResult<ScreenshotOptions> {
+    let format = format.trim().to_lowercase();
+    if let Err(e) = validate_screenshot_format(&format) {
+        return CommandResult::err(e);
+    }
+
+    let options = ScreenshotOptions {
+        format,
+        directory: directory.filter(|d| !d.trim().is_empty()),
+        template: template.filter(|t| !t.trim().is_empty()),
+    };
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.screenshot = options.clone()) {
+            return CommandResult::err(e);
+        }
+    }
+
+    // Apply now if mpv is running, otherwise on next init
+    if state.is_initialized() {
+        if let Err(e) = state.set_screenshot_options(&options) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(options)
+}
+
+/// Response for `set_osc_autohide`
+#[derive(Debug, Serialize)]
+pub struct OscState {
+    #[serde(flatten)]
+    pub settings: OscSettings,
+    /// False if the change needs a player restart (OSC loading is decided at spawn)
+    pub applied: bool,
+}
+
+/// Enable OSC auto-hide with the given delay, or disable mpv's OSC entirely
+#[tauri::command]
+pub fn set_osc_autohide(
+    app: AppHandle,
+    state: State<MpvState>,
+    enabled: bool,
+    delay_ms: Option<u32>,
+) -> CommandResult<OscState> {
+    let settings = OscSettings {
+        enabled,
+        hide_delay_ms: delay_ms.unwrap_or_else(|| OscSettings::default().hide_delay_ms),
+    };
+
+    if let Some(app_settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = app_settings.update(|s| s.osc = settings.clone()) {
+            return CommandResult::err(e);
+        }
+    }
+
+    let applied = if state.is_initialized() {
+        match state.set_osc_autohide(settings.enabled, settings.hide_delay_ms) {
+            Ok(applied) => applied,
+            Err(e) => return CommandResult::err(e.to_string()),
+        }
+    } else {
+        true
+    };
+
+    CommandResult::ok(OscState { settings, applied })
+}
+
+/// Set what happens at the end of a file: "yes" (stay on last frame), "no" (go idle) or "always"
+#[tauri::command]
+pub fn set_keep_open(
+    app: AppHandle,
+    state: State<MpvState>,
+    mode: KeepOpen,
+) -> CommandResult<KeepOpen> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.keep_open = mode) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_keep_open(mode) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(mode)
+}
+
+/// Highest mpv `osd-level` (status line always shown)
+const MAX_OSD_LEVEL: u8 = 3;
+
+/// Allowed `osd-duration` range in milliseconds
+const OSD_DURATION_RANGE_MS: std::ops::RangeInclusive<u32> = 100..=30_000;
+
+fn validate_osd_level(level: u8) -> Result<(), String> {
+    if level > MAX_OSD_LEVEL {
+        return Err(format!("Invalid OSD level {} (expected 0-{})", level, MAX_OSD_LEVEL));
+    }
+    Ok(())
+}
+
+fn validate_osd_duration(ms: u32) -> Result<(), String> {
+    if !OSD_DURATION_RANGE_MS.contains(&ms) {
+        return Err(format!(
+            "Invalid OSD duration {}ms (expected {}-{}ms)",
+            ms,
+            OSD_DURATION_RANGE_MS.start(),
+            OSD_DURATION_RANGE_MS.end()
+        ));
+    }
+    Ok(())
+}
+
+/// OSD level and duration in effect
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OsdBehavior {
+    pub level: u8,
+    pub duration_ms: u32,
+}
+
+/// Set how much on-screen info mpv shows for every action: 0 (none),
+/// 1 (messages and seek bar, mpv's default), 2 (plus time on seek) or
+/// 3 (status line always shown)
+///
+/// Saved for future spawns and applied to a running player.
+#[tauri::command]
+pub fn set_osd_level(app: AppHandle, state: State<MpvState>, level: u8) -> CommandResult<u8> {
+    if let Err(e) = validate_osd_level(level) {
+        return CommandResult::err(e);
+    }
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.osd_level = Some(level)) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_osd_level(level) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(level)
+}
+
+/// Set how long OSD messages and the seek bar stay on screen, e.g. longer
+/// for a TV across the room
+///
+/// Saved for future spawns and applied to a running player.
+#[tauri::command]
+pub fn set_osd_duration(app: AppHandle, state: State<MpvState>, ms: u32) -> CommandResult<u32> {
+    if let Err(e) = validate_osd_duration(ms) {
+        return CommandResult::err(e);
+    }
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.osd_duration_ms = Some(ms)) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_osd_duration(ms) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(ms)
+}
+
+/// Get the saved OSD level and duration
+#[tauri::command]
+pub fn get_osd_behavior(app: AppHandle) -> CommandResult<OsdBehavior> {
+    let settings = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get())
+        .unwrap_or_default();
+    CommandResult::ok(OsdBehavior {
+        level: settings.osd_level.unwrap_or(DEFAULT_OSD_LEVEL),
+        duration_ms: settings.osd_duration_ms.unwrap_or(DEFAULT_OSD_DURATION_MS),
+    })
+}
+
+/// Set the mpv window/taskbar title template using mpv property expansion,
+/// e.g. "${media-title} [${percent-pos}%]". An empty template restores the default.
+#[tauri::command]
+pub fn set_title_format(
+    app: AppHandle,
+    state: State<MpvState>,
+    template: String,
+) -> CommandResult<Option<String>> {
+    let template = Some(template.trim().to_string()).filter(|t| !t.is_empty());
+    if let Some(template) = template.as_deref() {
+        if let Err(e) = validate_title_format(template) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.title_format = template.clone()) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        apply_title_format(&app, &state);
+    }
+
+    CommandResult::ok(template)
+}
+
+/// mpv's default channel layout, used when no layout is saved
+const DEFAULT_AUDIO_CHANNELS: &str = "auto-safe";
+
+/// Layout used by the downmix preset
+const DOWNMIX_AUDIO_CHANNELS: &str = "stereo";
+
+fn validate_audio_channels(layout: &str) -> Result<(), String> {
+    let valid = layout
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | ',' | '-' | '_' | '(' | ')'));
+    if !valid {
+        return Err(format!("Invalid audio channel layout: {}", layout));
+    }
+    Ok(())
+}
+
+/// Save an `audio-channels` layout (None = mpv default) and apply it to the running player
+fn update_audio_channels(
+    app: &AppHandle,
+    state: &MpvState,
+    layout: Option<String>,
+) -> Result<String, String> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        settings.update(|s| s.audio_channels = layout.clone())?;
+    }
+
+    let layout = layout.unwrap_or_else(|| DEFAULT_AUDIO_CHANNELS.to_string());
+    if state.is_initialized() {
+        state.set_audio_channels(&layout).map_err(|e| e.to_string())?;
+    }
+    Ok(layout)
+}
+
+/// Set the audio channel layout for local mpv playback, e.g. "stereo", "5.1" or "auto"
+///
+/// Only affects mpv; streams served to a cast target are sent as-is.
+/// An empty layout restores mpv's default.
+#[tauri::command]
+pub fn set_audio_channels(
+    app: AppHandle,
+    state: State<MpvState>,
+    layout: String,
+) -> CommandResult<String> {
+    let layout = layout.trim();
+    if let Err(e) = validate_audio_channels(layout) {
+        return CommandResult::err(e);
+    }
+
+    let layout = (!layout.is_empty()).then(|| layout.to_string());
+    match update_audio_channels(&app, &state, layout) {
+        Ok(layout) => CommandResult::ok(layout),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get the saved audio channel layout (mpv's default if none is saved)
+#[tauri::command]
+pub fn get_audio_channels(app: AppHandle) -> CommandResult<String> {
+    let layout = app
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.get().audio_channels)
+        .unwrap_or_else(|| DEFAULT_AUDIO_CHANNELS.to_string());
+    CommandResult::ok(layout)
+}
+
+/// Enable/disable the stereo downmix preset for surround content on stereo outputs
+#[tauri::command]
+pub fn set_audio_downmix(
+    app: AppHandle,
+    state: State<MpvState>,
+    enabled: bool,
+) -> CommandResult<String> {
+    let layout = enabled.then(|| DOWNMIX_AUDIO_CHANNELS.to_string());
+    match update_audio_channels(&app, &state, layout) {
+        Ok(layout) => CommandResult::ok(layout),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Update the saved User-Agent/Referer and apply them to the running player
+fn update_http_identity<F>(app: &AppHandle, state: &MpvState, f: F) -> Result<(), String>
+where
+    F: FnOnce(&mut AppSettings),
+{
+    let settings = match app.try_state::<SettingsState>() {
+        Some(settings) => settings.update(f)?,
+        None => return Err("Settings not available".to_string()),
+    };
+
+    if state.is_initialized() {
+        state
+            .set_http_identity(
+                settings.http_user_agent.as_deref(),
+                settings.http_referrer.as_deref(),
+            )
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Set the HTTP User-Agent mpv sends for network streams (empty = mpv default)
+#[tauri::command]
+pub fn set_user_agent(
+    app: AppHandle,
+    state: State<MpvState>,
+    user_agent: String,
+) -> CommandResult<Option<String>> {
+    let user_agent = Some(user_agent.trim().to_string()).filter(|ua| !ua.is_empty());
+    let value = user_agent.clone();
+
+    match update_http_identity(&app, &state, |s| s.http_user_agent = value) {
+        Ok(()) => CommandResult::ok(user_agent),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Set the HTTP Referer mpv sends for network streams (empty = none)
+#[tauri::command]
+pub fn set_referrer(
+    app: AppHandle,
+    state: State<MpvState>,
+    referrer: String,
+) -> CommandResult<Option<String>> {
+    let referrer = Some(referrer.trim().to_string()).filter(|r| !r.is_empty());
+    let value = referrer.clone();
+
+    match update_http_identity(&app, &state, |s| s.http_referrer = value) {
+        Ok(()) => CommandResult::ok(referrer),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Take a screenshot of the current frame using the configured options
+#[tauri::command]
+pub fn take_screenshot(state: State<MpvState>) -> CommandResult<()> {
+    match state.take_screenshot() {
+        Ok(_) => CommandResult::ok_empty(),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Set the rendering quality profile (scalers, debanding) and save it
+#[tauri::command]
+pub fn set_quality_profile(
+    app: AppHandle,
+    state: State<MpvState>,
+    profile: QualityProfile,
+) -> CommandResult<QualityProfile> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.quality_profile = Some(profile)) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_quality_profile(profile) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(profile)
+}
+
+/// Get the saved quality profile (None = mpv defaults)
+#[tauri::command]
+pub fn get_quality_profile(app: AppHandle) -> CommandResult<Option<QualityProfile>> {
+    let profile = app
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.get().quality_profile);
+    CommandResult::ok(profile)
+}
+
+/// Set how frames are timed to the display and save it
+///
+/// `displayResample` removes 24fps judder on displays that can switch to (or
+/// are close to) a multiple of the film rate; `interpolateMotion` also blends
+/// frames for displays that can't.
+#[tauri::command]
+pub fn set_motion_smoothing(
+    app: AppHandle,
+    state: State<MpvState>,
+    mode: MotionSmoothing,
+) -> CommandResult<MotionSmoothing> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.motion_smoothing = mode) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_motion_smoothing(mode) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(mode)
+}
+
+/// Set the deblock/denoise level for low-quality sources and save it
+///
+/// `light` only denoises; `medium` also deblocks. Filtering runs on the CPU,
+/// so leave it off for good sources.
+#[tauri::command]
+pub fn set_source_cleanup(
+    app: AppHandle,
+    state: State<MpvState>,
+    level: SourceCleanup,
+) -> CommandResult<SourceCleanup> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.source_cleanup = level) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_source_cleanup(level) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(level)
+}
+
+/// Subtitle size applied by `set_subtitle_scale_for_viewing`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleScale {
+    pub distance: ViewingDistance,
+    pub match_resolution: bool,
+    /// `sub-scale` in effect (None until the player is running)
+    pub sub_scale: Option<f64>,
+    pub font_size: u32,
+}
+
+/// Size subtitles for how far away the viewer sits (near, normal or far)
+///
+/// `match_resolution` scales the preset by the output height relative to
+/// 1080p, so subtitles on a 4K TV grow with the picture. Saved across
+/// sessions.
+#[tauri::command]
+pub fn set_subtitle_scale_for_viewing(
+    app: AppHandle,
+    state: State<MpvState>,
+    distance: ViewingDistance,
+    match_resolution: Option<bool>,
+) -> CommandResult<SubtitleScale> {
+    let viewing = SubtitleViewing {
+        distance,
+        match_resolution: match_resolution.unwrap_or(false),
+    };
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.subtitle_viewing = Some(viewing)) {
+            return CommandResult::err(e);
+        }
+    }
+
+    let sub_scale = if state.is_initialized() {
+        match state.set_subtitle_viewing(viewing) {
+            Ok(scale) => Some(scale),
+            Err(e) => return CommandResult::err(e.to_string()),
+        }
+    } else {
+        None
+    };
+
+    CommandResult::ok(SubtitleScale {
+        distance,
+        match_resolution: viewing.match_resolution,
+        sub_scale,
+        font_size: distance.sub_size().1,
+    })
+}
+
+/// Compress the audio's dynamic range so dialog stays audible at low volume
+/// without loud scenes blasting. Saved across sessions; returns the new state.
+#[tauri::command]
+pub fn set_night_mode(
+    app: AppHandle,
+    state: State<MpvState>,
+    enabled: bool,
+) -> CommandResult<bool> {
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.night_mode = enabled) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_night_mode(enabled) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(enabled)
+}
+
+/// Switch the current video to software decoding
+///
+/// A one-click fix for green frames or stutter from the GPU decoder, without
+/// restarting mpv. Shows an OSD message and emits `hwdec-changed`; call
+/// `confirm_software_decode` once the user says whether it helped.
+#[tauri::command]
+pub fn try_software_decode_fallback(
+    app: AppHandle,
+    state: State<MpvState>,
+) -> CommandResult<HwdecFallback> {
+    let fallback = match state.software_decode_fallback() {
+        Ok(fallback) => fallback,
+        Err(e) => return CommandResult::err(e.to_string()),
+    };
+
+    log::info!(
+        "Switched to software decoding (was {}, codec {:?})",
+        fallback.previous_decoder,
+        fallback.codec
+    );
+    let _ = state.with_player(|ipc| ipc.show_text("Hardware decoding off", 2000));
+    let _ = app.emit("hwdec-changed", &fallback);
+    CommandResult::ok(fallback)
+}
+
+/// Record whether software decoding fixed playback of a codec
+///
+/// If it helped, the codec is always software-decoded from now on; if not,
+/// it's forgotten. Either way hardware decoding is turned back on for the
+/// other codecs. Returns the codecs that are software-decoded.
+#[tauri::command]
+pub fn confirm_software_decode(
+    app: AppHandle,
+    state: State<MpvState>,
+    codec: String,
+    helped: bool,
+) -> CommandResult<Vec<String>> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available");
+    };
+
+    let codec = codec.trim().to_lowercase();
+    let updated = settings.update(|s| {
+        s.software_decode_codecs.retain(|c| *c != codec);
+        if helped && !codec.is_empty() {
+            s.software_decode_codecs.push(codec.clone());
+        }
+    });
+    let codecs = match updated {
+        Ok(settings) => settings.software_decode_codecs,
+        Err(e) => return CommandResult::err(e),
+    };
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_software_decode_codecs(&codecs) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(codecs)
+}
+
+fn validate_tone_mapping(mode: &str) -> Result<(), String> {
+    if !TONE_MAPPING_MODES.contains(&mode) {
+        return Err(format!(
+            "Invalid tone mapping mode: {} (expected one of {})",
+            mode,
+            TONE_MAPPING_MODES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Set how mpv maps HDR video to an SDR display, e.g. "hable" or "bt.2390"
+///
+/// For local playback only; cast streams aren't tone-mapped. "auto" restores
+/// mpv's default. Saved across sessions; returns the new mode.
+#[tauri::command]
+pub fn set_tone_mapping(
+    app: AppHandle,
+    state: State<MpvState>,
+    mode: String,
+) -> CommandResult<String> {
+    let mode = mode.trim().to_lowercase();
+    if let Err(e) = validate_tone_mapping(&mode) {
+        return CommandResult::err(e);
+    }
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        let saved = (mode != "auto").then(|| mode.clone());
+        if let Err(e) = settings.update(|s| s.tone_mapping = saved) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_tone_mapping(&mode) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(mode)
+}
+
+/// Where mpv keeps its demuxer cache
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MpvCacheLocation {
+    /// None = mpv's default location
+    pub dir: Option<String>,
+    pub on_disk: bool,
+}
+
+/// Check that a cache directory exists (creating it if needed) and is writable
+fn validate_cache_dir(dir: &str) -> Result<(), String> {
+    let path = PathBuf::from(dir);
+    std::fs::create_dir_all(&path)
+        .map_err(|e| format!("Failed to create cache directory {}: {}", dir, e))?;
+
+    let probe = path.join(".hubremote-write-test");
+    std::fs::write(&probe, b"")
+        .map_err(|e| format!("Cache directory {} isn't writable: {}", dir, e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Move mpv's demuxer cache to another directory (e.g. a fast scratch disk)
+/// and choose whether it's kept on disk instead of in memory
+///
+/// Saved for future spawns; a running player picks it up from the next file.
+/// An empty `dir` restores mpv's default location.
+#[tauri::command]
+pub fn set_mpv_cache_location(
+    app: AppHandle,
+    state: State<MpvState>,
+    dir: Option<String>,
+    on_disk: bool,
+) -> CommandResult<MpvCacheLocation> {
+    let dir = dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty());
+    if let Some(dir) = dir.as_deref() {
+        if let Err(e) = validate_cache_dir(dir) {
+            return CommandResult::err(e);
+        }
+    }
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        let result = settings.update(|s| {
+            s.mpv_cache_dir = dir.clone();
+            s.mpv_cache_on_disk = on_disk;
+        });
+        if let Err(e) = result {
+            return CommandResult::err(e);
+        }
+    }
+
+    if state.is_initialized() {
+        if let Err(e) = state.set_cache_location(dir.as_deref(), on_disk) {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(MpvCacheLocation { dir, on_disk })
+}
+
+/// Get the saved mpv cache location
+#[tauri::command]
+pub fn get_mpv_cache_location(app: AppHandle) -> CommandResult<MpvCacheLocation> {
+    let settings = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get())
+        .unwrap_or_default();
+    CommandResult::ok(MpvCacheLocation {
+        dir: settings.mpv_cache_dir,
+        on_disk: settings.mpv_cache_on_disk,
+    })
+}
+
+/// Get whether night mode is on
+#[tauri::command]
+pub fn get_night_mode(app: AppHandle) -> CommandResult<bool> {
+    let enabled = app
+        .try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().night_mode);
+    CommandResult::ok(enabled)
+}
+
+/// Get the saved motion smoothing mode
+#[tauri::command]
+pub fn get_motion_smoothing(app: AppHandle) -> CommandResult<MotionSmoothing> {
+    let mode = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get().motion_smoothing)
+        .unwrap_or_default();
+    CommandResult::ok(mode)
+}
+
+/// Get the saved deblock/denoise level
+#[tauri::command]
+pub fn get_source_cleanup(app: AppHandle) -> CommandResult<SourceCleanup> {
+    let level = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get().source_cleanup)
+        .unwrap_or_default();
+    CommandResult::ok(level)
+}
+
+/// Toggle mpv's on-screen stats overlay
+#[tauri::command]
+pub fn toggle_stats_overlay(state: State<MpvState>) -> CommandResult<()> {
+    match state.toggle_stats() {
+        Ok(_) => CommandResult::ok_empty(),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Show a page of mpv's stats overlay (1 = overview, 2 = frame timings,
+/// 3 = input cache, 4 = key bindings)
 #[tauri::command]
-pub fn is_fullscreen(state: State<MpvState>) -> CommandResult<bool> {
-    match state.is_fullscreen() {
-        Ok(fs) => CommandResult::ok(fs),
+pub fn show_stats_page(state: State<MpvState>, page: u8) -> CommandResult<()> {
+    if !(1..=4).contains(&page) {
+        return CommandResult::err(format!("Invalid stats page: {} (expected 1-4)", page));
+    }
+
+    match state.show_stats_page(page) {
+        Ok(_) => CommandResult::ok_empty(),
         Err(e) => CommandResult::err(e.to_string()),
     }
 }
 
+/// Default number of raw IPC lines kept by the event log
+const DEFAULT_EVENT_LOG_CAPACITY: usize = 200;
+
+/// Most raw IPC lines the event log can keep
+const MAX_EVENT_LOG_CAPACITY: usize = 5000;
+
+/// Record the raw JSON lines exchanged with mpv for bug reports (off by default)
+///
+/// Keeps the last `capacity` lines (default 200). Disabling clears the log.
+#[tauri::command]
+pub fn set_mpv_event_logging(enabled: bool, capacity: Option<usize>) -> CommandResult<usize> {
+    let capacity = capacity.unwrap_or(DEFAULT_EVENT_LOG_CAPACITY);
+    if enabled && !(1..=MAX_EVENT_LOG_CAPACITY).contains(&capacity) {
+        return CommandResult::err(format!(
+            "Invalid event log capacity: {} (expected 1-{})",
+            capacity, MAX_EVENT_LOG_CAPACITY
+        ));
+    }
+
+    mpv_ipc::set_event_logging(enabled, capacity);
+    CommandResult::ok(if enabled { capacity } else { 0 })
+}
+
+/// Get the recorded raw mpv IPC lines (commands, responses and events), oldest first
+///
+/// There is no separate reader: events are recorded as they're read while
+/// waiting for a command's response.
+#[tauri::command]
+pub fn get_mpv_event_log() -> CommandResult<Vec<IpcLogEntry>> {
+    CommandResult::ok(mpv_ipc::event_log())
+}
+
+// ============================================
+// Panic / Privacy
+// ============================================
+
+/// What `panic_hide` managed to do (every step is best-effort)
+#[derive(Debug, Clone, Serialize)]
+pub struct PanicResult {
+    pub window_hidden: bool,
+    pub player_hidden: bool,
+    pub paused: bool,
+    pub muted: bool,
+    pub cast_stopped: bool,
+}
+
+/// Hide everything, pause and mute, optionally stopping the cast session
+pub fn activate_panic(app: &AppHandle, stop_cast: bool) -> PanicResult {
+    let window_hidden = app
+        .get_webview_window("main")
+        .is_some_and(|window| window.hide().is_ok());
+
+    let (player_hidden, paused, muted) = match app.try_state::<MpvState>() {
+        Some(state) => (
+            state.set_minimized(true).is_ok(),
+            state.pause().is_ok(),
+            state.set_mute(true).is_ok(),
+        ),
+        None => (false, false, false),
+    };
+
+    let cast_stopped = stop_cast && cast::end_session(app).is_some();
+
+    let result = PanicResult {
+        window_hidden,
+        player_hidden,
+        paused,
+        muted,
+        cast_stopped,
+    };
+
+    log::info!("Panic activated: {:?}", result);
+    let _ = app.emit("panic-activated", &result);
+    result
+}
+
+/// Instantly hide the app and player, pause and mute ("boss key")
+#[tauri::command]
+pub fn panic_hide(app: AppHandle, stop_cast: Option<bool>) -> CommandResult<PanicResult> {
+    CommandResult::ok(activate_panic(&app, stop_cast.unwrap_or(false)))
+}
+
 // ============================================
 // Streaming Server Commands
 // ============================================
@@ -303,15 +2164,27 @@ pub struct StreamInfo {
     pub stream_id: String,
     pub stream_url: String,
     pub server_url: String,
+    /// Size of the streamed content in bytes
+    pub file_size: u64,
 }
 
 /// Start streaming server
+///
+/// Binds all interfaces by default so TVs on the LAN can connect. Pass
+/// `loopback_only` to bind 127.0.0.1 only, and `cors_allowed_origins` to
+/// replace the wildcard CORS policy with specific origins.
 #[tauri::command]
 pub async fn start_stream_server(
     state: State<'_, StreamingState>,
     port: Option<u16>,
+    loopback_only: Option<bool>,
+    cors_allowed_origins: Option<Vec<String>>,
 ) -> Result<String, String> {
-    let port = port.unwrap_or(8765);
+    let options = ServerOptions {
+        port: port.unwrap_or(streaming::DEFAULT_PORT),
+        loopback_only: loopback_only.unwrap_or(false),
+        cors_allowed_origins: cors_allowed_origins.filter(|origins| !origins.is_empty()),
+    };
 
     // Check if already running
     {
@@ -324,16 +2197,295 @@ pub async fn start_stream_server(
     }
 
     // Start server
-    let result = {
+    let mut server = state.0.lock();
+    server.start(options).map_err(|e| e.to_string())?;
+    server
+        .get_url()
+        .ok_or_else(|| "Failed to get server URL".to_string())
+}
+
+/// Event payload for `stream-port-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPortChanged {
+    pub old_port: u16,
+    pub new_port: u16,
+    /// New base URL; stream URLs keep their paths under it
+    pub server_url: String,
+}
+
+/// Move the streaming server to another port without losing registered streams
+///
+/// Useful to resolve a port conflict mid-session. Emits `stream-port-changed`.
+#[tauri::command]
+pub fn change_stream_port(
+    app: AppHandle,
+    state: State<StreamingState>,
+    new_port: u16,
+) -> CommandResult<StreamPortChanged> {
+    let mut server = state.0.lock();
+    let old_port = server.port();
+
+    let new_port = match server.change_port(new_port) {
+        Ok(port) => port,
+        Err(e) => return CommandResult::err(e.to_string()),
+    };
+    let Some(server_url) = server.get_url() else {
+        return CommandResult::err("Failed to get server URL");
+    };
+    drop(server);
+
+    let event = StreamPortChanged {
+        old_port,
+        new_port,
+        server_url,
+    };
+    if old_port != new_port {
+        log::info!("Streaming server moved from port {} to {}", old_port, new_port);
+        let _ = app.emit("stream-port-changed", &event);
+    }
+    CommandResult::ok(event)
+}
+
+/// Event payload for `network-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkChanged {
+    /// IP in the current stream URLs
+    pub old_ip: Option<String>,
+    pub new_ip: String,
+}
+
+/// Emit `network-changed` once per new LAN IP while the streaming server runs
+///
+/// `last_notified` holds the IP already reported, so a poller can call this
+/// repeatedly without repeating the event.
+pub fn check_network_change(app: &AppHandle, last_notified: &mut Option<String>) {
+    let Some(state) = app.try_state::<StreamingState>() else {
+        return;
+    };
+    let server = state.0.lock();
+    let Some(new_ip) = server.detect_ip_change() else {
+        *last_notified = None;
+        return;
+    };
+    if last_notified.as_deref() == Some(new_ip.as_str()) {
+        return;
+    }
+
+    log::info!("LAN IP changed from {:?} to {}", server.local_ip(), new_ip);
+    let event = NetworkChanged {
+        old_ip: server.local_ip().map(str::to_string),
+        new_ip: new_ip.clone(),
+    };
+    drop(server);
+    let _ = app.emit("network-changed", &event);
+    *last_notified = Some(new_ip);
+}
+
+/// A stream's URL before and after `rebind_stream_server`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamUrlChange {
+    pub stream_id: String,
+    pub old_url: Option<String>,
+    pub new_url: Option<String>,
+}
+
+/// Result of `rebind_stream_server`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebindResult {
+    pub server_url: String,
+    pub streams: Vec<StreamUrlChange>,
+}
+
+/// Restart the streaming server on the current LAN IP after a network switch
+///
+/// Stream IDs and the port are kept; cast targets need the new URLs returned
+/// here to resume.
+#[tauri::command]
+pub fn rebind_stream_server(state: State<StreamingState>) -> CommandResult<RebindResult> {
+    let (old_urls, options) = {
         let mut server = state.0.lock();
-        // We need to run the async start in the current runtime
-        tokio::runtime::Handle::current().block_on(server.start(port))
+        let old_urls = server.list_streams();
+        match server.begin_rebind() {
+            Ok(options) => (old_urls, options),
+            Err(e) => return CommandResult::err(e.to_string()),
+        }
     };
 
-    match result {
-        Ok((ip, port)) => Ok(format!("http://{}:{}", ip, port)),
-        Err(e) => Err(e.to_string()),
+    // Wait for the old listener without holding the lock, so stream
+    // registration and status calls aren't stalled meanwhile
+    StreamingServer::wait_for_port(&options);
+
+    let mut server = state.0.lock();
+    if let Err(e) = server.finish_rebind(options) {
+        return CommandResult::err(e.to_string());
+    }
+    let Some(server_url) = server.get_url() else {
+        return CommandResult::err("Failed to get server URL");
+    };
+
+    let streams = old_urls
+        .into_iter()
+        .map(|(summary, old_url)| StreamUrlChange {
+            new_url: server.get_stream_url(&summary.stream_id, Some(&summary.filename)),
+            stream_id: summary.stream_id,
+            old_url,
+        })
+        .collect();
+
+    log::info!("Streaming server rebound at {}", server_url);
+    CommandResult::ok(RebindResult {
+        server_url,
+        streams,
+    })
+}
+
+/// Start the streaming server on the preferred port if auto-start is enabled
+///
+/// Called at launch; emits `stream-server-ready` with the base URL on success.
+/// Failures are only logged so they never block startup.
+pub fn auto_start_stream_server(app: &AppHandle) {
+    let Some(settings) = app.try_state::<SettingsState>().map(|s| s.get()) else {
+        return;
+    };
+    if !settings.auto_start_streaming {
+        return;
+    }
+
+    match ensure_stream_server(app) {
+        Ok(url) => {
+            log::info!("Streaming server auto-started at {}", url);
+            let _ = app.emit("stream-server-ready", url);
+        }
+        Err(e) => log::warn!("Failed to auto-start streaming server: {}", e),
+    }
+}
+
+/// Start the streaming server on the preferred port unless it's running. Returns the base URL.
+pub(crate) fn ensure_stream_server(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<StreamingState>();
+    let mut server = state.0.lock();
+    if let Some(url) = server.get_url().filter(|_| server.is_running()) {
+        return Ok(url);
+    }
+
+    let port = app
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.get().streaming_port)
+        .unwrap_or(streaming::DEFAULT_PORT);
+    let options = ServerOptions {
+        port,
+        ..ServerOptions::default()
+    };
+
+    server.start(options).map_err(|e| e.to_string())?;
+    server
+        .get_url()
+        .ok_or_else(|| "Failed to get server URL".to_string())
+}
+
+/// Apply the saved advertised hostname to the streaming server
+pub fn apply_stream_hostname(app: &AppHandle) {
+    let hostname = app
+        .try_state::<SettingsState>()
+        .and_then(|settings| settings.get().stream_hostname);
+
+    if let Err(e) = app.state::<StreamingState>().0.lock().set_hostname(hostname) {
+        log::warn!("Ignoring saved stream hostname: {}", e);
+    }
+}
+
+/// Restrict the streaming server to the saved client IP ranges
+pub fn apply_stream_ip_allowlist(app: &AppHandle) {
+    let ranges = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get().stream_ip_allowlist)
+        .unwrap_or_default();
+
+    let ranges = ranges
+        .iter()
+        .filter_map(|range| match IpRange::parse(range) {
+            Ok(range) => Some(range),
+            Err(e) => {
+                log::warn!("Ignoring saved stream IP range: {}", e);
+                None
+            }
+        })
+        .collect();
+    app.state::<StreamingState>().0.lock().set_ip_allowlist(ranges);
+}
+
+/// Only serve streams to clients in these IP ranges, e.g. ["192.168.1.0/24"]
+/// or a single TV's address
+///
+/// Other clients get 403; this machine is always allowed. An empty list
+/// allows any client. Takes effect immediately. Returns the normalized ranges.
+#[tauri::command]
+pub fn set_stream_ip_allowlist(
+    app: AppHandle,
+    state: State<StreamingState>,
+    cidrs: Vec<String>,
+) -> CommandResult<Vec<String>> {
+    let ranges = match cidrs
+        .iter()
+        .filter(|cidr| !cidr.trim().is_empty())
+        .map(|cidr| IpRange::parse(cidr))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(ranges) => ranges,
+        Err(e) => return CommandResult::err(e.to_string()),
+    };
+    let normalized: Vec<String> = ranges.iter().map(IpRange::to_string).collect();
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.stream_ip_allowlist = normalized.clone()) {
+            return CommandResult::err(e);
+        }
     }
+
+    state.0.lock().set_ip_allowlist(ranges);
+    log::info!("Stream IP allowlist set to {:?}", normalized);
+    CommandResult::ok(normalized)
+}
+
+/// Get the client IP ranges allowed to fetch streams (empty = any client)
+#[tauri::command]
+pub fn get_stream_ip_allowlist(app: AppHandle) -> CommandResult<Vec<String>> {
+    let ranges = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get().stream_ip_allowlist)
+        .unwrap_or_default();
+    CommandResult::ok(ranges)
+}
+
+/// Advertise a hostname (e.g. "desktop.local") in stream URLs instead of the LAN IP
+///
+/// Pass None or an empty string to go back to the IP. Returns the current server URL.
+#[tauri::command]
+pub fn set_stream_hostname(
+    app: AppHandle,
+    state: State<StreamingState>,
+    hostname: Option<String>,
+) -> CommandResult<Option<String>> {
+    let hostname = hostname
+        .map(|h| h.trim().trim_end_matches('.').to_string())
+        .filter(|h| !h.is_empty());
+
+    let mut server = state.0.lock();
+    if let Err(e) = server.set_hostname(hostname.clone()) {
+        return CommandResult::err(e.to_string());
+    }
+
+    if let Some(settings) = app.try_state::<SettingsState>() {
+        if let Err(e) = settings.update(|s| s.stream_hostname = hostname) {
+            return CommandResult::err(e);
+        }
+    }
+
+    CommandResult::ok(server.get_url())
 }
 
 /// Stop the streaming server
@@ -359,10 +2511,78 @@ pub fn get_stream_server_url(state: State<StreamingState>) -> CommandResult<Opti
 }
 
 /// Register a file for streaming and get the stream URL
+///
+/// The read buffer size is picked from the file type (small for audio, large
+/// for video); `chunk_size` overrides it in bytes.
 #[tauri::command]
 pub fn create_stream(
     state: State<StreamingState>,
     file_path: String,
+    chunk_size: Option<usize>,
+) -> Result<StreamInfo, String> {
+    register_file_stream(&state, &file_path, chunk_size)
+}
+
+/// A file `create_stream_batch` couldn't register
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedStream {
+    pub path: String,
+    pub error: String,
+}
+
+/// Streams registered by `create_stream_batch`, in the order given
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamBatch {
+    /// Identifies the queue for `remove_stream_queue` (None if nothing was registered)
+    pub queue_id: Option<String>,
+    pub streams: Vec<StreamInfo>,
+    pub skipped: Vec<SkippedStream>,
+}
+
+/// Register several files at once, e.g. a season to cast as a queue
+///
+/// Missing or unreadable files are skipped and reported instead of failing
+/// the batch.
+#[tauri::command]
+pub fn create_stream_batch(
+    state: State<StreamingState>,
+    paths: Vec<String>,
+) -> Result<StreamBatch, String> {
+    if !state.0.lock().is_running() {
+        return Err("Streaming server not running. Call start_stream_server first.".to_string());
+    }
+
+    let mut streams = Vec::new();
+    let mut skipped = Vec::new();
+    for path in paths {
+        match register_file_stream(&state, &path, None) {
+            Ok(stream) => streams.push(stream),
+            Err(error) => {
+                log::warn!("Skipping {} in stream batch: {}", path, error);
+                skipped.push(SkippedStream { path, error });
+            }
+        }
+    }
+
+    let queue_id = (!streams.is_empty()).then(|| {
+        let ids = streams.iter().map(|s| s.stream_id.clone()).collect();
+        state.0.lock().register_queue(ids)
+    });
+
+    Ok(StreamBatch {
+        queue_id,
+        streams,
+        skipped,
+    })
+}
+
+/// Register a file with the running streaming server
+pub(crate) fn register_file_stream(
+    state: &StreamingState,
+    file_path: &str,
+    chunk_size: Option<usize>,
 ) -> Result<StreamInfo, String> {
     let server = state.0.lock();
 
@@ -370,21 +2590,18 @@ pub fn create_stream(
         return Err("Streaming server not running. Call start_stream_server first.".to_string());
     }
 
-    let path = PathBuf::from(&file_path);
+    let path = PathBuf::from(file_path);
 
-    // Check if file exists
-    if !path.exists() {
-        return Err(format!("File not found: {}", file_path));
-    }
+    // Check the file exists and get its size for the frontend's warnings
+    let file_size = file_size(&path)?;
 
     // Get filename for URL (helps TV identify content type)
-    let filename = path.file_name()
-        .and_then(|n| n.to_str())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "video.mp4".to_string());
+    let filename = streaming::stream_filename(&path);
 
     // Register stream
-    let stream_id = server.register_stream(path);
+    let stream_id = server
+        .register_stream(path, chunk_size)
+        .map_err(|e| e.to_string())?;
 
     // Get URLs
     let stream_url = server.get_stream_url(&stream_id, Some(&filename))
@@ -396,6 +2613,126 @@ pub fn create_stream(
         stream_id,
         stream_url,
         server_url,
+        file_size,
+    })
+}
+
+/// Size of a local or mounted file in bytes
+pub(crate) fn file_size(path: &std::path::Path) -> Result<u64, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("File not found: {}", path.display()),
+        _ => format!("Failed to read {}: {}", path.display(), e),
+    })?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", path.display()));
+    }
+    Ok(metadata.len())
+}
+
+/// Get the size of a local or mounted file in bytes, e.g. to estimate the
+/// bandwidth a cast needs before registering it
+#[tauri::command]
+pub fn get_file_size(path: String) -> CommandResult<u64> {
+    match file_size(std::path::Path::new(&path)) {
+        Ok(size) => CommandResult::ok(size),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Decode standard base64, ignoring whitespace and an optional `data:...;base64,` prefix
+fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    let input = match input.split_once(";base64,") {
+        Some((prefix, data)) if prefix.starts_with("data:") => data,
+        _ => input,
+    };
+
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+    for c in input.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            _ => return Err(format!("Invalid base64 character: {:?}", c as char)),
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(output)
+}
+
+/// File extension for common media content types, so receivers get a sensible filename
+fn extension_for_content_type(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "video/mp4" => "mp4",
+        "video/x-matroska" => "mkv",
+        "video/webm" => "webm",
+        "video/mp2t" => "ts",
+        "audio/mpeg" => "mp3",
+        "audio/mp4" => "m4a",
+        "audio/flac" => "flac",
+        "audio/wav" => "wav",
+        "audio/ogg" => "ogg",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        _ => "bin",
+    }
+}
+
+/// Register generated content (base64) for streaming and get the stream URL
+///
+/// Served with Range support like file streams. Small payloads stay in
+/// memory, larger ones are written to a temp file that's deleted with the stream.
+#[tauri::command]
+pub fn create_bytes_stream(
+    state: State<StreamingState>,
+    data_base64: String,
+    content_type: String,
+    filename: Option<String>,
+) -> Result<StreamInfo, String> {
+    let content_type = content_type.trim();
+    if content_type.is_empty() || !content_type.contains('/') {
+        return Err(format!("Invalid content type: {:?}", content_type));
+    }
+
+    let data = decode_base64(&data_base64)?;
+    if data.is_empty() {
+        return Err("Stream data is empty".to_string());
+    }
+
+    let filename = filename
+        .map(|name| name.trim().replace(['/', '\\'], "_"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("stream.{}", extension_for_content_type(content_type)));
+
+    let server = state.0.lock();
+    if !server.is_running() {
+        return Err("Streaming server not running. Call start_stream_server first.".to_string());
+    }
+
+    let file_size = data.len() as u64;
+    let stream_id = server
+        .register_bytes_stream(data, content_type, &filename)
+        .map_err(|e| e.to_string())?;
+    let stream_url = server
+        .get_stream_url(&stream_id, Some(&filename))
+        .ok_or("Failed to get stream URL")?;
+    let server_url = server.get_url().ok_or("Failed to get server URL")?;
+
+    Ok(StreamInfo {
+        stream_id,
+        stream_url,
+        server_url,
+        file_size,
     })
 }
 
@@ -407,6 +2744,112 @@ pub fn remove_stream(state: State<StreamingState>, stream_id: String) -> Command
     CommandResult::ok_empty()
 }
 
+/// Remove every stream of a queue made by `create_stream_batch`
+///
+/// Returns how many streams were removed.
+#[tauri::command]
+pub fn remove_stream_queue(state: State<StreamingState>, queue_id: String) -> CommandResult<usize> {
+    match state.0.lock().remove_queue(&queue_id) {
+        Some(removed) => CommandResult::ok(removed),
+        None => CommandResult::err(format!("Queue not found: {}", queue_id)),
+    }
+}
+
+/// Payload of the `stream-mode-changed` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamModeChanged {
+    pub stream_id: String,
+    pub mode: StreamMode,
+    /// Unchanged; the cast target reloads it to pick up the new mode
+    pub stream_url: Option<String>,
+}
+
+/// Switch a stream between direct and ffmpeg-transcoded serving
+///
+/// The stream keeps its ID and URL. A running transcode is stopped, so the
+/// connected device has to reload the URL. Emits `stream-mode-changed`.
+#[tauri::command]
+pub fn set_stream_mode(
+    app: AppHandle,
+    state: State<StreamingState>,
+    stream_id: String,
+    mode: StreamMode,
+) -> CommandResult<StreamModeChanged> {
+    let server = state.0.lock();
+    if let Err(e) = server.set_stream_mode(&stream_id, mode) {
+        return CommandResult::err(e.to_string());
+    }
+
+    let stream_url = server
+        .list_streams()
+        .into_iter()
+        .find(|(summary, _)| summary.stream_id == stream_id)
+        .and_then(|(_, url)| url);
+    drop(server);
+
+    log::info!("Stream {} now served as {:?}", stream_id, mode);
+    let changed = StreamModeChanged {
+        stream_id,
+        mode,
+        stream_url,
+    };
+    let _ = app.emit("stream-mode-changed", changed.clone());
+    CommandResult::ok(changed)
+}
+
+/// Preview the URL a TV would use for a stream on the given interface and port
+///
+/// Formatted exactly like `create_stream` URLs, but works while the server is stopped.
+#[tauri::command]
+pub fn preview_stream_url(
+    interface_ip: String,
+    port: Option<u16>,
+    stream_id: String,
+    filename: Option<String>,
+) -> CommandResult<String> {
+    let ip = interface_ip.trim();
+    if ip.parse::<std::net::IpAddr>().is_err() {
+        return CommandResult::err(format!("Invalid interface IP: {}", ip));
+    }
+
+    let base_url = streaming::format_base_url(ip, port.unwrap_or(streaming::DEFAULT_PORT));
+    CommandResult::ok(streaming::format_stream_url(
+        &base_url,
+        &stream_id,
+        filename.as_deref(),
+    ))
+}
+
+/// Registered stream with its URL, for the cast management UI
+#[derive(Debug, Serialize)]
+pub struct StreamListing {
+    #[serde(flatten)]
+    pub summary: StreamSummary,
+    pub stream_url: Option<String>,
+}
+
+/// List registered streams with paths, URLs and transfer stats
+#[tauri::command]
+pub fn list_streams(state: State<StreamingState>) -> CommandResult<Vec<StreamListing>> {
+    let server = state.0.lock();
+    let streams = server
+        .list_streams()
+        .into_iter()
+        .map(|(summary, stream_url)| StreamListing {
+            summary,
+            stream_url,
+        })
+        .collect();
+    CommandResult::ok(streams)
+}
+
+/// Get streaming read buffer memory usage
+#[tauri::command]
+pub fn get_stream_buffer_usage(state: State<StreamingState>) -> CommandResult<BufferUsage> {
+    CommandResult::ok(state.0.lock().buffer_usage())
+}
+
 /// Get local IP address
 #[tauri::command]
 pub fn get_local_ip() -> CommandResult<String> {
@@ -0,0 +1,215 @@
+//! DLNA (UPnP AVTransport) playback control
+//!
+//! Just enough SOAP to hand a renderer a stream URL and drive its transport
+//! (play, pause, seek, stop). The AVTransport control URL is read from the
+//! device description at the location found by discovery (SSDP `LOCATION`).
+//! Chromecast targets are recognized there and refused: the Cast protocol
+//! needs TLS, which the backend doesn't have.
+
+use crate::net;
+use std::time::Duration;
+
+/// Timeout for each SOAP call
+const SOAP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// AVTransport service type
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+
+/// Text of the first `<tag>` element in `xml` (namespace prefixes ignored)
+fn element_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = xml
+        .match_indices('<')
+        .map(|(index, _)| &xml[index + 1..])
+        .find(|rest| {
+            let name = rest.split(['>', ' ', '/']).next().unwrap_or_default();
+            name.rsplit(':').next() == Some(tag)
+        })?;
+    let body = &open[open.find('>')? + 1..];
+    Some(body[..body.find('<')?].trim())
+}
+
+/// Resolve a URL from a device description against its location
+fn resolve_url(location: &str, base: Option<&str>, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return url.to_string();
+    }
+    let base = base.filter(|base| !base.is_empty()).unwrap_or(location);
+    let origin_end = base
+        .find("://")
+        .and_then(|scheme| base[scheme + 3..].find('/').map(|index| scheme + 3 + index))
+        .unwrap_or(base.len());
+    if url.starts_with('/') {
+        format!("{}{}", &base[..origin_end], url)
+    } else {
+        let dir_end = base[origin_end..].rfind('/').map_or(base.len(), |i| origin_end + i);
+        format!("{}/{}", &base[..dir_end], url)
+    }
+}
+
+/// Find the AVTransport control URL in a device description
+///
+/// Errors name the reason a device can't be cast to, e.g. a Chromecast.
+pub fn control_url_from_description(location: &str, xml: &str) -> Result<String, String> {
+    let transport = xml
+        .split("</service>")
+        .find(|service| {
+            element_text(service, "serviceType").is_some_and(|t| t.contains("AVTransport"))
+        })
+        .and_then(|service| element_text(service, "controlURL"));
+    if let Some(control_url) = transport {
+        return Ok(resolve_url(location, element_text(xml, "URLBase"), control_url));
+    }
+
+    let device_type = element_text(xml, "deviceType").unwrap_or_default();
+    let manufacturer = element_text(xml, "manufacturer").unwrap_or_default();
+    if device_type.contains("dial-multiscreen-org") || manufacturer.contains("Google") {
+        Err("Chromecast not supported".to_string())
+    } else {
+        Err("Device doesn't support DLNA playback (no AVTransport service)".to_string())
+    }
+}
+
+/// Fetch a device description from its discovery location and find its
+/// AVTransport control URL
+pub fn find_control_url(location: &str) -> Result<String, String> {
+    let xml = net::get(location, &[], SOAP_TIMEOUT)
+        .map_err(|e| format!("Failed to read the device description: {}", e))?;
+    control_url_from_description(location, &xml)
+}
+
+/// Escape text for an XML element body
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Call an AVTransport action on instance 0
+fn invoke(control_url: &str, action: &str, arguments: &str) -> Result<(), String> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:{action} xmlns:u=\"{service}\">\
+         <InstanceID>0</InstanceID>{arguments}</u:{action}></s:Body></s:Envelope>",
+        action = action,
+        service = AV_TRANSPORT,
+        arguments = arguments,
+    );
+    let soap_action = format!("\"{}#{}\"", AV_TRANSPORT, action);
+    let headers = [
+        ("Content-Type", "text/xml; charset=\"utf-8\""),
+        ("SOAPAction", soap_action.as_str()),
+    ];
+
+    net::post(control_url, &headers, &body, SOAP_TIMEOUT)
+        .map(|_| ())
+        .map_err(|e| format!("{} failed: {}", action, e))
+}
+
+/// Load a stream URL on the renderer and start playback
+pub fn play(control_url: &str, stream_url: &str) -> Result<(), String> {
+    invoke(
+        control_url,
+        "SetAVTransportURI",
+        &format!(
+            "<CurrentURI>{}</CurrentURI><CurrentURIMetaData></CurrentURIMetaData>",
+            xml_escape(stream_url)
+        ),
+    )?;
+    resume(control_url)
+}
+
+/// Resume paused playback on the renderer
+pub fn resume(control_url: &str) -> Result<(), String> {
+    invoke(control_url, "Play", "<Speed>1</Speed>")
+}
+
+/// Pause playback on the renderer
+pub fn pause(control_url: &str) -> Result<(), String> {
+    invoke(control_url, "Pause", "")
+}
+
+/// Seek the renderer to `position` seconds
+pub fn seek(control_url: &str, position: f64) -> Result<(), String> {
+    let total = position.max(0.0) as u64;
+    let target = format!("{}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60);
+    invoke(
+        control_url,
+        "Seek",
+        &format!("<Unit>REL_TIME</Unit><Target>{}</Target>", target),
+    )
+}
+
+/// Stop playback on the renderer
+pub fn stop(control_url: &str) -> Result<(), String> {
+    invoke(control_url, "Stop", "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENDERER: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+    <manufacturer>Samsung Electronics</manufacturer>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <controlURL>/upnp/control/RenderingControl1</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>
+        <controlURL>/upnp/control/AVTransport1</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    const CHROMECAST: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:dial-multiscreen-org:device:dial:1</deviceType>
+    <manufacturer>Google Inc.</manufacturer>
+    <serviceList>
+      <service>
+        <serviceType>urn:dial-multiscreen-org:service:dial:1</serviceType>
+        <controlURL>/ssdp/notfound</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    #[test]
+    fn finds_the_av_transport_control_url() {
+        let location = "http://192.168.1.20:9197/dmr";
+        assert_eq!(
+            control_url_from_description(location, RENDERER).unwrap(),
+            "http://192.168.1.20:9197/upnp/control/AVTransport1"
+        );
+    }
+
+    #[test]
+    fn resolves_relative_control_urls() {
+        let location = "http://10.0.0.5:8080/desc/device.xml";
+        assert_eq!(resolve_url(location, None, "ctl/avt"), "http://10.0.0.5:8080/desc/ctl/avt");
+        assert_eq!(
+            resolve_url(location, Some("http://10.0.0.5:49152"), "/avt"),
+            "http://10.0.0.5:49152/avt"
+        );
+        assert_eq!(resolve_url(location, None, "http://other/avt"), "http://other/avt");
+    }
+
+    #[test]
+    fn refuses_chromecasts() {
+        let location = "http://192.168.1.30:8008/ssdp/device-desc.xml";
+        assert_eq!(
+            control_url_from_description(location, CHROMECAST).unwrap_err(),
+            "Chromecast not supported"
+        );
+    }
+}
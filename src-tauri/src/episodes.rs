@@ -0,0 +1,163 @@
+//! Next/previous episode navigation for plain folders
+//!
+//! For TV folders browsed without a server library or playlist: the media
+//! files next to the current one are sorted naturally (`Ep 2` before `Ep 10`)
+//! and the neighbour in the requested direction is played from the start.
+//! Navigation stops at the edge of the folder rather than crossing seasons.
+
+use crate::commands::{play_with_options, CommandResult, PlayOptions};
+use crate::mpv::MpvState;
+use crate::streaming;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+/// Episode that was started
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjacentEpisode {
+    pub path: String,
+    /// Position in the sorted folder listing
+    pub index: usize,
+    pub total: usize,
+}
+
+/// Compare file names the way a person would: case-insensitive, with digit
+/// runs compared by value
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x_digits = String::new();
+                while let Some(c) = a.next_if(|c| c.is_ascii_digit()) {
+                    x_digits.push(c);
+                }
+                let mut y_digits = String::new();
+                while let Some(c) = b.next_if(|c| c.is_ascii_digit()) {
+                    y_digits.push(c);
+                }
+
+                // Compare by value without parsing (runs can exceed u64)
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// List the media files in a folder, naturally sorted
+fn list_media_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    // Works the same for rclone mounts: they're listed like any directory
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to list {:?}: {}", dir, e))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| streaming::is_media_path(&path.to_string_lossy()))
+        .collect();
+
+    files.sort_by(|a, b| {
+        let a = a.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let b = b.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        natural_cmp(&a, &b)
+    });
+
+    Ok(files)
+}
+
+/// Find the file next to `current_path` in the given direction (None at the folder edge)
+pub fn find_adjacent(
+    current_path: &Path,
+    forward: bool,
+) -> Result<Option<AdjacentEpisode>, String> {
+    let dir = current_path
+        .parent()
+        .ok_or_else(|| format!("No parent directory for {:?}", current_path))?;
+    let file_name = current_path
+        .file_name()
+        .ok_or_else(|| format!("Invalid media file name: {:?}", current_path))?;
+
+    let files = list_media_files(dir)?;
+    let current = files
+        .iter()
+        .position(|path| path.file_name() == Some(file_name))
+        .ok_or_else(|| format!("{:?} isn't a media file in {:?}", file_name, dir))?;
+
+    let index = if forward {
+        current + 1
+    } else {
+        match current.checked_sub(1) {
+            Some(index) => index,
+            None => return Ok(None),
+        }
+    };
+
+    Ok(files.get(index).map(|path| AdjacentEpisode {
+        path: path.to_string_lossy().into_owned(),
+        index,
+        total: files.len(),
+    }))
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Play the next (`direction` > 0) or previous (`direction` < 0) media file
+/// in the current file's folder, from the start
+///
+/// Returns None without touching playback at the first/last file.
+#[tauri::command]
+pub fn play_adjacent_episode(
+    app: AppHandle,
+    state: State<MpvState>,
+    current_path: String,
+    direction: i8,
+) -> CommandResult<Option<AdjacentEpisode>> {
+    if direction == 0 {
+        return CommandResult::err("Direction must be positive (next) or negative (previous)");
+    }
+
+    let episode = match find_adjacent(Path::new(&current_path), direction > 0) {
+        Ok(Some(episode)) => episode,
+        Ok(None) => return CommandResult::ok(None),
+        Err(e) => return CommandResult::err(e),
+    };
+
+    let options = PlayOptions {
+        url: episode.path.clone(),
+        start_position: Some(0.0),
+        ..PlayOptions::default()
+    };
+    match play_with_options(&app, &state, &options) {
+        Ok(_) => {
+            log::info!("Playing adjacent episode {}", episode.path);
+            CommandResult::ok(Some(episode))
+        }
+        Err(e) => CommandResult::err(e),
+    }
+}
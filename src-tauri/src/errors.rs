@@ -0,0 +1,55 @@
+//! Background error reporting
+//!
+//! Failures that happen outside of a command (mpv exiting, a mount dropping,
+//! a stream breaking mid-transfer) are emitted as `backend-error` events so
+//! the frontend can surface them the same way regardless of where they came from.
+
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+/// App handle for reporting from code that doesn't have one (server tasks, the mpv IPC client)
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Part of the backend an error came from
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Subsystem {
+    Mpv,
+    Streaming,
+    Rclone,
+    Shortcuts,
+}
+
+/// Event payload for `backend-error`
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendError {
+    pub subsystem: Subsystem,
+    /// Stable machine-readable code, e.g. "process_exited"
+    pub code: &'static str,
+    pub message: String,
+    /// The operation can be retried (e.g. by restarting the player or remounting)
+    pub recoverable: bool,
+}
+
+/// Make the app handle available to `report`. Called once at startup.
+pub fn init(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+/// Log a background error and emit it as `backend-error`
+pub fn report(subsystem: Subsystem, code: &'static str, message: String, recoverable: bool) {
+    log::error!("[{:?}] {}: {}", subsystem, code, message);
+
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit(
+            "backend-error",
+            BackendError {
+                subsystem,
+                code,
+                message,
+                recoverable,
+            },
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Windows Firewall checks for the streaming server
+//!
+//! The most common reason a TV can't reach a stream is Windows Firewall
+//! dropping inbound connections to the streaming port. These commands look for
+//! an inbound rule covering the port or the app and can add one with
+//! `netsh advfirewall`, which needs an elevated process.
+//!
+//! netsh output is localized, so rules are matched by name and by the exe
+//! path rather than by parsing field labels. Other platforms report
+//! `supported: false`.
+
+use crate::commands::{CommandResult, StreamingState};
+use crate::streaming::DEFAULT_PORT;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+/// What's known about inbound access to the streaming port
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallStatus {
+    /// False on platforms without a firewall check
+    pub supported: bool,
+    pub port: u16,
+    /// The rule added by `request_firewall_exception` exists
+    pub port_rule: bool,
+    /// An inbound rule mentions this executable (e.g. from the Windows prompt)
+    pub program_rule: bool,
+    /// The app runs with admin rights and can add a rule itself
+    pub elevated: bool,
+    /// What the user should do next
+    pub guidance: String,
+    /// Command to run in an admin prompt to allow the port
+    pub command: Option<String>,
+}
+
+/// Port to check: the requested one, else the running server's, else the default
+fn resolve_port(app: &AppHandle, port: Option<u16>) -> u16 {
+    port.or_else(|| {
+        app.try_state::<StreamingState>()
+            .map(|state| state.0.lock().port())
+            .filter(|port| *port != 0)
+    })
+    .unwrap_or(DEFAULT_PORT)
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::FirewallStatus;
+    use crate::privileges::is_elevated;
+    use std::process::Command;
+
+    /// Name of the rule `request_firewall_exception` adds for a port
+    fn rule_name(port: u16) -> String {
+        format!("HubRemote Streaming (TCP {})", port)
+    }
+
+    /// Build a command that doesn't open a console window
+    fn hidden(program: &str) -> Command {
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new(program);
+        // CREATE_NO_WINDOW = 0x08000000
+        cmd.creation_flags(0x08000000);
+        cmd
+    }
+
+    /// netsh arguments that add an inbound allow rule for the port
+    fn add_rule_args(port: u16) -> Vec<String> {
+        vec![
+            "advfirewall".to_string(),
+            "firewall".to_string(),
+            "add".to_string(),
+            "rule".to_string(),
+            format!("name={}", rule_name(port)),
+            "dir=in".to_string(),
+            "action=allow".to_string(),
+            "protocol=TCP".to_string(),
+            format!("localport={}", port),
+            "profile=private,domain".to_string(),
+        ]
+    }
+
+    /// The same rule as a command line to paste into an admin prompt
+    fn add_rule_command(port: u16) -> String {
+        add_rule_args(port)
+            .iter()
+            .map(|arg| match arg.split_once('=') {
+                Some((key, value)) if value.contains(' ') => format!("{}=\"{}\"", key, value),
+                _ => arg.clone(),
+            })
+            .fold("netsh".to_string(), |command, arg| command + " " + &arg)
+    }
+
+    fn has_port_rule(port: u16) -> bool {
+        // netsh exits non-zero with "No rules match" when the rule is missing
+        hidden("netsh")
+            .args(["advfirewall", "firewall", "show", "rule"])
+            .arg(format!("name={}", rule_name(port)))
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    fn has_program_rule() -> bool {
+        let Ok(exe) = std::env::current_exe() else {
+            return false;
+        };
+        let exe = exe.to_string_lossy().to_lowercase();
+
+        let Ok(output) = hidden("netsh")
+            .args(["advfirewall", "firewall", "show", "rule", "name=all"])
+            .args(["dir=in", "verbose"])
+            .output()
+        else {
+            return false;
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .to_lowercase()
+            .contains(&exe)
+    }
+
+    pub fn check(port: u16) -> FirewallStatus {
+        let port_rule = has_port_rule(port);
+        let program_rule = has_program_rule();
+        let elevated = is_elevated();
+
+        let guidance = if port_rule {
+            format!("Inbound connections to port {} are allowed.", port)
+        } else if program_rule {
+            "HubRemote has a firewall rule. If devices still can't connect, make sure it \
+             allows Private networks, or add a rule for the port."
+                .to_string()
+        } else if elevated {
+            format!(
+                "No rule allows port {}. Add one to let devices on your network connect.",
+                port
+            )
+        } else {
+            format!(
+                "No rule allows port {}. Restart HubRemote as administrator to add one, \
+                 or run the command below in an administrator Command Prompt.",
+                port
+            )
+        };
+
+        FirewallStatus {
+            supported: true,
+            port,
+            port_rule,
+            program_rule,
+            elevated,
+            guidance,
+            command: (!port_rule).then(|| add_rule_command(port)),
+        }
+    }
+
+    pub fn add_rule(port: u16) -> Result<(), String> {
+        if has_port_rule(port) {
+            return Ok(());
+        }
+        if !is_elevated() {
+            return Err(format!(
+                "Adding a firewall rule needs administrator rights. Restart HubRemote as \
+                 administrator, or run this in an administrator Command Prompt: {}",
+                add_rule_command(port)
+            ));
+        }
+
+        let output = hidden("netsh")
+            .args(add_rule_args(port))
+            .output()
+            .map_err(|e| format!("Failed to run netsh: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Err(format!("netsh failed: {}", stdout.trim()))
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::FirewallStatus;
+
+    pub fn check(port: u16) -> FirewallStatus {
+        FirewallStatus {
+            supported: false,
+            port,
+            port_rule: false,
+            program_rule: false,
+            elevated: false,
+            guidance: "Firewall checks are only available on Windows. Make sure your firewall \
+                       allows inbound TCP connections to the streaming port."
+                .to_string(),
+            command: None,
+        }
+    }
+
+    pub fn add_rule(_port: u16) -> Result<(), String> {
+        Err("Adding a firewall rule is only supported on Windows".to_string())
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Check whether the firewall lets devices reach the streaming port
+///
+/// `port` defaults to the running server's port (or the default port).
+#[tauri::command]
+pub async fn check_firewall_access(
+    app: AppHandle,
+    port: Option<u16>,
+) -> CommandResult<FirewallStatus> {
+    let port = resolve_port(&app, port);
+
+    match tauri::async_runtime::spawn_blocking(move || platform::check(port)).await {
+        Ok(status) => CommandResult::ok(status),
+        Err(e) => CommandResult::err(format!("Firewall check failed: {}", e)),
+    }
+}
+
+/// Add an inbound rule for the streaming port (Windows, admin rights needed)
+///
+/// Without admin rights the error explains how to add the rule manually.
+#[tauri::command]
+pub async fn request_firewall_exception(
+    app: AppHandle,
+    port: Option<u16>,
+) -> CommandResult<FirewallStatus> {
+    let port = resolve_port(&app, port);
+
+    let task = tauri::async_runtime::spawn_blocking(move || {
+        platform::add_rule(port)?;
+        log::info!("Firewall rule added for port {}", port);
+        Ok::<_, String>(platform::check(port))
+    })
+    .await;
+
+    match task {
+        Ok(Ok(status)) => CommandResult::ok(status),
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Failed to add firewall rule: {}", e)),
+    }
+}
@@ -0,0 +1,218 @@
+//! One-shot "play this Jellyfin item" flow
+//!
+//! Looks the item up on the server for its resume position and file path,
+//! then either plays the server stream in mpv or, when casting, registers the
+//! file (reachable through an rclone mount or local disk) with the streaming
+//! server.
+//!
+//! The backend has no TLS client, so the lookup only works against `http://`
+//! servers. For `https://` servers playback still works, just without the
+//! server-side resume position; casting needs the lookup for the file path.
+
+use crate::commands::{
+    ensure_stream_server, play_with_options, register_file_stream, CommandResult, PlayOptions,
+    StreamInfo, StreamingState,
+};
+use crate::mpv::MpvState;
+use crate::net;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Timeout for the item lookup
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Jellyfin ticks per second (100ns units)
+const TICKS_PER_SECOND: f64 = 10_000_000.0;
+
+/// Item fields we need from `/Items`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JellyfinItem {
+    name: Option<String>,
+    path: Option<String>,
+    media_type: Option<String>,
+    user_data: Option<JellyfinUserData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct JellyfinUserData {
+    #[serde(default)]
+    playback_position_ticks: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ItemsResponse {
+    #[serde(default)]
+    items: Vec<JellyfinItem>,
+}
+
+/// What `play_jellyfin_item` did
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayItemSummary {
+    pub item_id: String,
+    pub name: Option<String>,
+    /// "mpv" or "cast"
+    pub mode: &'static str,
+    /// Server stream URL loaded in mpv (without the token)
+    pub url: Option<String>,
+    /// Resume position used, in seconds
+    pub start_position: Option<f64>,
+    /// Local stream registered for casting
+    pub stream: Option<StreamInfo>,
+    /// The item lookup failed; playback started without server data
+    pub lookup_error: Option<String>,
+}
+
+/// Blocking GET of a Jellyfin API URL, returning the response body
+fn http_get(url: &str, token: &str) -> Result<String, String> {
+    if !url.starts_with("http://") {
+        return Err("Item lookup needs an http:// server URL".to_string());
+    }
+    net::get(
+        url,
+        &[("X-Emby-Token", token), ("Accept", "application/json")],
+        HTTP_TIMEOUT,
+    )
+}
+
+/// Look up an item's name, path, media type and resume position
+fn fetch_item(
+    base_url: &str,
+    item_id: &str,
+    token: &str,
+    user_id: Option<&str>,
+) -> Result<JellyfinItem, String> {
+    let mut url = format!(
+        "{}/Items?ids={}&fields=Path&enableUserData=true",
+        base_url, item_id
+    );
+    if let Some(user_id) = user_id {
+        url.push_str(&format!("&userId={}", user_id));
+    }
+
+    let body = http_get(&url, token)?;
+    let response: ItemsResponse =
+        serde_json::from_str(&body).map_err(|e| format!("Invalid item response: {}", e))?;
+    response
+        .items
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Item {} not found", item_id))
+}
+
+/// Play a Jellyfin item in mpv or register it for casting
+pub fn play_item(
+    app: &AppHandle,
+    base_url: &str,
+    item_id: &str,
+    token: &str,
+    user_id: Option<&str>,
+    cast: bool,
+) -> Result<PlayItemSummary, String> {
+    let base_url = base_url.trim_end_matches('/');
+
+    let (item, lookup_error) = match fetch_item(base_url, item_id, token, user_id) {
+        Ok(item) => (Some(item), None),
+        Err(e) => {
+            log::warn!("Jellyfin item lookup failed: {}", e);
+            (None, Some(e))
+        }
+    };
+    let name = item.as_ref().and_then(|i| i.name.clone());
+
+    if cast {
+        let path = item
+            .as_ref()
+            .and_then(|i| i.path.clone())
+            .ok_or_else(|| match &lookup_error {
+                Some(e) => format!("Can't cast without the item's file path: {}", e),
+                None => "Item has no file path to cast".to_string(),
+            })?;
+        if !Path::new(&path).exists() {
+            return Err(format!(
+                "Item file isn't reachable from this machine (is the drive mounted?): {}",
+                path
+            ));
+        }
+
+        ensure_stream_server(app)?;
+        let stream = register_file_stream(&app.state::<StreamingState>(), &path, None)?;
+
+        return Ok(PlayItemSummary {
+            item_id: item_id.to_string(),
+            name,
+            mode: "cast",
+            url: None,
+            start_position: None,
+            stream: Some(stream),
+            lookup_error,
+        });
+    }
+
+    let start_position = item
+        .as_ref()
+        .and_then(|i| i.user_data.as_ref())
+        .map(|data| data.playback_position_ticks as f64 / TICKS_PER_SECOND)
+        .filter(|position| *position > 0.0);
+
+    let url = format!("{}/Videos/{}/stream?static=true", base_url, item_id);
+    let options = PlayOptions {
+        url: url.clone(),
+        start_position,
+        auth_token: Some(token.to_string()),
+        media_type: item.as_ref().and_then(|i| i.media_type.clone()),
+        ..PlayOptions::default()
+    };
+    let start_position = play_with_options(app, &app.state::<MpvState>(), &options)?;
+
+    Ok(PlayItemSummary {
+        item_id: item_id.to_string(),
+        name,
+        mode: "mpv",
+        url: Some(url),
+        start_position,
+        stream: None,
+        lookup_error,
+    })
+}
+
+/// Item id of a Jellyfin stream URL (`.../Videos/<id>/stream...`)
+pub fn item_id_from_url(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("/Videos/")?;
+    let id = rest.split(['/', '?']).next()?;
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Play a Jellyfin item end to end: look it up, resume from the server's
+/// position and play it in mpv, or register it as a local stream when `cast`
+///
+/// Runs off the main thread since the lookup can take a few seconds.
+#[tauri::command]
+pub async fn play_jellyfin_item(
+    app: AppHandle,
+    base_url: String,
+    item_id: String,
+    token: String,
+    cast: bool,
+    user_id: Option<String>,
+) -> CommandResult<PlayItemSummary> {
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        play_item(&app, &base_url, &item_id, &token, user_id.as_deref(), cast)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(summary)) => CommandResult::ok(summary),
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Playback task failed: {}", e)),
+    }
+}
@@ -3,17 +3,54 @@
 //! This library provides the Tauri backend for the HubRemote application,
 //! including MPV video playback integration.
 
+mod av_sync;
+mod bandwidth;
+mod buffering;
+mod cast;
+mod chapters;
 mod commands;
+mod dlna;
+mod episodes;
+mod errors;
+mod firewall;
+mod jellyfin;
 mod mpv;
 mod mpv_ipc;
+mod net;
+mod player_monitor;
+mod playlist;
+mod power;
+mod privileges;
+mod probe;
+mod qr;
 mod rclone;
+mod recent;
+mod schedule;
+mod session;
+mod settings;
 mod shortcuts;
+mod source_profiles;
 mod streaming;
+mod subtitles;
+mod tracks;
 mod tray;
 
+use av_sync::SyncState;
+use bandwidth::BandwidthState;
+use buffering::BufferingState;
+use cast::CastState;
+use chapters::AutoSkipState;
 use commands::StreamingState;
 use mpv::MpvState;
-use tauri::Manager;
+use player_monitor::VolumeWatchState;
+use power::PowerState;
+use recent::RecentState;
+use schedule::ScheduleState;
+use session::SessionState;
+use settings::SettingsState;
+use source_profiles::SourceProfileState;
+use tauri::{Emitter, Manager};
+use tracks::TrackWatchState;
 use tray::TrayState;
 
 /// Greet command for testing
@@ -33,16 +70,50 @@ pub fn run() {
         .manage(TrayState::new())
         // Initialize streaming state
         .manage(StreamingState::new())
+        // Initialize cast session state
+        .manage(CastState::new())
+        .manage(BandwidthState::new())
+        // Initialize persisted settings, recent media and crash recovery
+        .manage(SettingsState::new())
+        .manage(RecentState::new())
+        .manage(SessionState::new())
+        .manage(SyncState::new())
+        .manage(ScheduleState::new())
+        // Initialize volume/mute watcher state
+        .manage(VolumeWatchState::new())
+        // Initialize chapter auto-skip state
+        .manage(AutoSkipState::new())
+        // Initialize track watcher state
+        .manage(TrackWatchState::new())
+        // Initialize cache refill watcher state
+        .manage(BufferingState::new())
+        // Initialize source profile tracking
+        .manage(SourceProfileState::new())
+        // Initialize suspend listener state
+        .manage(PowerState::new())
         // Register all commands
         .invoke_handler(tauri::generate_handler![
             // Test command
             greet,
             // Player initialization
             commands::init_player,
+            commands::cleanup_orphaned_players,
             commands::destroy_player,
+            commands::get_mpv_version,
+            commands::get_player_capabilities,
+            commands::set_mpv_event_logging,
+            commands::get_mpv_event_log,
             // Playback control
             commands::play_video,
             commands::play_video_with_options,
+            jellyfin::play_jellyfin_item,
+            episodes::play_adjacent_episode,
+            commands::reload_current,
+            playlist::export_playlist,
+            playlist::load_playlist_file,
+            schedule::schedule_playback,
+            schedule::cancel_scheduled_playback,
+            schedule::get_scheduled_playback,
             commands::pause_video,
             commands::resume_video,
             commands::toggle_playback,
@@ -50,6 +121,7 @@ pub fn run() {
             // Seeking
             commands::seek_video,
             commands::seek_video_relative,
+            commands::get_seekable,
             // Volume
             commands::set_volume,
             commands::get_volume,
@@ -57,16 +129,95 @@ pub fn run() {
             commands::set_mute,
             // State
             commands::get_playback_state,
+            commands::get_current_media,
+            commands::get_player_status,
             commands::get_position,
             commands::get_duration,
+            commands::get_progress_detail,
+            commands::set_force_format,
+            commands::get_force_format,
+            commands::show_seek_osd,
+            commands::set_seek_osd,
+            commands::get_seek_osd,
+            source_profiles::set_source_profile,
+            source_profiles::list_source_profiles,
+            power::set_pause_on_suspend,
+            power::get_pause_on_suspend,
+            qr::get_url_qr,
             // Tracks
             commands::set_audio_track,
             commands::set_subtitle_track,
+            commands::cycle_audio_track,
+            commands::cycle_subtitle_track,
+            commands::set_subtitle_visibility,
+            commands::set_subtitle_scale_for_viewing,
+            commands::get_subtitle_visibility,
             commands::set_playback_speed,
+            subtitles::find_sidecar_subtitles,
+            subtitles::add_subtitle_file,
+            probe::probe_tracks,
+            probe::get_stream_bitrate,
+            chapters::get_chapters,
+            chapters::set_auto_skip,
+            chapters::set_auto_skip_for_session,
+            tracks::set_track_preferences,
+            tracks::get_track_preferences,
+            tracks::reapply_track_preferences,
+            tracks::get_selected_tracks,
+            tracks::set_subtitle_fallback,
+            tracks::set_honor_forced_subtitles,
+            buffering::set_buffering_strategy,
+            buffering::get_buffering_strategy,
+            session::get_recoverable_session,
+            session::restore_session,
+            session::discard_session,
+            av_sync::nudge_audio_sync,
+            av_sync::nudge_subtitle_sync,
+            av_sync::set_sync_step,
             // Fullscreen
             commands::toggle_fullscreen,
             commands::set_fullscreen,
             commands::is_fullscreen,
+            commands::set_audio_only,
+            commands::is_audio_only,
+            commands::set_always_on_top,
+            commands::get_always_on_top,
+            commands::list_monitors,
+            commands::move_window_to_monitor,
+            // On-screen controller
+            commands::set_osc_autohide,
+            commands::set_keep_open,
+            commands::set_title_format,
+            commands::set_osd_level,
+            commands::set_osd_duration,
+            commands::get_osd_behavior,
+            commands::set_audio_channels,
+            commands::get_audio_channels,
+            commands::set_audio_downmix,
+            commands::set_user_agent,
+            commands::set_referrer,
+            // Screenshots
+            commands::set_screenshot_options,
+            commands::take_screenshot,
+            commands::set_quality_profile,
+            commands::get_quality_profile,
+            commands::set_motion_smoothing,
+            commands::get_motion_smoothing,
+            commands::set_source_cleanup,
+            commands::get_source_cleanup,
+            commands::try_software_decode_fallback,
+            commands::confirm_software_decode,
+            commands::set_tone_mapping,
+            commands::get_available_vos,
+            commands::set_vo,
+            commands::set_night_mode,
+            commands::get_night_mode,
+            commands::set_mpv_cache_location,
+            commands::get_mpv_cache_location,
+            commands::toggle_stats_overlay,
+            commands::show_stats_page,
+            // Privacy
+            commands::panic_hide,
             // Global shortcuts
             shortcuts::enable_global_shortcuts,
             shortcuts::disable_global_shortcuts,
@@ -74,30 +225,132 @@ pub fn run() {
             shortcuts::set_shortcuts_active,
             shortcuts::register_custom_shortcuts,
             shortcuts::get_default_shortcut_config,
+            shortcuts::get_pause_shortcuts_when_focused,
+            shortcuts::set_pause_shortcuts_when_focused,
             // Tray commands
             tray::update_tray_playback,
             tray::get_minimize_to_tray,
             tray::set_minimize_to_tray,
+            tray::get_tray_left_click_action,
+            tray::set_tray_left_click_action,
             tray::show_window,
+            tray::focus_now_playing,
             tray::hide_window,
             // Rclone commands
             rclone::mount_drive,
+            rclone::mount_drive_async,
+            rclone::cancel_mount,
             rclone::unmount_drive,
             rclone::check_mount_status,
             rclone::check_rclone,
+            rclone::check_mount_prerequisites,
             rclone::get_default_rclone_config,
+            rclone::validate_mount_point,
+            rclone::get_remote_quota,
+            rclone::get_remote_file_size,
+            rclone::check_cache_disk_space,
+            rclone::list_remote_files,
+            rclone::start_rclone_serve,
+            rclone::stop_rclone_serve,
+            rclone::prewarm_file,
+            rclone::rclone_copy,
+            rclone::cancel_rclone_copy,
             // Streaming commands
             commands::start_stream_server,
             commands::stop_stream_server,
             commands::is_stream_server_running,
+            commands::change_stream_port,
+            commands::rebind_stream_server,
             commands::get_stream_server_url,
             commands::create_stream,
+            commands::create_stream_batch,
+            commands::remove_stream_queue,
+            commands::get_file_size,
+            commands::create_bytes_stream,
             commands::remove_stream,
+            commands::set_stream_mode,
+            commands::list_streams,
+            commands::set_stream_hostname,
+            commands::set_stream_ip_allowlist,
+            commands::get_stream_ip_allowlist,
+            commands::get_stream_buffer_usage,
             commands::get_local_ip,
+            commands::preview_stream_url,
+            // Cast commands
+            cast::start_mirror_session,
+            cast::stop_mirror_session,
+            cast::get_cast_session,
+            cast::quick_cast,
+            bandwidth::measure_cast_bandwidth,
+            bandwidth::cancel_bandwidth_test,
+            firewall::check_firewall_access,
+            firewall::request_firewall_exception,
+            privileges::get_privilege_status,
+            // Settings and session restore
+            settings::get_settings,
+            settings::export_settings,
+            settings::import_settings,
+            settings::set_resume_on_startup,
+            settings::set_remember_volume,
+            settings::get_remember_volume,
+            settings::set_audio_only_behavior,
+            settings::get_resume_offset,
+            settings::set_resume_offset,
+            settings::get_idle_destroy_timeout,
+            settings::set_idle_destroy_timeout,
+            settings::get_stall_watchdog,
+            settings::set_stall_watchdog,
+            settings::set_auto_start_streaming,
+            player_monitor::watch_volume_changes,
+            recent::get_recent_media,
+            recent::resume_last_session,
         ])
         .setup(|app| {
             // Log app startup
             log::info!("HubRemote starting up...");
+            errors::init(app.handle());
+
+            // Load persisted settings, recent media and any crash recovery snapshot
+            app.state::<SettingsState>().load(app.handle());
+            app.state::<RecentState>().load(app.handle());
+            app.state::<SessionState>().load(app.handle());
+            app.state::<SyncState>().load(app.handle());
+            settings::restore_integrations(app.handle());
+            commands::apply_stream_hostname(app.handle());
+            commands::apply_stream_ip_allowlist(app.handle());
+            commands::apply_always_on_top(app.handle());
+
+            // Watch the player (events, idle shutdown, crash recovery)
+            player_monitor::start(app.handle().clone());
+            power::start(app.handle());
+
+            // Reload the last session (paused) if enabled; the UI shows a resume prompt
+            if app.state::<SettingsState>().get().resume_on_startup {
+                let handle = app.handle().clone();
+                std::thread::spawn(move || match recent::resume_latest(&handle, None) {
+                    Ok(Some(entry)) => {
+                        let _ = handle.emit("session-resumed", entry);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::warn!("Could not resume last session: {}", e);
+                        let _ = handle.emit("session-resume-failed", e);
+                    }
+                });
+            }
+
+            // Kill mpv windows left behind by a crashed instance if enabled
+            if app.state::<SettingsState>().get().cleanup_orphans_on_startup {
+                std::thread::spawn(|| {
+                    if let Err(e) = mpv_ipc::cleanup_orphaned_players() {
+                        log::warn!("Orphaned player cleanup failed: {}", e);
+                    }
+                });
+            }
+
+            // Start the streaming server in the background if enabled
+            let handle = app.handle().clone();
+            std::thread::spawn(move || commands::auto_start_stream_server(&handle));
 
             // Create system tray
             match tray::create_tray(app.handle()) {
@@ -107,9 +360,9 @@ pub fn run() {
 
             Ok(())
         })
-        .on_window_event(|window, event| {
+        .on_window_event(|window, event| match event {
             // Handle window close event - minimize to tray instead of quitting
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
                 if tray::should_minimize_to_tray(window.app_handle()) {
                     // Prevent the window from closing
                     api.prevent_close();
@@ -118,11 +371,22 @@ pub fn run() {
                     log::info!("Window minimized to tray");
                 }
             }
+            // Keep media keys local while typing in the app, if enabled
+            tauri::WindowEvent::Focused(focused) if window.label() == "main" => {
+                shortcuts::handle_focus_change(window.app_handle(), *focused);
+            }
+            _ => {}
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app, event| {
+        .run(|app, event| {
             if let tauri::RunEvent::Exit = event {
+                // Remember where playback stopped
+                recent::save_progress(app);
+                player_monitor::remember_volume(app, &app.state::<MpvState>());
+                schedule::cancel(app);
+                // Clean exit: no crash recovery needed next time
+                session::clear(app);
                 // Cleanup rclone mounts on exit
                 rclone::cleanup();
                 log::info!("HubRemote shutting down...");
@@ -3,8 +3,17 @@
 //! Manages the MPV player instance using IPC communication.
 //! This works with any installed mpv version.
 
-use crate::mpv_ipc::{MpvIpc, MpvIpcError, PlaybackState};
-use parking_lot::RwLock;
+use crate::mpv_ipc::{
+    Chapter, CurrentMedia, HwdecFallback, MpvCapabilities, MpvIpc, MpvIpcError, MpvSpawnOptions,
+    MpvTrack, MpvVersion, PlaybackState, PlayerStatus, ProgressDetail,
+};
+use crate::settings::{
+    AudioOnlyBehavior, BufferingStrategy, KeepOpen, MotionSmoothing, QualityProfile,
+    ScreenshotOptions, SourceCleanup, SubtitleViewing, TrackPreferences,
+};
+use parking_lot::{Mutex, RwLock};
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -19,11 +28,31 @@ pub enum MpvError {
 
     #[error("IPC error: {0}")]
     IpcError(#[from] MpvIpcError),
+
+    #[error("Content is not seekable")]
+    NotSeekable,
 }
 
+/// lavfi graph used for the audio visualizer layout (needs an audio track)
+const AUDIO_VISUALIZER_GRAPH: &str =
+    "[aid1]asplit[ao][a];[a]showwaves=s=1280x720:mode=cline:rate=30,format=yuv420p[vo]";
+
 /// Thread-safe MPV state container
 pub struct MpvState {
     player: Arc<RwLock<Option<MpvIpc>>>,
+    /// Whether the audio-only layout is currently applied
+    audio_layout: AtomicBool,
+    /// Windowed geometry saved before entering fullscreen
+    saved_geometry: Mutex<Option<String>>,
+    /// Number of finished spawn attempts, so callers that waited on one can share its result
+    init_attempts: AtomicU64,
+    /// Error of the last failed spawn attempt
+    last_init_error: Mutex<Option<String>>,
+    /// `vid` before audio-only mode was enabled, and whether the window was
+    /// minimized for it (None = video output on)
+    audio_only: Mutex<Option<(serde_json::Value, bool)>>,
+    /// Demuxer format forced for loads that don't pick their own (session only)
+    force_format: Mutex<Option<String>>,
 }
 
 impl MpvState {
@@ -31,11 +60,40 @@ impl MpvState {
     pub fn new() -> Self {
         Self {
             player: Arc::new(RwLock::new(None)),
+            audio_layout: AtomicBool::new(false),
+            saved_geometry: Mutex::new(None),
+            init_attempts: AtomicU64::new(0),
+            last_init_error: Mutex::new(None),
+            audio_only: Mutex::new(None),
+            force_format: Mutex::new(None),
         }
     }
 
-    /// Initialize the MPV player (fullscreen with OSC)
-    pub fn init(&self) -> Result<(), MpvError> {
+    /// Initialize the MPV player (fullscreen, OSC per `options`)
+    ///
+    /// Idempotent and safe to call concurrently: the spawn runs under the write
+    /// lock, and callers that were waiting on it get its result instead of
+    /// spawning again. A failed spawn leaves the player uninitialized.
+    pub fn init(&self, options: &MpvSpawnOptions) -> Result<(), MpvError> {
+        self.init_with(|| {
+            let mut mpv = MpvIpc::new();
+            match mpv.start(options) {
+                Ok(()) => Ok(mpv),
+                Err(e) => {
+                    // Kill a process that started but never answered on the pipe
+                    mpv.stop();
+                    Err(e)
+                }
+            }
+        })
+    }
+
+    /// `init` with the spawn step passed in, so the locking doesn't depend on mpv
+    fn init_with<F>(&self, spawn: F) -> Result<(), MpvError>
+    where
+        F: FnOnce() -> Result<MpvIpc, MpvIpcError>,
+    {
+        let attempts_before = self.init_attempts.load(Ordering::SeqCst);
         let mut player_guard = self.player.write();
 
         // Already initialized?
@@ -43,17 +101,38 @@ impl MpvState {
             return Ok(());
         }
 
+        // Another caller's spawn finished (and failed) while we waited for the lock
+        if self.init_attempts.load(Ordering::SeqCst) != attempts_before {
+            if let Some(error) = self.last_init_error.lock().clone() {
+                return Err(MpvError::InitError(error));
+            }
+        }
+
         log::info!("Initializing MPV player via IPC...");
 
-        let mut mpv = MpvIpc::new();
-        mpv.start().map_err(|e| MpvError::InitError(e.to_string()))?;
+        let result = spawn();
+        self.init_attempts.fetch_add(1, Ordering::SeqCst);
 
+        let mpv = match result {
+            Ok(mpv) => mpv,
+            Err(e) => {
+                *self.last_init_error.lock() = Some(e.to_string());
+                return Err(MpvError::InitError(e.to_string()));
+            }
+        };
+
+        *self.last_init_error.lock() = None;
         *player_guard = Some(mpv);
 
         log::info!("MPV player initialized successfully");
         Ok(())
     }
 
+    /// Check if the player has been initialized
+    pub fn is_initialized(&self) -> bool {
+        self.player.read().is_some()
+    }
+
     /// Destroy the player
     pub fn destroy(&self) {
         let mut player_guard = self.player.write();
@@ -61,6 +140,38 @@ impl MpvState {
             log::info!("Destroying MPV player");
             mpv.stop();
         }
+        self.audio_layout.store(false, Ordering::Relaxed);
+        *self.audio_only.lock() = None;
+    }
+
+    /// Drop the player if the mpv process has exited (window closed or crash)
+    ///
+    /// Returns the exit status so the caller can tell a crash from a normal quit.
+    pub fn reap_exited(&self) -> Option<ExitStatus> {
+        let mut player_guard = self.player.write();
+        let status = player_guard.as_mut()?.exit_status()?;
+
+        log::info!("mpv exited with {}", status);
+        if let Some(mut mpv) = player_guard.take() {
+            mpv.stop();
+        }
+        self.audio_layout.store(false, Ordering::Relaxed);
+        *self.audio_only.lock() = None;
+        Some(status)
+    }
+
+    /// Get the mpv version detected at init (None if detection failed)
+    pub fn version(&self) -> Result<Option<MpvVersion>, MpvError> {
+        let player_guard = self.player.read();
+        let player = player_guard.as_ref().ok_or(MpvError::NotInitialized)?;
+        Ok(player.version().cloned())
+    }
+
+    /// Get version-gated capabilities of the running mpv
+    pub fn capabilities(&self) -> Result<MpvCapabilities, MpvError> {
+        let player_guard = self.player.read();
+        let player = player_guard.as_ref().ok_or(MpvError::NotInitialized)?;
+        Ok(player.capabilities())
     }
 
     /// Execute an operation on the player
@@ -102,6 +213,7 @@ impl<'a> MpvPlayer<'a> {
         url: &str,
         start_position: Option<f64>,
         headers: Option<&[(&str, &str)]>,
+        paused: bool,
     ) -> Result<(), MpvIpcError> {
         // Build URL with headers if provided
         let final_url = if let Some(hdrs) = headers {
@@ -124,13 +236,21 @@ impl<'a> MpvPlayer<'a> {
             url.to_string()
         };
 
-        // Set start position if provided
+        // Pass the start position and pause as per-file options; the global
+        // `start` and `pause` properties would also apply to every file
+        // loaded after this one
+        let mut file_options = Vec::new();
         if let Some(pos) = start_position {
-            // Convert to seconds and set start property
-            let _ = self.ipc.set_property("start", format!("{}", pos));
+            file_options.push(format!("start={}", pos));
+        }
+        if paused {
+            file_options.push("pause=yes".to_string());
+        }
+        if file_options.is_empty() {
+            self.ipc.load_file(&final_url)
+        } else {
+            self.ipc.load_file_with_file_options(&final_url, &file_options.join(","))
         }
-
-        self.ipc.load_file(&final_url)
     }
 
     /// Pause playback
@@ -229,12 +349,42 @@ impl MpvState {
         url: &str,
         start_position: Option<f64>,
         headers: Option<&[(&str, &str)]>,
+        paused: bool,
     ) -> Result<(), MpvError> {
         self.with_player(|ipc| {
-            MpvPlayer::new(ipc).load_file_with_options(url, start_position, headers)
+            MpvPlayer::new(ipc).load_file_with_options(url, start_position, headers, paused)
         })
     }
 
+    /// Reload the current file at the current position, returning that position
+    pub fn reload_current(&self) -> Result<f64, MpvError> {
+        self.with_player(|ipc| ipc.reload_current())
+    }
+
+    /// Set HTTP User-Agent and Referer used for the next network loads
+    pub fn set_http_identity(
+        &self,
+        user_agent: Option<&str>,
+        referrer: Option<&str>,
+    ) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_http_identity(user_agent, referrer))
+    }
+
+    /// Remember the format to force for the following loads
+    pub fn set_force_format(&self, format: Option<String>) {
+        *self.force_format.lock() = format;
+    }
+
+    /// Format forced for the following loads, if any
+    pub fn force_format(&self) -> Option<String> {
+        self.force_format.lock().clone()
+    }
+
+    /// Force the demuxer format for the next load (None = detect)
+    pub fn apply_force_format(&self, format: Option<&str>) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_force_format(format))
+    }
+
     /// Pause
     pub fn pause(&self) -> Result<(), MpvError> {
         self.with_player(|ipc| MpvPlayer::new(ipc).pause())
@@ -257,14 +407,30 @@ impl MpvState {
 
     /// Seek absolute
     pub fn seek(&self, position: f64) -> Result<(), MpvError> {
+        self.ensure_seekable()?;
         self.with_player(|ipc| MpvPlayer::new(ipc).seek(position))
     }
 
     /// Seek relative
     pub fn seek_relative(&self, offset: f64) -> Result<(), MpvError> {
+        self.ensure_seekable()?;
         self.with_player(|ipc| MpvPlayer::new(ipc).seek_relative(offset))
     }
 
+    /// Check if the loaded content can be seeked (false for live streams and pipes)
+    pub fn is_seekable(&self) -> Result<bool, MpvError> {
+        self.with_player(|ipc| ipc.is_seekable())
+    }
+
+    /// Fail with a readable error instead of mpv's when the content isn't seekable
+    fn ensure_seekable(&self) -> Result<(), MpvError> {
+        if self.is_seekable()? {
+            Ok(())
+        } else {
+            Err(MpvError::NotSeekable)
+        }
+    }
+
     /// Set volume
     pub fn set_volume(&self, volume: i64) -> Result<(), MpvError> {
         self.with_player(|ipc| MpvPlayer::new(ipc).set_volume(volume))
@@ -275,6 +441,11 @@ impl MpvState {
         self.with_player(|ipc| MpvPlayer::new(ipc).get_volume())
     }
 
+    /// Check if muted
+    pub fn is_muted(&self) -> Result<bool, MpvError> {
+        self.with_player(|ipc| ipc.is_muted())
+    }
+
     /// Set mute
     pub fn set_mute(&self, muted: bool) -> Result<(), MpvError> {
         self.with_player(|ipc| MpvPlayer::new(ipc).set_mute(muted))
@@ -295,11 +466,63 @@ impl MpvState {
         self.with_player(|ipc| MpvPlayer::new(ipc).get_duration())
     }
 
+    /// Check if the player is idle (nothing loaded or at end of file)
+    pub fn is_idle(&self) -> Result<bool, MpvError> {
+        self.with_player(|ipc| ipc.is_idle())
+    }
+
     /// Get state
     pub fn get_state(&self) -> Result<PlaybackState, MpvError> {
         self.with_player(|ipc| MpvPlayer::new(ipc).get_state())
     }
 
+    /// Get the path or URL of the loaded file
+    pub fn get_path(&self) -> Result<Option<String>, MpvError> {
+        self.with_player(|ipc| ipc.get_path())
+    }
+
+    /// Get the player status; an unresponsive mpv is reported as `Error`
+    pub fn status(&self) -> PlayerStatus {
+        match self.with_player(|ipc| ipc.get_status()) {
+            Ok(status) => status,
+            Err(MpvError::NotInitialized) => PlayerStatus::Uninitialized,
+            Err(e) => {
+                log::warn!("Could not read player status: {}", e);
+                PlayerStatus::Error
+            }
+        }
+    }
+
+    /// Get the loaded file's path, title, tags and playlist entry
+    pub fn get_current_media(&self) -> Result<CurrentMedia, MpvError> {
+        self.with_player(|ipc| ipc.get_current_media())
+    }
+
+    /// Get position, duration, remaining time and percentage
+    pub fn get_progress_detail(&self) -> Result<ProgressDetail, MpvError> {
+        self.with_player(|ipc| ipc.get_progress_detail())
+    }
+
+    /// Get the chapters of the loaded file
+    pub fn get_chapters(&self) -> Result<Vec<Chapter>, MpvError> {
+        self.with_player(|ipc| ipc.get_chapters())
+    }
+
+    /// Get the current chapter index
+    pub fn get_chapter(&self) -> Result<Option<i64>, MpvError> {
+        self.with_player(|ipc| ipc.get_chapter())
+    }
+
+    /// Jump to a chapter
+    pub fn set_chapter(&self, index: i64) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_chapter(index))
+    }
+
+    /// Seek to the end of the file
+    pub fn seek_to_end(&self) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.seek_to_end())
+    }
+
     /// Set audio track
     pub fn set_audio_track(&self, index: i64) -> Result<(), MpvError> {
         self.with_player(|ipc| MpvPlayer::new(ipc).set_audio_track(index))
@@ -310,19 +533,297 @@ impl MpvState {
         self.with_player(|ipc| MpvPlayer::new(ipc).set_subtitle_track(index))
     }
 
+    /// Show or hide subtitles, keeping the selected track
+    pub fn set_subtitle_visibility(&self, visible: bool) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_subtitle_visibility(visible))
+    }
+
+    /// Check if subtitles are shown
+    pub fn get_subtitle_visibility(&self) -> Result<bool, MpvError> {
+        self.with_player(|ipc| ipc.get_subtitle_visibility())
+    }
+
+    /// Switch to the next audio track
+    pub fn cycle_audio_track(&self) -> Result<Option<MpvTrack>, MpvError> {
+        self.with_player(|ipc| ipc.cycle_audio_track())
+    }
+
+    /// Switch to the next subtitle track (or off)
+    pub fn cycle_subtitle_track(&self) -> Result<Option<MpvTrack>, MpvError> {
+        self.with_player(|ipc| ipc.cycle_subtitle_track())
+    }
+
+    /// Load and select an external subtitle file
+    pub fn add_subtitle(&self, path: &str) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.sub_add(path))
+    }
+
     /// Set speed
     pub fn set_speed(&self, speed: f64) -> Result<(), MpvError> {
         self.with_player(|ipc| MpvPlayer::new(ipc).set_speed(speed))
     }
 
-    /// Toggle fullscreen
+    /// Switch between the audio-only and the default video layout
+    ///
+    /// `has_audio` adds the visualizer graph if the behavior asks for it; it's
+    /// only known once the file's track list is in, and the graph fails to
+    /// build without an audio track.
+    pub fn apply_media_layout(
+        &self,
+        is_audio: bool,
+        behavior: AudioOnlyBehavior,
+        has_audio: bool,
+    ) -> Result<(), MpvError> {
+        let audio_layout = is_audio && behavior != AudioOnlyBehavior::Fullscreen;
+        let was_audio_layout = self.audio_layout.swap(audio_layout, Ordering::Relaxed);
+
+        self.with_player(|ipc| {
+            if audio_layout {
+                // Keep a window open for cover art / visualizer instead of a black fullscreen
+                ipc.set_property("force-window", "yes")?;
+                if !was_audio_layout {
+                    ipc.set_fullscreen(false)?;
+                }
+                let graph = match behavior {
+                    AudioOnlyBehavior::Visualizer if has_audio => AUDIO_VISUALIZER_GRAPH,
+                    _ => "",
+                };
+                ipc.set_property("lavfi-complex", graph)
+            } else if was_audio_layout {
+                // Back to the default video layout
+                ipc.set_property("lavfi-complex", "")?;
+                ipc.set_property("force-window", "no")?;
+                ipc.set_fullscreen(true)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// Turn video output off (audio keeps playing) or back on
+    ///
+    /// Independent of the audio-only layout: this works for any file and stays
+    /// on across files until disabled. The previous `vid` is restored on
+    /// disable, along with the window if `minimize` hid it.
+    pub fn set_audio_only(&self, enabled: bool, minimize: bool) -> Result<(), MpvError> {
+        self.with_player(|ipc| {
+            // Locked after the player, in the same order as `destroy`
+            let mut audio_only = self.audio_only.lock();
+            match (enabled, audio_only.as_ref()) {
+                (true, None) => {
+                    let vid = ipc
+                        .get_property::<serde_json::Value>("vid")
+                        .ok()
+                        .filter(|vid| vid.is_number())
+                        .unwrap_or_else(|| serde_json::json!("auto"));
+                    ipc.set_property("vid", "no")?;
+                    if minimize {
+                        ipc.set_minimized(true)?;
+                    }
+                    *audio_only = Some((vid, minimize));
+                }
+                (false, Some((vid, minimized))) => {
+                    ipc.set_property("vid", vid)?;
+                    if *minimized {
+                        ipc.set_minimized(false)?;
+                    }
+                    *audio_only = None;
+                }
+                // Already in the requested state
+                _ => {}
+            }
+            Ok(())
+        })
+    }
+
+    /// Whether video output is turned off by `set_audio_only`
+    pub fn is_audio_only(&self) -> bool {
+        self.audio_only.lock().is_some()
+    }
+
+    /// Toggle fullscreen, remembering/restoring the windowed geometry
     pub fn toggle_fullscreen(&self) -> Result<(), MpvError> {
-        self.with_player(|ipc| ipc.toggle_fullscreen())
+        let fullscreen = self.is_fullscreen()?;
+        self.set_fullscreen(!fullscreen)
     }
 
     /// Set fullscreen state
+    ///
+    /// Entering fullscreen saves the window geometry; leaving restores it
+    /// instead of letting mpv fall back to its default size and position.
     pub fn set_fullscreen(&self, fullscreen: bool) -> Result<(), MpvError> {
-        self.with_player(|ipc| ipc.set_fullscreen(fullscreen))
+        self.with_player(|ipc| {
+            let was_fullscreen = ipc.is_fullscreen()?;
+
+            if fullscreen && !was_fullscreen {
+                if let Ok(Some(geometry)) = ipc.get_window_geometry() {
+                    *self.saved_geometry.lock() = Some(geometry);
+                }
+            }
+
+            ipc.set_fullscreen(fullscreen)?;
+
+            if !fullscreen && was_fullscreen {
+                if let Some(geometry) = self.saved_geometry.lock().clone() {
+                    if let Err(e) = ipc.set_window_geometry(&geometry) {
+                        log::warn!("Failed to restore window geometry {}: {}", geometry, e);
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Apply screenshot format, directory and template
+    pub fn set_screenshot_options(&self, options: &ScreenshotOptions) -> Result<(), MpvError> {
+        self.with_player(|ipc| {
+            ipc.set_screenshot_options(
+                &options.format,
+                options.directory.as_deref(),
+                options.template.as_deref(),
+            )
+        })
+    }
+
+    /// Take a screenshot using the configured options
+    pub fn take_screenshot(&self) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.screenshot())
+    }
+
+    /// Set the audio output channel layout
+    pub fn set_audio_channels(&self, layout: &str) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_audio_channels(layout))
+    }
+
+    /// Size subtitles for a viewing distance. Returns the `sub-scale` applied.
+    ///
+    /// With `match_resolution` the preset is scaled by the output height
+    /// relative to 1080p (when mpv reports it) instead of by the window.
+    pub fn set_subtitle_viewing(&self, viewing: SubtitleViewing) -> Result<f64, MpvError> {
+        self.with_player(|ipc| {
+            let (mut scale, font_size) = viewing.distance.sub_size();
+            if viewing.match_resolution {
+                let height = ipc.get_property::<f64>("osd-height").unwrap_or(0.0);
+                if height > 0.0 {
+                    scale *= (height / 1080.0).clamp(0.5, 2.0);
+                }
+            }
+            let scale = (scale * 100.0).round() / 100.0;
+            ipc.set_subtitle_size(scale, font_size, !viewing.match_resolution)?;
+            Ok(scale)
+        })
+    }
+
+    /// Apply a readahead/cache-pause preset
+    pub fn set_buffering_strategy(&self, strategy: BufferingStrategy) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_properties(strategy.mpv_properties()))
+    }
+
+    /// Set the HDR tone mapping curve
+    pub fn set_tone_mapping(&self, mode: &str) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_tone_mapping(mode))
+    }
+
+    /// Apply a scaler/deband quality profile
+    pub fn set_quality_profile(&self, profile: QualityProfile) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_properties(profile.mpv_properties()))
+    }
+
+    /// Set the demuxer cache directory and whether the cache is kept on disk
+    pub fn set_cache_location(&self, dir: Option<&str>, on_disk: bool) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_cache_location(dir, on_disk))
+    }
+
+    /// Switch the current video to software decoding
+    pub fn software_decode_fallback(&self) -> Result<HwdecFallback, MpvError> {
+        self.with_player(|ipc| ipc.software_decode_fallback())
+    }
+
+    /// Software-decode these codecs and hardware-decode the rest
+    pub fn set_software_decode_codecs(&self, codecs: &[String]) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_software_decode_codecs(codecs))
+    }
+
+    /// Switch the running player's video output driver
+    pub fn set_video_output(&self, name: &str) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_video_output(name))
+    }
+
+    /// Turn the night mode dynamic range compression on or off
+    pub fn set_night_mode(&self, enabled: bool) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_night_mode(enabled))
+    }
+
+    /// Set the languages mpv selects tracks by when a file loads
+    pub fn set_track_languages(&self, preferences: &TrackPreferences) -> Result<(), MpvError> {
+        self.with_player(|ipc| {
+            ipc.set_track_languages(
+                &preferences.audio_languages.join(","),
+                &preferences.subtitle_languages.join(","),
+            )
+        })
+    }
+
+    /// Apply a video sync/interpolation mode
+    pub fn set_motion_smoothing(&self, mode: MotionSmoothing) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_properties(mode.mpv_properties()))
+    }
+
+    /// Apply a deblock/denoise level
+    pub fn set_source_cleanup(&self, level: SourceCleanup) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_cleanup_filter(level.filter_graph()))
+    }
+
+    /// Toggle the stats overlay
+    pub fn toggle_stats(&self) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.toggle_stats())
+    }
+
+    /// Show a stats overlay page
+    pub fn show_stats_page(&self, page: u8) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.show_stats_page(page))
+    }
+
+    /// Configure OSC visibility. Returns false if the OSC isn't loaded and a restart is needed.
+    pub fn set_osc_autohide(&self, enabled: bool, hide_delay_ms: u32) -> Result<bool, MpvError> {
+        self.with_player(|ipc| {
+            if !ipc.is_osc_loaded() {
+                return Ok(!enabled);
+            }
+            ipc.set_osc_autohide(enabled, hide_delay_ms)?;
+            Ok(true)
+        })
+    }
+
+    /// Set the window title template
+    pub fn set_title(&self, template: &str) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_title(template))
+    }
+
+    /// Set end-of-file behavior
+    pub fn set_keep_open(&self, mode: KeepOpen) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_keep_open(mode.as_str()))
+    }
+
+    /// Set how much on-screen info mpv shows (0-3)
+    pub fn set_osd_level(&self, level: u8) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_osd_level(level))
+    }
+
+    /// Set how long OSD messages stay up, in milliseconds
+    pub fn set_osd_duration(&self, duration_ms: u32) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_osd_duration(duration_ms))
+    }
+
+    /// Keep the player window above other windows
+    pub fn set_ontop(&self, enabled: bool) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_ontop(enabled))
+    }
+
+    /// Minimize or restore the player window
+    pub fn set_minimized(&self, minimized: bool) -> Result<(), MpvError> {
+        self.with_player(|ipc| ipc.set_minimized(minimized))
     }
 
     /// Check if fullscreen
@@ -330,3 +831,49 @@ impl MpvState {
         self.with_player(|ipc| ipc.is_fullscreen())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Barrier;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn concurrent_init_spawns_once() {
+        let state = Arc::new(MpvState::new());
+        let spawns = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(2));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let (state, spawns, barrier) = (state.clone(), spawns.clone(), barrier.clone());
+                thread::spawn(move || {
+                    barrier.wait();
+                    state.init_with(|| {
+                        spawns.fetch_add(1, Ordering::SeqCst);
+                        // Hold the lock long enough for the other thread to queue up
+                        thread::sleep(Duration::from_millis(100));
+                        Ok(MpvIpc::new())
+                    })
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap().is_ok());
+        }
+        assert_eq!(spawns.load(Ordering::SeqCst), 1);
+        assert!(state.is_initialized());
+    }
+
+    #[test]
+    fn failed_init_leaves_player_uninitialized() {
+        let state = MpvState::new();
+        let result = state.init_with(|| Err(MpvIpcError::StartError("no mpv".to_string())));
+
+        assert!(matches!(result, Err(MpvError::InitError(_))));
+        assert!(!state.is_initialized());
+    }
+}
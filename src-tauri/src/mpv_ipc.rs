@@ -5,30 +5,245 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
 use std::io::{BufRead, BufReader, Write};
-use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
+use crate::errors::Subsystem;
+
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
+/// Prefix of our IPC pipe/socket names, followed by the owning HubRemote PID
+const PIPE_PREFIX: &str = "hubremote-mpv-";
+
+/// Raw IPC traffic is recorded (off by default)
+static EVENT_LOGGING: AtomicBool = AtomicBool::new(false);
+
+/// Recorded IPC lines and the ring buffer capacity
+static EVENT_LOG: Mutex<(VecDeque<IpcLogEntry>, usize)> = Mutex::new((VecDeque::new(), 0));
+
+/// A raw JSON line sent to or received from mpv
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcLogEntry {
+    /// Unix timestamp in milliseconds
+    pub timestamp_ms: u64,
+    /// "sent" or "received"
+    pub direction: &'static str,
+    pub line: String,
+}
+
+/// Turn raw IPC recording on or off, keeping the last `capacity` lines
+///
+/// Turning it off (or shrinking it) drops what no longer fits.
+pub fn set_event_logging(enabled: bool, capacity: usize) {
+    if let Ok(mut log) = EVENT_LOG.lock() {
+        let (entries, max) = &mut *log;
+        *max = if enabled { capacity } else { 0 };
+        while entries.len() > *max {
+            entries.pop_front();
+        }
+    }
+    EVENT_LOGGING.store(enabled, Ordering::Relaxed);
+}
+
+/// Get the recorded IPC lines, oldest first
+pub fn event_log() -> Vec<IpcLogEntry> {
+    EVENT_LOG
+        .lock()
+        .map(|log| log.0.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Record a raw IPC line if logging is on
+fn record_ipc_line(direction: &'static str, line: &str) {
+    if !EVENT_LOGGING.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Ok(mut log) = EVENT_LOG.lock() {
+        let (entries, max) = &mut *log;
+        if *max == 0 {
+            return;
+        }
+        if entries.len() >= *max {
+            entries.pop_front();
+        }
+        entries.push_back(IpcLogEntry {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            direction,
+            line: line.trim_end().to_string(),
+        });
+    }
+}
+
 /// Generate unique pipe name with process ID
 fn get_pipe_name() -> String {
     let pid = std::process::id();
     #[cfg(windows)]
     {
-        format!(r"\\.\pipe\hubremote-mpv-{}", pid)
+        format!(r"\\.\pipe\{}{}", PIPE_PREFIX, pid)
     }
     #[cfg(not(windows))]
     {
-        format!("/tmp/hubremote-mpv-{}.sock", pid)
+        format!("/tmp/{}{}.sock", PIPE_PREFIX, pid)
     }
 }
 
+/// An mpv process left behind by a HubRemote instance that's no longer running
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedPlayer {
+    pub pid: u32,
+    /// PID of the HubRemote instance that started it
+    pub owner_pid: u32,
+    pub killed: bool,
+}
+
+/// List running processes as (pid, command line)
+fn list_processes() -> Result<Vec<(u32, String)>, MpvIpcError> {
+    #[cfg(windows)]
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId) $($_.CommandLine)\" }",
+        ])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()?;
+
+    #[cfg(not(windows))]
+    let output = Command::new("ps").args(["-axww", "-o", "pid=,command="]).output()?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, command) = line.trim().split_once(' ')?;
+            Some((pid.parse().ok()?, command.trim().to_string()))
+        })
+        .collect())
+}
+
+/// Owner PID from an mpv command line using one of our IPC pipes
+fn ipc_owner_pid(command: &str) -> Option<u32> {
+    let (_, rest) = command.split_once("--input-ipc-server=")?;
+    let (_, rest) = rest.split_once(PIPE_PREFIX)?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Kill mpv processes whose HubRemote owner is gone, and remove stale sockets
+///
+/// Processes started by this instance or another running instance are left alone.
+pub fn cleanup_orphaned_players() -> Result<Vec<OrphanedPlayer>, MpvIpcError> {
+    let processes = list_processes()?;
+    let own_pid = std::process::id();
+    let is_alive = |pid: u32| processes.iter().any(|(p, _)| *p == pid);
+
+    let mut orphans = Vec::new();
+    for (pid, command) in &processes {
+        let Some(owner_pid) = ipc_owner_pid(command) else {
+            continue;
+        };
+        if owner_pid == own_pid || is_alive(owner_pid) {
+            continue;
+        }
+
+        #[cfg(windows)]
+        let status = Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/F"])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .status();
+        #[cfg(not(windows))]
+        let status = Command::new("kill").arg(pid.to_string()).status();
+
+        let killed = status.map(|s| s.success()).unwrap_or(false);
+        log::info!(
+            "Orphaned mpv {} (owner {} gone): {}",
+            pid,
+            owner_pid,
+            if killed { "killed" } else { "failed to kill" }
+        );
+        orphans.push(OrphanedPlayer {
+            pid: *pid,
+            owner_pid,
+            killed,
+        });
+    }
+
+    // Sockets outlive their processes on Unix
+    #[cfg(not(windows))]
+    if let Ok(entries) = std::fs::read_dir("/tmp") {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let owner = name
+                .strip_prefix(PIPE_PREFIX)
+                .and_then(|rest| rest.strip_suffix(".sock"))
+                .and_then(|pid| pid.parse::<u32>().ok());
+            if let Some(owner) = owner {
+                if owner != own_pid && !is_alive(owner) {
+                    log::info!("Removing stale mpv socket {}", name);
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+    }
+
+    Ok(orphans)
+}
+
+/// A video output driver the installed mpv supports
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoOutput {
+    pub name: String,
+    pub description: String,
+}
+
+/// List the installed mpv's video outputs by running `mpv --vo=help` once
+///
+/// Outputs that can't show a player window (`null`, `image`, terminal
+/// renderers) are left out.
+pub fn list_video_outputs() -> Result<Vec<VideoOutput>, MpvIpcError> {
+    let mut cmd = Command::new("mpv");
+    cmd.args(["--no-config", "--vo=help"]).stdin(Stdio::null());
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| MpvIpcError::StartError(format!("Failed to run mpv: {}", e)))?;
+
+    // Entries are indented "  name  description" under an "Available video outputs:" header
+    let outputs: Vec<VideoOutput> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.starts_with(' '))
+        .map(|line| {
+            let line = line.trim();
+            let (name, description) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            VideoOutput {
+                name: name.to_string(),
+                description: description.trim().to_string(),
+            }
+        })
+        .filter(|vo| !NON_WINDOW_VOS.contains(&vo.name.as_str()))
+        .collect();
+
+    if outputs.is_empty() {
+        return Err(MpvIpcError::StartError("mpv didn't list any video outputs".to_string()));
+    }
+    Ok(outputs)
+}
+
 /// Errors that can occur during MPV IPC operations
 #[derive(Error, Debug)]
 pub enum MpvIpcError {
@@ -85,12 +300,359 @@ pub struct PlaybackState {
     pub media_title: Option<String>,
 }
 
+/// Overall player state, so the UI doesn't have to infer it from positions
+/// and filenames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayerStatus {
+    /// No mpv process
+    Uninitialized,
+    /// mpv is running with nothing loaded (never played, or stopped)
+    Idle,
+    Playing,
+    Paused,
+    /// Stopped on the last frame at the end of the file (keep-open)
+    Ended,
+    /// mpv doesn't answer
+    Error,
+}
+
+/// Now-playing details of the loaded file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentMedia {
+    /// Full path or URL as passed to `loadfile`
+    pub path: Option<String>,
+    pub media_title: Option<String>,
+    /// File tags such as artist, album and date (keys as stored in the file)
+    pub metadata: BTreeMap<String, String>,
+    /// Zero-based playlist position (None when nothing is loaded)
+    pub playlist_index: Option<i64>,
+    pub playlist_count: i64,
+    /// Title given to the playlist entry, if any
+    pub playlist_title: Option<String>,
+}
+
+/// Playback position with remaining time and percentage
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressDetail {
+    /// Seconds into the file
+    pub position: f64,
+    /// Total length in seconds (None for live streams)
+    pub duration: Option<f64>,
+    /// Seconds left at the current playback speed (None if the duration is unknown)
+    pub remaining: Option<f64>,
+    /// 0-100 (None if the duration is unknown)
+    pub percent: Option<f64>,
+}
+
+/// An entry of mpv's playlist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    /// Path or URL as it was loaded
+    pub filename: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// The entry that's loaded
+    #[serde(default)]
+    pub current: bool,
+}
+
+/// A chapter marker of the loaded file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Start time in seconds
+    pub time: f64,
+}
+
+/// An audio, video or subtitle track of the loaded file (from `track-list`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MpvTrack {
+    pub id: i64,
+    /// "audio", "video" or "sub"
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Language tag as stored in the file, e.g. "eng" or "en"
+    #[serde(default)]
+    pub lang: Option<String>,
+    #[serde(default)]
+    pub codec: Option<String>,
+    #[serde(default)]
+    pub selected: bool,
+    /// Flagged as the default track in the file
+    #[serde(default)]
+    pub default: bool,
+    /// Flagged as forced (e.g. subtitles for foreign-language dialogue only)
+    #[serde(default)]
+    pub forced: bool,
+    /// Loaded from a separate file
+    #[serde(default)]
+    pub external: bool,
+    /// Video track that is really embedded cover art
+    #[serde(default)]
+    pub albumart: bool,
+}
+
+/// mpv's built-in `user-agent` default
+const DEFAULT_USER_AGENT: &str = "libmpv";
+
+/// Labelled audio filter used for night mode: dynamic range compression that
+/// lifts quiet dialog and tames loud scenes
+const NIGHT_MODE_FILTER: &str = "@nightmode:lavfi=[dynaudnorm=f=250:g=15:p=0.9]";
+
+/// Label of the deblock/denoise video filter
+const CLEANUP_FILTER_LABEL: &str = "@cleanup";
+
+/// Video output used unless another one is chosen (`--vo`)
+pub const DEFAULT_VO: &str = "gpu";
+
+/// Video outputs mpv lists that don't open a player window
+const NON_WINDOW_VOS: &[&str] = &["caca", "image", "kitty", "libmpv", "null", "sixel", "tct"];
+
+/// mpv's default `osd-level`: messages and the seek bar, no status line
+pub const DEFAULT_OSD_LEVEL: u8 = 1;
+
+/// mpv's default `osd-duration` in milliseconds
+pub const DEFAULT_OSD_DURATION_MS: u32 = 1000;
+
+/// Hardware decoding mode set at spawn (`--hwdec`)
+const DEFAULT_HWDEC: &str = "auto-safe";
+
+/// Codecs mpv hardware-decodes by default (`--hwdec-codecs`)
+const HWDEC_CODECS: &[&str] = &["h264", "vc1", "hevc", "vp8", "vp9", "av1", "prores"];
+
+/// FFmpeg formats offered for forcing the demuxer of misdetected files
+pub const FORCE_FORMATS: &[&str] = &[
+    "h264", "hevc", "mpegts", "mpeg", "matroska", "mov", "avi", "flv", "aac", "mp3",
+];
+
+/// Window title set at spawn (`--title`)
+pub const DEFAULT_TITLE: &str = "HubRemote Player";
+
+/// Oldest mpv release we test against; anything older gets a warning
+pub const MIN_SUPPORTED_VERSION: (u32, u32) = (0, 34);
+
+/// Parsed mpv version as reported by the `mpv-version` property
+#[derive(Debug, Clone, Serialize)]
+pub struct MpvVersion {
+    /// Raw version string, e.g. "mpv 0.37.0" or "mpv v0.36.0-git-1a2b3c"
+    pub raw: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl MpvVersion {
+    /// Parse an `mpv-version` string. Git builds and a leading "v" are accepted.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let version = raw
+            .trim()
+            .strip_prefix("mpv")
+            .unwrap_or(raw)
+            .trim()
+            .trim_start_matches('v');
+
+        let mut parts = version
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u32>().ok());
+
+        let major = parts.next()??;
+        let minor = parts.next()??;
+        let patch = parts.next().flatten().unwrap_or(0);
+
+        Some(Self {
+            raw: raw.trim().to_string(),
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// Check if this version is at least `major.minor`
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+
+    /// Check if this version is older than the minimum we support
+    pub fn is_outdated(&self) -> bool {
+        !self.at_least(MIN_SUPPORTED_VERSION.0, MIN_SUPPORTED_VERSION.1)
+    }
+
+    /// Features that depend on the installed mpv version
+    pub fn capabilities(&self) -> MpvCapabilities {
+        MpvCapabilities {
+            gpu_next: self.at_least(0, 35),
+            hwdec_auto_safe: self.at_least(0, 31),
+            loadfile_index: self.at_least(0, 38),
+        }
+    }
+}
+
+/// Version-gated mpv features
+#[derive(Debug, Clone, Copy, Serialize, Default)]
+pub struct MpvCapabilities {
+    /// `--vo=gpu-next` (libplacebo renderer)
+    pub gpu_next: bool,
+    /// `--hwdec=auto-safe`
+    pub hwdec_auto_safe: bool,
+    /// `loadfile` takes an insert index before per-file options
+    pub loadfile_index: bool,
+}
+
+/// mpv properties the backend reads or sets; keep in sync when wrapping more
+pub const WRAPPED_PROPERTIES: &[&str] = &[
+    "af",
+    "aid",
+    "alang",
+    "audio-channels",
+    "audio-delay",
+    "cache-buffering-state",
+    "cache-pause",
+    "cache-pause-initial",
+    "cache-pause-wait",
+    "chapter",
+    "chapter-list",
+    "cscale",
+    "deband",
+    "demuxer",
+    "demuxer-cache-dir",
+    "demuxer-lavf-format",
+    "demuxer-readahead-secs",
+    "demuxer-thread",
+    "dscale",
+    "duration",
+    "eof-reached",
+    "filename",
+    "fullscreen",
+    "geometry",
+    "http-header-fields",
+    "hwdec",
+    "hwdec-codecs",
+    "hwdec-current",
+    "idle-active",
+    "interpolation",
+    "keep-open",
+    "lavfi-complex",
+    "media-title",
+    "metadata",
+    "mpv-version",
+    "mute",
+    "ontop",
+    "osd-duration",
+    "osd-height",
+    "osd-level",
+    "osd-width",
+    "path",
+    "pause",
+    "paused-for-cache",
+    "percent-pos",
+    "playlist",
+    "playlist-count",
+    "playlist-pos",
+    "playtime-remaining",
+    "referrer",
+    "scale",
+    "screenshot-directory",
+    "screenshot-format",
+    "screenshot-template",
+    "script-opts",
+    "seekable",
+    "sid",
+    "sigmoid-upscaling",
+    "slang",
+    "speed",
+    "sub-delay",
+    "sub-font-size",
+    "sub-scale",
+    "sub-scale-by-window",
+    "sub-visibility",
+    "time-pos",
+    "title",
+    "tone-mapping",
+    "track-list",
+    "tscale",
+    "user-agent",
+    "vid",
+    "video-sync",
+    "vo",
+    "volume",
+];
+
+/// Properties the player monitor polls for changes (mpv doesn't push them to us)
+pub const OBSERVED_PROPERTIES: &[&str] = &[
+    "cache-buffering-state",
+    "chapter",
+    "idle-active",
+    "mute",
+    "path",
+    "paused-for-cache",
+    "time-pos",
+    "track-list",
+    "volume",
+];
+
+/// Decoder state after switching to software decoding
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HwdecFallback {
+    /// `hwdec` before the switch, e.g. "auto-safe"
+    pub previous_hwdec: String,
+    /// Hardware decoder that was in use ("no" = it was already software)
+    pub previous_decoder: String,
+    /// Codec of the current video track, e.g. "hevc"
+    pub codec: Option<String>,
+}
+
+/// Options that have to be passed on the mpv command line
+#[derive(Debug, Clone)]
+pub struct MpvSpawnOptions {
+    /// Load mpv's on-screen controller (`--osc`); when off the frontend provides controls
+    pub osc: bool,
+    /// `--keep-open` value: "yes", "no" or "always"
+    pub keep_open: &'static str,
+    /// Keep the mpv window above other windows (`--ontop`)
+    pub ontop: bool,
+    /// Where the demuxer cache is written when it's on disk (`--demuxer-cache-dir`)
+    pub cache_dir: Option<String>,
+    /// Keep the demuxer cache in a temp file instead of memory (`--cache-on-disk`)
+    pub cache_on_disk: bool,
+    /// Video output driver (`--vo`, None = `DEFAULT_VO`)
+    pub vo: Option<String>,
+    /// How much on-screen info is shown (`--osd-level`, 0-3)
+    pub osd_level: u8,
+    /// How long OSD messages stay up (`--osd-duration`, ms)
+    pub osd_duration_ms: u32,
+}
+
+impl Default for MpvSpawnOptions {
+    fn default() -> Self {
+        Self {
+            osc: true,
+            keep_open: "yes",
+            ontop: false,
+            cache_dir: None,
+            cache_on_disk: false,
+            vo: None,
+            osd_level: DEFAULT_OSD_LEVEL,
+            osd_duration_ms: DEFAULT_OSD_DURATION_MS,
+        }
+    }
+}
+
 /// MPV IPC Client
 pub struct MpvIpc {
     process: Option<Child>,
     pipe: Option<Arc<Mutex<std::fs::File>>>,
     request_id: AtomicU64,
     pipe_name: String,
+    version: Option<MpvVersion>,
+    osc_loaded: bool,
 }
 
 impl MpvIpc {
@@ -101,11 +663,13 @@ impl MpvIpc {
             pipe: None,
             request_id: AtomicU64::new(1),
             pipe_name: get_pipe_name(),
+            version: None,
+            osc_loaded: false,
         }
     }
 
-    /// Start mpv process in fullscreen with OSC (on-screen controls)
-    pub fn start(&mut self) -> Result<(), MpvIpcError> {
+    /// Start mpv process in fullscreen, with OSC (on-screen controls) unless disabled
+    pub fn start(&mut self, options: &MpvSpawnOptions) -> Result<(), MpvIpcError> {
         // Kill any existing process
         self.stop();
 
@@ -115,16 +679,26 @@ impl MpvIpc {
         let mut cmd = Command::new("mpv");
         cmd.arg("--idle=yes")
             .arg(format!("--input-ipc-server={}", self.pipe_name))
-            .arg("--vo=gpu")
-            .arg("--hwdec=auto-safe")
-            .arg("--keep-open=yes")
+            .arg(format!("--vo={}", options.vo.as_deref().unwrap_or(DEFAULT_VO)))
+            .arg(format!("--hwdec={}", DEFAULT_HWDEC))
+            .arg(format!("--keep-open={}", options.keep_open))
             .arg("--cache=yes")
             .arg("--demuxer-max-bytes=150MiB")
             .arg("--demuxer-max-back-bytes=75MiB")
-            // Fullscreen with OSC
+            // Fullscreen, OSC unless the frontend draws its own controls
             .arg("--fullscreen=yes")
-            .arg("--osc=yes")
-            .arg("--title=HubRemote Player");
+            .arg(if options.osc { "--osc=yes" } else { "--no-osc" })
+            .arg(format!("--ontop={}", if options.ontop { "yes" } else { "no" }))
+            .arg(format!("--title={}", DEFAULT_TITLE))
+            .arg(format!("--osd-level={}", options.osd_level))
+            .arg(format!("--osd-duration={}", options.osd_duration_ms));
+
+        if options.cache_on_disk {
+            cmd.arg("--cache-on-disk=yes");
+        }
+        if let Some(dir) = &options.cache_dir {
+            cmd.arg(format!("--demuxer-cache-dir={}", dir));
+        }
 
         cmd.stdin(Stdio::null())
             .stdout(Stdio::null())
@@ -141,11 +715,48 @@ impl MpvIpc {
 
         self.process = Some(child);
         self.connect_with_retry()?;
+        self.detect_version();
+        self.osc_loaded = options.osc;
 
-        log::info!("mpv started in fullscreen mode with OSC");
+        log::info!(
+            "mpv started in fullscreen mode {}",
+            if options.osc { "with OSC" } else { "without OSC" }
+        );
         Ok(())
     }
 
+    /// Query and cache the running mpv version
+    fn detect_version(&mut self) {
+        self.version = self
+            .get_property::<String>("mpv-version")
+            .ok()
+            .and_then(|raw| MpvVersion::parse(&raw));
+
+        match &self.version {
+            Some(version) if version.is_outdated() => log::warn!(
+                "{} is older than the minimum supported mpv {}.{}",
+                version.raw,
+                MIN_SUPPORTED_VERSION.0,
+                MIN_SUPPORTED_VERSION.1
+            ),
+            Some(version) => log::info!("Detected {}", version.raw),
+            None => log::warn!("Could not determine mpv version"),
+        }
+    }
+
+    /// Get the cached mpv version (None until started or if detection failed)
+    pub fn version(&self) -> Option<&MpvVersion> {
+        self.version.as_ref()
+    }
+
+    /// Get version-gated capabilities (all disabled if the version is unknown)
+    pub fn capabilities(&self) -> MpvCapabilities {
+        self.version
+            .as_ref()
+            .map(MpvVersion::capabilities)
+            .unwrap_or_default()
+    }
+
     /// Connect to the IPC socket with retries
     fn connect_with_retry(&mut self) -> Result<(), MpvIpcError> {
         let max_attempts = 50; // 5 seconds total
@@ -214,6 +825,7 @@ impl MpvIpc {
         }
 
         self.pipe = None;
+        self.version = None;
 
         if let Some(mut child) = self.process.take() {
             let _ = child.kill();
@@ -223,6 +835,15 @@ impl MpvIpc {
         log::info!("mpv stopped");
     }
 
+    /// Get the exit status if the mpv process has exited on its own
+    ///
+    /// The pipe is dropped as well so `stop` doesn't try to talk to a dead process.
+    pub fn exit_status(&mut self) -> Option<ExitStatus> {
+        let status = self.process.as_mut()?.try_wait().ok().flatten()?;
+        self.pipe = None;
+        Some(status)
+    }
+
     /// Check if mpv is running
     #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
@@ -231,12 +852,20 @@ impl MpvIpc {
 
     /// Send a command to mpv and get response
     pub fn command(&self, args: &[&str]) -> Result<Value, MpvIpcError> {
+        self.send_request(args.iter().map(|s| json!(s)).collect())
+    }
+
+    /// Send a request and wait for its response
+    ///
+    /// The pipe stays locked from write until the matching response is read,
+    /// so concurrent callers can't consume (and drop) each other's responses.
+    fn send_request(&self, command: Vec<Value>) -> Result<Value, MpvIpcError> {
         let pipe = self.pipe.as_ref().ok_or(MpvIpcError::NotRunning)?;
 
         let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
 
         let request = IpcRequest {
-            command: args.iter().map(|s| json!(s)).collect(),
+            command,
             request_id,
         };
 
@@ -246,36 +875,29 @@ impl MpvIpc {
 
         log::debug!("Sending mpv command: {}", json_str.trim());
 
-        // Send command
-        {
-            let mut pipe_guard = pipe
-                .lock()
-                .map_err(|e| MpvIpcError::SendError(format!("Lock error: {}", e)))?;
+        let mut pipe_guard = pipe
+            .lock()
+            .map_err(|e| MpvIpcError::SendError(format!("Lock error: {}", e)))?;
+        // Under the lock, so the log keeps each command next to its response
+        record_ipc_line("sent", &json_str);
 
-            pipe_guard
-                .write_all(json_str.as_bytes())
-                .map_err(|e| MpvIpcError::SendError(format!("Write error: {}", e)))?;
+        pipe_guard
+            .write_all(json_str.as_bytes())
+            .map_err(|e| MpvIpcError::SendError(format!("Write error: {}", e)))?;
 
-            pipe_guard
-                .flush()
-                .map_err(|e| MpvIpcError::SendError(format!("Flush error: {}", e)))?;
-        }
+        pipe_guard
+            .flush()
+            .map_err(|e| MpvIpcError::SendError(format!("Flush error: {}", e)))?;
 
         log::debug!("Command sent, waiting for response...");
 
         // Read response
-        self.read_response(request_id)
+        Self::read_response(&pipe_guard, request_id)
     }
 
     /// Read response for a specific request
-    fn read_response(&self, expected_id: u64) -> Result<Value, MpvIpcError> {
-        let pipe = self.pipe.as_ref().ok_or(MpvIpcError::NotRunning)?;
-
-        let pipe_guard = pipe
-            .lock()
-            .map_err(|e| MpvIpcError::ReceiveError(format!("Lock error: {}", e)))?;
-
-        let mut reader = BufReader::new(&*pipe_guard);
+    fn read_response(pipe: &std::fs::File, expected_id: u64) -> Result<Value, MpvIpcError> {
+        let mut reader = BufReader::new(pipe);
         let mut line = String::new();
 
         // Read lines until we get our response
@@ -283,11 +905,17 @@ impl MpvIpc {
             line.clear();
             match reader.read_line(&mut line) {
                 Ok(0) => {
-                    log::error!("EOF reached while waiting for response");
+                    crate::errors::report(
+                        Subsystem::Mpv,
+                        "ipc_eof",
+                        "mpv closed the IPC connection".to_string(),
+                        true,
+                    );
                     return Err(MpvIpcError::ReceiveError("EOF reached".to_string()));
                 }
                 Ok(_) => {
                     log::debug!("Received from mpv (attempt {}): {}", attempt, line.trim());
+                    record_ipc_line("received", &line);
                     // Try to parse as response
                     if let Ok(response) = serde_json::from_str::<IpcResponse>(&line) {
                         if response.request_id == expected_id {
@@ -325,31 +953,7 @@ impl MpvIpc {
         let value_json = serde_json::to_value(value)
             .map_err(|e| MpvIpcError::SendError(format!("Failed to serialize value: {}", e)))?;
 
-        let pipe = self.pipe.as_ref().ok_or(MpvIpcError::NotRunning)?;
-        let request_id = self.request_id.fetch_add(1, Ordering::SeqCst);
-
-        let request = json!({
-            "command": ["set_property", name, value_json],
-            "request_id": request_id
-        });
-
-        let mut json_str = serde_json::to_string(&request)
-            .map_err(|e| MpvIpcError::SendError(format!("Failed to serialize: {}", e)))?;
-        json_str.push('\n');
-
-        {
-            let mut pipe_guard = pipe
-                .lock()
-                .map_err(|e| MpvIpcError::SendError(format!("Lock error: {}", e)))?;
-
-            pipe_guard
-                .write_all(json_str.as_bytes())
-                .map_err(|e| MpvIpcError::SendError(format!("Write error: {}", e)))?;
-
-            pipe_guard.flush()?;
-        }
-
-        self.read_response(request_id)?;
+        self.send_request(vec![json!("set_property"), json!(name), value_json])?;
         Ok(())
     }
 
@@ -365,6 +969,78 @@ impl MpvIpc {
         Ok(())
     }
 
+    /// Load a file with per-file options (e.g. "start=42,aid=2")
+    ///
+    /// Per-file options only apply to this file, unlike setting the global property.
+    pub fn load_file_with_file_options(&self, path: &str, options: &str) -> Result<(), MpvIpcError> {
+        log::info!("Loading file: {} ({})", path, options);
+        if self.capabilities().loadfile_index {
+            self.command(&["loadfile", path, "replace", "-1", options])?;
+        } else {
+            self.command(&["loadfile", path, "replace", options])?;
+        }
+        Ok(())
+    }
+
+    /// Add a file to the end of the playlist, starting it if nothing plays
+    pub fn append_file(&self, path: &str) -> Result<(), MpvIpcError> {
+        self.command(&["loadfile", path, "append-play"])?;
+        Ok(())
+    }
+
+    /// Get the playlist entries in order
+    pub fn get_playlist(&self) -> Result<Vec<PlaylistEntry>, MpvIpcError> {
+        Ok(self.get_property("playlist").unwrap_or_default())
+    }
+
+    /// Reload the current file at the current position and tracks
+    ///
+    /// Returns the restored position, or an error if nothing is loaded.
+    pub fn reload_current(&self) -> Result<f64, MpvIpcError> {
+        let path = self
+            .get_path()?
+            .ok_or_else(|| MpvIpcError::MpvError("No file loaded".to_string()))?;
+        let position = self.get_position()?;
+
+        let mut options = vec![format!("start={}", position)];
+        for track in ["aid", "sid"] {
+            if let Ok(value) = self.get_property::<Value>(track) {
+                match value {
+                    Value::Number(id) => options.push(format!("{}={}", track, id)),
+                    Value::Bool(false) => options.push(format!("{}=no", track)),
+                    _ => {}
+                }
+            }
+        }
+
+        self.load_file_with_file_options(&path, &options.join(","))?;
+        Ok(position)
+    }
+
+    /// Set the HTTP User-Agent and Referer for network streams (None = mpv default)
+    pub fn set_http_identity(
+        &self,
+        user_agent: Option<&str>,
+        referrer: Option<&str>,
+    ) -> Result<(), MpvIpcError> {
+        self.set_property("user-agent", user_agent.unwrap_or(DEFAULT_USER_AGENT))?;
+        self.set_property("referrer", referrer.unwrap_or(""))
+    }
+
+    /// Force the FFmpeg demuxer and format used for the next loads (None = detect)
+    pub fn set_force_format(&self, format: Option<&str>) -> Result<(), MpvIpcError> {
+        match format {
+            Some(format) => {
+                self.set_property("demuxer", "lavf")?;
+                self.set_property("demuxer-lavf-format", format)
+            }
+            None => {
+                self.set_property("demuxer", "")?;
+                self.set_property("demuxer-lavf-format", "")
+            }
+        }
+    }
+
     /// Pause playback
     pub fn pause(&self) -> Result<(), MpvIpcError> {
         self.set_property("pause", true)
@@ -393,6 +1069,11 @@ impl MpvIpc {
         Ok(())
     }
 
+    /// Check if the loaded content can be seeked (false when nothing is loaded)
+    pub fn is_seekable(&self) -> Result<bool, MpvIpcError> {
+        self.get_property("seekable").or(Ok(false))
+    }
+
     /// Seek relative (seconds, can be negative)
     pub fn seek_relative(&self, offset: f64) -> Result<(), MpvIpcError> {
         self.command(&["seek", &offset.to_string(), "relative"])?;
@@ -435,11 +1116,73 @@ impl MpvIpc {
         self.get_property("pause").or(Ok(true))
     }
 
+    /// Check if nothing is playing: no file loaded, or stopped at the end (keep-open)
+    pub fn is_idle(&self) -> Result<bool, MpvIpcError> {
+        let idle = self.get_property::<bool>("idle-active").unwrap_or(false);
+        let eof = self.get_property::<bool>("eof-reached").unwrap_or(false);
+        Ok(idle || eof)
+    }
+
+    /// Get the player status; errors if mpv doesn't answer
+    pub fn get_status(&self) -> Result<PlayerStatus, MpvIpcError> {
+        if self.get_property::<bool>("idle-active")? {
+            return Ok(PlayerStatus::Idle);
+        }
+        // keep-open pauses at the end, so this comes before the pause check
+        if self.get_property::<bool>("eof-reached").unwrap_or(false) {
+            return Ok(PlayerStatus::Ended);
+        }
+        if self.get_property::<bool>("pause")? {
+            Ok(PlayerStatus::Paused)
+        } else {
+            Ok(PlayerStatus::Playing)
+        }
+    }
+
     /// Check if muted
     pub fn is_muted(&self) -> Result<bool, MpvIpcError> {
         self.get_property("mute").or(Ok(false))
     }
 
+    /// Get the path or URL of the loaded file (None when idle)
+    pub fn get_path(&self) -> Result<Option<String>, MpvIpcError> {
+        Ok(self.get_property::<String>("path").ok())
+    }
+
+    /// Get the chapters of the loaded file (empty if it has none)
+    pub fn get_chapters(&self) -> Result<Vec<Chapter>, MpvIpcError> {
+        Ok(self.get_property("chapter-list").unwrap_or_default())
+    }
+
+    /// Get the current chapter index (None without chapters or before the first one)
+    pub fn get_chapter(&self) -> Result<Option<i64>, MpvIpcError> {
+        Ok(self
+            .get_property::<i64>("chapter")
+            .ok()
+            .filter(|chapter| *chapter >= 0))
+    }
+
+    /// Jump to a chapter by index
+    pub fn set_chapter(&self, index: i64) -> Result<(), MpvIpcError> {
+        self.set_property("chapter", index)
+    }
+
+    /// Seek to the end of the file (skips trailing credits)
+    pub fn seek_to_end(&self) -> Result<(), MpvIpcError> {
+        self.command(&["seek", "100", "absolute-percent"])?;
+        Ok(())
+    }
+
+    /// Show or hide subtitles without changing the selected track
+    pub fn set_subtitle_visibility(&self, visible: bool) -> Result<(), MpvIpcError> {
+        self.set_property("sub-visibility", visible)
+    }
+
+    /// Check if subtitles are shown (the track may still be off)
+    pub fn get_subtitle_visibility(&self) -> Result<bool, MpvIpcError> {
+        self.get_property("sub-visibility")
+    }
+
     /// Set audio track by index
     pub fn set_audio_track(&self, index: i64) -> Result<(), MpvIpcError> {
         self.set_property("aid", index)
@@ -454,6 +1197,43 @@ impl MpvIpc {
         }
     }
 
+    /// Get the tracks of the loaded file (empty when nothing is loaded)
+    pub fn get_track_list(&self) -> Result<Vec<MpvTrack>, MpvIpcError> {
+        Ok(self.get_property("track-list").unwrap_or_default())
+    }
+
+    /// Set the languages mpv picks audio/subtitle tracks by when a file loads
+    /// (comma-separated priority lists, empty = mpv default)
+    pub fn set_track_languages(&self, alang: &str, slang: &str) -> Result<(), MpvIpcError> {
+        self.set_property("alang", alang)?;
+        self.set_property("slang", slang)
+    }
+
+    /// Switch to the next audio track. Returns the selected track.
+    pub fn cycle_audio_track(&self) -> Result<Option<MpvTrack>, MpvIpcError> {
+        self.cycle_track("aid", "audio")
+    }
+
+    /// Switch to the next subtitle track, including off after the last one.
+    /// Returns the selected track (None = subtitles off).
+    pub fn cycle_subtitle_track(&self) -> Result<Option<MpvTrack>, MpvIpcError> {
+        self.cycle_track("sid", "sub")
+    }
+
+    fn cycle_track(&self, property: &str, kind: &str) -> Result<Option<MpvTrack>, MpvIpcError> {
+        self.command(&["cycle", property])?;
+        Ok(self
+            .get_track_list()?
+            .into_iter()
+            .find(|track| track.kind == kind && track.selected))
+    }
+
+    /// Load an external subtitle file and select it
+    pub fn sub_add(&self, path: &str) -> Result<(), MpvIpcError> {
+        self.command(&["sub-add", path, "select"])?;
+        Ok(())
+    }
+
     /// Set playback speed (1.0 = normal)
     pub fn set_speed(&self, speed: f64) -> Result<(), MpvIpcError> {
         self.set_property("speed", speed.clamp(0.1, 4.0))
@@ -473,6 +1253,61 @@ impl MpvIpc {
         })
     }
 
+    /// Get the loaded file's path, title, tags and playlist entry
+    pub fn get_current_media(&self) -> Result<CurrentMedia, MpvIpcError> {
+        // -1 when nothing is loaded
+        let playlist_index = self
+            .get_property::<i64>("playlist-pos")
+            .ok()
+            .filter(|index| *index >= 0);
+        let playlist_title = playlist_index.and_then(|index| {
+            self.get_property::<String>(&format!("playlist/{}/title", index))
+                .ok()
+        });
+
+        Ok(CurrentMedia {
+            path: self.get_path()?,
+            media_title: self.get_property::<String>("media-title").ok(),
+            metadata: self.get_property("metadata").unwrap_or_default(),
+            playlist_index,
+            playlist_count: self.get_property("playlist-count").unwrap_or(0),
+            playlist_title,
+        })
+    }
+
+    /// Get position, duration, remaining time and percentage in one call
+    pub fn get_progress_detail(&self) -> Result<ProgressDetail, MpvIpcError> {
+        let position = self.get_position()?;
+        // Unavailable for live streams and while nothing is loaded
+        let duration = self
+            .get_property::<f64>("duration")
+            .ok()
+            .filter(|duration| *duration > 0.0);
+
+        let (remaining, percent) = match duration {
+            Some(duration) => {
+                // playtime-remaining accounts for the playback speed
+                let remaining = self
+                    .get_property::<f64>("playtime-remaining")
+                    .unwrap_or(duration - position)
+                    .max(0.0);
+                let percent = self
+                    .get_property::<f64>("percent-pos")
+                    .unwrap_or(position / duration * 100.0)
+                    .clamp(0.0, 100.0);
+                (Some(remaining), Some(percent))
+            }
+            None => (None, None),
+        };
+
+        Ok(ProgressDetail {
+            position,
+            duration,
+            remaining,
+            percent,
+        })
+    }
+
     /// Toggle fullscreen mode
     pub fn toggle_fullscreen(&self) -> Result<(), MpvIpcError> {
         self.command(&["cycle", "fullscreen"])?;
@@ -484,6 +1319,255 @@ impl MpvIpc {
         self.set_property("fullscreen", fullscreen)
     }
 
+    /// Set screenshot format, output directory and filename template
+    pub fn set_screenshot_options(
+        &self,
+        format: &str,
+        directory: Option<&str>,
+        template: Option<&str>,
+    ) -> Result<(), MpvIpcError> {
+        self.set_property("screenshot-format", format)?;
+        self.set_property("screenshot-directory", directory.unwrap_or(""))?;
+        self.set_property("screenshot-template", template.unwrap_or("mpv-shot%n"))
+    }
+
+    /// Take a screenshot of the current frame (with subtitles)
+    pub fn screenshot(&self) -> Result<(), MpvIpcError> {
+        self.command(&["screenshot", "subtitles"])?;
+        Ok(())
+    }
+
+    /// Set the output channel layout (`audio-channels`), e.g. "stereo", "5.1" or "auto-safe"
+    pub fn set_audio_channels(&self, layout: &str) -> Result<(), MpvIpcError> {
+        self.set_property("audio-channels", layout)
+    }
+
+    /// Set the subtitle size. With `by_window` mpv also scales subtitles with
+    /// the window size.
+    pub fn set_subtitle_size(
+        &self,
+        scale: f64,
+        font_size: u32,
+        by_window: bool,
+    ) -> Result<(), MpvIpcError> {
+        self.set_property("sub-scale", scale)?;
+        self.set_property("sub-font-size", font_size)?;
+        self.set_property("sub-scale-by-window", by_window)
+    }
+
+    /// Set how HDR video is mapped to the display's range ("auto" = mpv default)
+    pub fn set_tone_mapping(&self, mode: &str) -> Result<(), MpvIpcError> {
+        self.set_property("tone-mapping", mode)
+    }
+
+    /// Get the audio delay in seconds (positive = audio later)
+    pub fn get_audio_delay(&self) -> Result<f64, MpvIpcError> {
+        self.get_property("audio-delay").or(Ok(0.0))
+    }
+
+    /// Set the audio delay in seconds
+    pub fn set_audio_delay(&self, seconds: f64) -> Result<(), MpvIpcError> {
+        self.set_property("audio-delay", seconds)
+    }
+
+    /// Get the subtitle delay in seconds (positive = subtitles later)
+    pub fn get_subtitle_delay(&self) -> Result<f64, MpvIpcError> {
+        self.get_property("sub-delay").or(Ok(0.0))
+    }
+
+    /// Set the subtitle delay in seconds
+    pub fn set_subtitle_delay(&self, seconds: f64) -> Result<(), MpvIpcError> {
+        self.set_property("sub-delay", seconds)
+    }
+
+    /// Show a message on the OSD for `duration_ms`
+    pub fn show_text(&self, text: &str, duration_ms: u32) -> Result<(), MpvIpcError> {
+        self.command(&["show-text", text, &duration_ms.to_string()])?;
+        Ok(())
+    }
+
+    /// Show the progress bar and position on the OSD
+    pub fn show_progress(&self) -> Result<(), MpvIpcError> {
+        self.command(&["show-progress"])?;
+        Ok(())
+    }
+
+    /// Set where the demuxer cache goes (takes effect from the next file)
+    pub fn set_cache_location(&self, dir: Option<&str>, on_disk: bool) -> Result<(), MpvIpcError> {
+        self.set_property("cache-on-disk", on_disk)?;
+        // An empty directory means mpv's default location
+        self.set_property("demuxer-cache-dir", dir.unwrap_or(""))
+    }
+
+    /// Switch the current video to software decoding
+    pub fn software_decode_fallback(&self) -> Result<HwdecFallback, MpvIpcError> {
+        let previous_hwdec: String = self.get_property("hwdec")?;
+        // Unavailable while nothing is decoded
+        let previous_decoder = self
+            .get_property::<String>("hwdec-current")
+            .unwrap_or_else(|_| "no".to_string());
+        let codec = self
+            .get_track_list()?
+            .into_iter()
+            .find(|track| track.kind == "video" && track.selected)
+            .and_then(|track| track.codec);
+
+        self.set_property("hwdec", "no")?;
+        Ok(HwdecFallback {
+            previous_hwdec,
+            previous_decoder,
+            codec,
+        })
+    }
+
+    /// Hardware-decode every default codec except `excluded`, and go back to
+    /// the spawn-time `hwdec` mode
+    pub fn set_software_decode_codecs(&self, excluded: &[String]) -> Result<(), MpvIpcError> {
+        let codecs: Vec<&str> = HWDEC_CODECS
+            .iter()
+            .copied()
+            .filter(|codec| !excluded.iter().any(|e| e.eq_ignore_ascii_case(codec)))
+            .collect();
+        self.set_property("hwdec-codecs", codecs.join(","))?;
+        self.set_property("hwdec", self.default_hwdec())
+    }
+
+    /// The spawn-time `hwdec` mode, or plain "auto" on mpv too old for "auto-safe"
+    fn default_hwdec(&self) -> &'static str {
+        if self.capabilities().hwdec_auto_safe {
+            DEFAULT_HWDEC
+        } else {
+            "auto"
+        }
+    }
+
+    /// Switch the video output driver (`vo`); mpv re-creates the video window
+    pub fn set_video_output(&self, name: &str) -> Result<(), MpvIpcError> {
+        self.set_property("vo", name)
+    }
+
+    /// Add or remove the night mode audio filter, leaving other filters alone
+    pub fn set_night_mode(&self, enabled: bool) -> Result<(), MpvIpcError> {
+        if enabled {
+            // Adding a filter with an existing label replaces it
+            self.command(&["af", "add", NIGHT_MODE_FILTER])?;
+        } else {
+            self.command(&["af", "remove", "@nightmode"])?;
+        }
+        Ok(())
+    }
+
+    /// Add or replace the labelled cleanup video filter, or remove it (None)
+    pub fn set_cleanup_filter(&self, graph: Option<&str>) -> Result<(), MpvIpcError> {
+        match graph {
+            Some(graph) => {
+                let filter = format!("{}:lavfi=[{}]", CLEANUP_FILTER_LABEL, graph);
+                self.command(&["vf", "add", &filter])?;
+            }
+            // Removing a label that isn't present is an error in mpv
+            None => {
+                let _ = self.command(&["vf", "remove", CLEANUP_FILTER_LABEL]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set several properties, stopping at the first failure
+    pub fn set_properties(&self, properties: &[(&str, &str)]) -> Result<(), MpvIpcError> {
+        for (name, value) in properties {
+            self.set_property(name, *value)?;
+        }
+        Ok(())
+    }
+
+    /// Toggle mpv's built-in stats overlay
+    pub fn toggle_stats(&self) -> Result<(), MpvIpcError> {
+        self.command(&["script-binding", "stats/display-stats-toggle"])?;
+        Ok(())
+    }
+
+    /// Show a specific stats overlay page (1-4)
+    pub fn show_stats_page(&self, page: u8) -> Result<(), MpvIpcError> {
+        let binding = format!("stats/display-page-{}", page);
+        self.command(&["script-binding", &binding])?;
+        Ok(())
+    }
+
+    /// Set the window title; mpv expands `${property}` references itself
+    pub fn set_title(&self, template: &str) -> Result<(), MpvIpcError> {
+        self.set_property("title", template)
+    }
+
+    /// Set end-of-file behavior ("yes", "no" or "always")
+    pub fn set_keep_open(&self, mode: &str) -> Result<(), MpvIpcError> {
+        self.set_property("keep-open", mode)
+    }
+
+    /// Set how much on-screen info is shown (0 = none, 3 = always show the status line)
+    pub fn set_osd_level(&self, level: u8) -> Result<(), MpvIpcError> {
+        self.set_property("osd-level", level)
+    }
+
+    /// Set how long OSD messages and the seek bar stay up, in milliseconds
+    pub fn set_osd_duration(&self, duration_ms: u32) -> Result<(), MpvIpcError> {
+        self.set_property("osd-duration", duration_ms)
+    }
+
+    /// Check if the OSC script was loaded at spawn
+    pub fn is_osc_loaded(&self) -> bool {
+        self.osc_loaded
+    }
+
+    /// Show/hide the OSC and set how long it stays visible after mouse movement
+    ///
+    /// Only works if the OSC was loaded at spawn (see `MpvSpawnOptions::osc`).
+    pub fn set_osc_autohide(&self, enabled: bool, hide_delay_ms: u32) -> Result<(), MpvIpcError> {
+        let visibility = if enabled { "auto" } else { "never" };
+        self.command(&["script-message", "osc-visibility", visibility, "no-osd"])?;
+        self.command(&[
+            "change-list",
+            "script-opts",
+            "append",
+            &format!("osc-hidetimeout={}", hide_delay_ms),
+        ])?;
+        Ok(())
+    }
+
+    /// Keep the mpv window above other windows
+    pub fn set_ontop(&self, enabled: bool) -> Result<(), MpvIpcError> {
+        self.set_property("ontop", enabled)
+    }
+
+    /// Minimize or restore the mpv window
+    pub fn set_minimized(&self, minimized: bool) -> Result<(), MpvIpcError> {
+        self.set_property("window-minimized", minimized)
+    }
+
+    /// Get the current window geometry as an mpv `geometry` string
+    ///
+    /// mpv doesn't report the window position, so this is the explicitly set
+    /// `geometry` if there is one, otherwise the window size as "WxH".
+    pub fn get_window_geometry(&self) -> Result<Option<String>, MpvIpcError> {
+        if let Ok(geometry) = self.get_property::<String>("geometry") {
+            if !geometry.is_empty() {
+                return Ok(Some(geometry));
+            }
+        }
+
+        let width = self.get_property::<i64>("osd-width").unwrap_or(0);
+        let height = self.get_property::<i64>("osd-height").unwrap_or(0);
+        if width > 0 && height > 0 {
+            Ok(Some(format!("{}x{}", width, height)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Set the window geometry (e.g. "1280x720" or "1280x720+100+50")
+    pub fn set_window_geometry(&self, geometry: &str) -> Result<(), MpvIpcError> {
+        self.set_property("geometry", geometry)
+    }
+
     /// Check if fullscreen
     pub fn is_fullscreen(&self) -> Result<bool, MpvIpcError> {
         self.get_property("fullscreen").or(Ok(false))
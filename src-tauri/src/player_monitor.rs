@@ -0,0 +1,358 @@
+//! Background player monitor
+//!
+//! A single thread polls mpv for the state changes the IPC client doesn't
+//! get pushed. Every tick it reads the observed properties once and hands
+//! that snapshot to the volume, chapter auto-skip, track layout and
+//! buffering checks, each of which only acts while it's switched on, and
+//! settles the window layout once a new file's tracks are known. Slower
+//! housekeeping runs every few seconds on the same thread, such as
+//! destroying the process after a long idle.
+//! Also notices mpv or the rclone mount exiting unexpectedly and reports it
+//! as a `backend-error`, writes the crash recovery snapshot, and emits
+//! `network-changed` when the LAN IP in stream URLs goes stale.
+//!
+//! With the stall watchdog on, playback whose position stops advancing while
+//! mpv is neither paused nor refilling its cache (e.g. a read wedged on a
+//! flaky mount) emits `playback-stalled` and is optionally kicked back into
+//! motion with a seek or a reload.
+//!
+//! Volume and mute changes are emitted on request so the UI follows changes
+//! made in the mpv window itself. Housekeeping also saves them to the
+//! settings so the next player starts where this one was left.
+
+use crate::commands::{self, CommandResult};
+use crate::errors::{self, Subsystem};
+use crate::mpv::MpvState;
+use crate::mpv_ipc::MpvTrack;
+use crate::{buffering, chapters, rclone, recent, session, tracks};
+use crate::settings::{LastVolume, SettingsState, StallRecovery, StallWatchdog};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often the observed properties are read
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Ticks between housekeeping checks (5s)
+const HOUSEKEEPING_TICKS: u32 = 20;
+
+/// Positions closer than this count as not having moved
+const STALL_EPSILON_SECS: f64 = 0.01;
+
+/// Event payload for `playback-stalled`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackStalled {
+    pub position: f64,
+    /// How long the position has been frozen
+    pub stalled_secs: u64,
+    /// Recovery that was attempted
+    pub recovery: StallRecovery,
+    /// Why the recovery attempt failed, if it did
+    pub error: Option<String>,
+}
+
+/// Last advancing position seen by the stall watchdog and since when
+#[derive(Default)]
+struct StallTracker {
+    position: Option<f64>,
+    since: Option<Instant>,
+}
+
+/// Observed properties read once per tick (`mpv_ipc::OBSERVED_PROPERTIES`)
+#[derive(Debug, Clone, Default)]
+pub struct PlayerSnapshot {
+    pub path: Option<String>,
+    pub volume: Option<i64>,
+    pub muted: Option<bool>,
+    pub chapter: Option<i64>,
+    pub tracks: Vec<MpvTrack>,
+    pub paused_for_cache: bool,
+    /// Cache refill progress (0-100), only while a cached stream plays
+    pub cache_buffering: Option<i64>,
+}
+
+/// Volume/mute watcher state
+pub struct VolumeWatchState {
+    /// Watching was requested by the frontend
+    enabled: AtomicBool,
+}
+
+impl VolumeWatchState {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Default for VolumeWatchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start the monitor thread
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        let mut idle_since: Option<Instant> = None;
+        let mut notified_ip: Option<String> = None;
+        let mut stall = StallTracker::default();
+        let mut volume = VolumeTracker::default();
+        // File whose tracks last decided the window layout
+        let mut layout_path: Option<String> = None;
+        let mut tick: u32 = 0;
+
+        loop {
+            thread::sleep(TICK_INTERVAL);
+            tick = tick.wrapping_add(1);
+
+            let mpv = app.state::<MpvState>();
+            if let Some(snapshot) = read_snapshot(&mpv) {
+                if snapshot.path != layout_path && !snapshot.tracks.is_empty() {
+                    layout_path = snapshot.path.clone();
+                    commands::apply_loaded_media_layout(&app, &mpv, &snapshot.tracks);
+                }
+                check_volume(&app, &mut volume, &snapshot);
+                chapters::check(&app, &mpv, &snapshot);
+                tracks::check(&app, &mpv, &snapshot);
+                buffering::check(&app, &snapshot);
+            }
+
+            if tick % HOUSEKEEPING_TICKS == 0 {
+                housekeeping(&app, &mpv, &mut idle_since, &mut notified_ip, &mut stall);
+            }
+        }
+    });
+}
+
+/// Read the observed properties in one pass (None without a player)
+fn read_snapshot(mpv: &MpvState) -> Option<PlayerSnapshot> {
+    if !mpv.is_initialized() {
+        return None;
+    }
+    mpv.with_player(|ipc| {
+        Ok(PlayerSnapshot {
+            path: ipc.get_path().ok().flatten(),
+            volume: ipc.get_volume().ok(),
+            muted: ipc.is_muted().ok(),
+            chapter: ipc.get_chapter().ok().flatten(),
+            tracks: ipc.get_track_list().unwrap_or_default(),
+            paused_for_cache: ipc.get_property::<bool>("paused-for-cache").unwrap_or(false),
+            cache_buffering: ipc.get_property::<i64>("cache-buffering-state").ok(),
+        })
+    })
+    .ok()
+}
+
+/// Crash checks, session snapshot, volume memory, network change, stall
+/// watchdog and idle shutdown
+fn housekeeping(
+    app: &AppHandle,
+    mpv: &MpvState,
+    idle_since: &mut Option<Instant>,
+    notified_ip: &mut Option<String>,
+    stall: &mut StallTracker,
+) {
+    check_exited_processes(app, mpv);
+    session::snapshot(app);
+    remember_volume(app, mpv);
+    commands::check_network_change(app, notified_ip);
+
+    let settings = app.state::<SettingsState>().get();
+    match settings.stall_watchdog {
+        Some(watchdog) => check_stall(app, mpv, stall, watchdog),
+        None => *stall = StallTracker::default(),
+    }
+
+    let timeout = settings.idle_destroy_timeout_secs.filter(|secs| *secs > 0);
+
+    let Some(timeout) = timeout else {
+        *idle_since = None;
+        return;
+    };
+
+    // Any playback (or no player at all) cancels the countdown
+    if !mpv.is_initialized() || !mpv.is_idle().unwrap_or(false) {
+        *idle_since = None;
+        return;
+    }
+
+    let since = *idle_since.get_or_insert_with(Instant::now);
+    if since.elapsed() >= Duration::from_secs(timeout) {
+        log::info!("mpv idle for {}s, shutting it down", timeout);
+        recent::save_progress(app);
+        session::clear(app);
+        mpv.destroy();
+        *idle_since = None;
+        let _ = app.emit("player-idle-destroyed", timeout);
+    }
+}
+
+/// Save the player's volume and mute state for the next player, if changed
+pub(crate) fn remember_volume(app: &AppHandle, mpv: &MpvState) {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return;
+    };
+    if !mpv.is_initialized() || settings.get().reset_volume_on_start {
+        return;
+    }
+    let (Ok(volume), Ok(muted)) = (mpv.get_volume(), mpv.is_muted()) else {
+        return;
+    };
+
+    let current = LastVolume { volume, muted };
+    if settings.get().last_volume == Some(current) {
+        return;
+    }
+    if let Err(e) = settings.update(|s| s.last_volume = Some(current)) {
+        log::warn!("Failed to save volume: {}", e);
+    }
+}
+
+/// Playback position if it should be advancing: a file is playing, not
+/// paused and not waiting for the cache to refill
+fn advancing_position(mpv: &MpvState) -> Option<f64> {
+    if !mpv.is_initialized() {
+        return None;
+    }
+    mpv.with_player(|ipc| {
+        let waiting = ipc.is_idle()?
+            || ipc.is_paused()?
+            || ipc.get_property::<bool>("paused-for-cache").unwrap_or(false);
+        if waiting {
+            return Ok(None);
+        }
+        Ok(ipc.get_property::<f64>("time-pos").ok())
+    })
+    .ok()
+    .flatten()
+}
+
+/// Emit `playback-stalled` and attempt recovery once the position has been
+/// frozen for the watchdog's timeout
+fn check_stall(
+    app: &AppHandle,
+    mpv: &MpvState,
+    tracker: &mut StallTracker,
+    watchdog: StallWatchdog,
+) {
+    let Some(position) = advancing_position(mpv) else {
+        *tracker = StallTracker::default();
+        return;
+    };
+
+    let moved = tracker
+        .position
+        .is_none_or(|last| (position - last).abs() > STALL_EPSILON_SECS);
+    if moved {
+        tracker.position = Some(position);
+        tracker.since = Some(Instant::now());
+        return;
+    }
+
+    let stalled = tracker.since.map(|since| since.elapsed()).unwrap_or_default();
+    if stalled < Duration::from_secs(watchdog.timeout_secs) {
+        return;
+    }
+
+    log::warn!(
+        "Playback stalled at {:.1}s for {}s, recovering with {:?}",
+        position,
+        stalled.as_secs(),
+        watchdog.recovery
+    );
+    let result = match watchdog.recovery {
+        StallRecovery::Notify => Ok(()),
+        StallRecovery::Seek => mpv.seek(position),
+        StallRecovery::Reload => mpv.reload_current().map(|_| ()),
+    };
+    let error = result.err().map(|e| e.to_string());
+    if let Some(e) = &error {
+        log::warn!("Stall recovery failed: {}", e);
+    }
+
+    let _ = app.emit(
+        "playback-stalled",
+        PlaybackStalled {
+            position,
+            stalled_secs: stalled.as_secs(),
+            recovery: watchdog.recovery,
+            error,
+        },
+    );
+
+    // Give the recovery a full timeout before trying again
+    tracker.since = Some(Instant::now());
+}
+
+/// Report mpv or the rclone mount having exited on their own
+fn check_exited_processes(app: &AppHandle, mpv: &MpvState) {
+    // A clean exit is the user closing the mpv window; only crashes are errors
+    if let Some(status) = mpv.reap_exited() {
+        if status.success() {
+            session::clear(app);
+        } else {
+            errors::report(
+                Subsystem::Mpv,
+                "process_crashed",
+                format!("mpv exited unexpectedly ({})", status),
+                true,
+            );
+        }
+    }
+
+    if let Some(status) = rclone::reap_mount_process() {
+        errors::report(
+            Subsystem::Rclone,
+            "mount_dropped",
+            format!("rclone mount process exited ({})", status),
+            true,
+        );
+    }
+}
+
+/// Last volume/mute reported while watching (None = no baseline yet)
+#[derive(Default)]
+struct VolumeTracker {
+    volume: Option<i64>,
+    muted: Option<bool>,
+}
+
+/// Emit `volume-changed`/`mute-changed` for changes since the last tick
+fn check_volume(app: &AppHandle, tracker: &mut VolumeTracker, snapshot: &PlayerSnapshot) {
+    if !app.state::<VolumeWatchState>().enabled.load(Ordering::SeqCst) {
+        // The first reading after enabling is the baseline
+        *tracker = VolumeTracker::default();
+        return;
+    }
+
+    if let Some(volume) = snapshot.volume {
+        if tracker.volume.is_some_and(|last| last != volume) {
+            let _ = app.emit("volume-changed", volume);
+        }
+        tracker.volume = Some(volume);
+    }
+
+    if let Some(muted) = snapshot.muted {
+        if tracker.muted.is_some_and(|last| last != muted) {
+            let _ = app.emit("mute-changed", muted);
+        }
+        tracker.muted = Some(muted);
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Start/stop emitting `volume-changed` and `mute-changed` events
+///
+/// Only changes are emitted; the first reading after enabling is the baseline.
+#[tauri::command]
+pub fn watch_volume_changes(state: State<VolumeWatchState>, enabled: bool) -> CommandResult<bool> {
+    state.enabled.store(enabled, Ordering::SeqCst);
+    CommandResult::ok(enabled)
+}
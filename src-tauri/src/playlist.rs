@@ -0,0 +1,197 @@
+//! M3U playlist import/export
+//!
+//! Saves mpv's playlist as an extended M3U (`#EXTINF` titles) that other
+//! players can open, and queues the entries of an M3U file. Relative entries
+//! in a playlist are resolved against the playlist's own folder; URLs are
+//! passed through unchanged.
+
+use crate::commands::{ensure_player, CommandResult};
+use crate::mpv::MpvState;
+use crate::mpv_ipc::PlaylistEntry;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+/// An entry read from an M3U file
+#[derive(Debug, Clone)]
+struct M3uEntry {
+    /// Absolute path or URL
+    location: String,
+    title: Option<String>,
+}
+
+/// What `load_playlist_file` queued
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistLoad {
+    pub queued: usize,
+    /// Local files listed in the playlist that don't exist
+    pub missing: Vec<String>,
+}
+
+/// `scheme://...` (http, smb, file, ...) rather than a file path
+fn is_url(location: &str) -> bool {
+    location
+        .split_once("://")
+        .is_some_and(|(scheme, _)| {
+            scheme.len() > 1 && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+')
+        })
+}
+
+/// Parse an M3U/M3U8 playlist, resolving relative paths against `base_dir`
+fn parse_m3u(contents: &str, base_dir: &Path) -> Vec<M3uEntry> {
+    let mut entries = Vec::new();
+    let mut title = None;
+
+    for line in contents.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // "#EXTINF:<duration> [attributes],<title>"
+            title = info
+                .split_once(',')
+                .map(|(_, t)| t.trim().to_string())
+                .filter(|t| !t.is_empty());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let location = if is_url(line) || Path::new(line).is_absolute() {
+            line.to_string()
+        } else {
+            base_dir.join(line).to_string_lossy().into_owned()
+        };
+        entries.push(M3uEntry {
+            location,
+            title: title.take(),
+        });
+    }
+
+    entries
+}
+
+/// Write entries as an extended M3U. With `relative_paths`, files inside the
+/// playlist's folder are written relative to it so the folder can be moved.
+fn write_m3u(entries: &[PlaylistEntry], relative_paths: bool, base_dir: &Path) -> String {
+    let mut out = String::from("#EXTM3U\n");
+
+    for entry in entries {
+        let title = entry
+            .title
+            .as_deref()
+            .unwrap_or_else(|| display_name(&entry.filename));
+        // mpv doesn't know the duration of entries that haven't been played
+        out.push_str(&format!("#EXTINF:-1,{}\n", title.replace(['\r', '\n'], " ")));
+
+        let location = Path::new(&entry.filename)
+            .strip_prefix(base_dir)
+            .ok()
+            .filter(|_| relative_paths && !is_url(&entry.filename))
+            .map(|relative| relative.to_string_lossy().into_owned())
+            .unwrap_or_else(|| entry.filename.clone());
+        out.push_str(&location);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Last path or URL segment, used as the title of untitled entries
+fn display_name(location: &str) -> &str {
+    location
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(location)
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Save mpv's playlist as an `.m3u`/`.m3u8` file. Returns the entry count.
+///
+/// `relative_paths` writes files inside the playlist's folder relative to it.
+#[tauri::command]
+pub fn export_playlist(
+    state: State<MpvState>,
+    output_path: String,
+    relative_paths: Option<bool>,
+) -> CommandResult<usize> {
+    let entries = match state.with_player(|ipc| ipc.get_playlist()) {
+        Ok(entries) => entries,
+        Err(e) => return CommandResult::err(e.to_string()),
+    };
+    if entries.is_empty() {
+        return CommandResult::err("The playlist is empty");
+    }
+
+    let output = Path::new(&output_path);
+    let contents = write_m3u(&entries, relative_paths.unwrap_or(false), &parent_dir(output));
+    if let Err(e) = std::fs::write(output, contents) {
+        return CommandResult::err(format!("Failed to write {}: {}", output_path, e));
+    }
+
+    log::info!("Exported {} playlist entries to {}", entries.len(), output_path);
+    CommandResult::ok(entries.len())
+}
+
+/// Queue the entries of an `.m3u`/`.m3u8` file
+///
+/// Replaces the current playlist unless `append` is set. Missing local files
+/// are skipped and listed in the result.
+#[tauri::command]
+pub fn load_playlist_file(
+    app: AppHandle,
+    state: State<MpvState>,
+    path: String,
+    append: Option<bool>,
+) -> CommandResult<PlaylistLoad> {
+    let playlist = Path::new(&path);
+    let contents = match std::fs::read(playlist) {
+        // Plain .m3u files may be in a legacy encoding; keep what decodes
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) => return CommandResult::err(format!("Failed to read {}: {}", path, e)),
+    };
+
+    let (entries, missing): (Vec<M3uEntry>, Vec<M3uEntry>) =
+        parse_m3u(&contents, &parent_dir(playlist))
+            .into_iter()
+            .partition(|entry| is_url(&entry.location) || Path::new(&entry.location).exists());
+    if entries.is_empty() {
+        return CommandResult::err("The playlist has no playable entries");
+    }
+
+    if let Err(e) = ensure_player(&app, &state) {
+        return CommandResult::err(format!("Failed to initialize player: {}", e));
+    }
+
+    let result = state.with_player(|ipc| {
+        for (i, entry) in entries.iter().enumerate() {
+            if i == 0 && !append.unwrap_or(false) {
+                ipc.load_file(&entry.location)?;
+            } else {
+                ipc.append_file(&entry.location)?;
+            }
+            log::debug!("Queued {:?} ({})", entry.title, entry.location);
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        return CommandResult::err(e.to_string());
+    }
+
+    log::info!("Queued {} entries from {}", entries.len(), path);
+    CommandResult::ok(PlaylistLoad {
+        queued: entries.len(),
+        missing: missing.into_iter().map(|entry| entry.location).collect(),
+    })
+}
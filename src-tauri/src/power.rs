@@ -0,0 +1,366 @@
+//! Pause playback around system sleep
+//!
+//! When enabled, playback is paused just before the system suspends and its
+//! position saved, so waking up doesn't find mpv stuck on a dead network
+//! read. `power-suspend` carries the position for the frontend to report to
+//! Jellyfin. On wake, playback that was auto-paused resumes after a short
+//! delay for the network to come back, and `power-resume` is emitted.
+//!
+//! Suspend notifications come from `WM_POWERBROADCAST` on Windows and
+//! logind's `PrepareForSleep` signal (through `dbus-monitor`) on Linux.
+//! logind doesn't wait for signal listeners, so on Linux a delay inhibitor
+//! (through `systemd-inhibit`) is held while awake and released once
+//! playback is paused. Other platforms have no hook.
+
+use crate::cast::{self, CastTransport};
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use crate::recent;
+use crate::settings::SettingsState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Wait after wake before resuming, so the network and mount can reconnect
+const RESUME_DELAY: Duration = Duration::from_secs(3);
+
+/// Event payload for `power-suspend`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspendEvent {
+    /// Playback was paused for the suspend
+    pub auto_paused: bool,
+    /// Position playback was paused at
+    pub position: Option<f64>,
+}
+
+/// Suspend listener state
+pub struct PowerState {
+    /// The platform listener was started
+    listening: AtomicBool,
+    /// Playback was paused by a suspend and should resume on wake
+    auto_paused: AtomicBool,
+}
+
+impl PowerState {
+    pub fn new() -> Self {
+        Self {
+            listening: AtomicBool::new(false),
+            auto_paused: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Default for PowerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn pause_on_suspend_enabled(app: &AppHandle) -> bool {
+    app.try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().pause_on_suspend)
+}
+
+/// Pause playing media before the system sleeps
+///
+/// Runs on the listener thread before it acknowledges the suspend, so it
+/// must stay quick.
+fn on_suspend(app: &AppHandle) {
+    if !pause_on_suspend_enabled(app) {
+        return;
+    }
+    let (Some(mpv), Some(power)) = (app.try_state::<MpvState>(), app.try_state::<PowerState>())
+    else {
+        return;
+    };
+
+    let playing = mpv.is_initialized()
+        && !mpv.is_idle().unwrap_or(true)
+        && mpv.get_state().is_ok_and(|state| !state.is_paused);
+    if !playing {
+        let _ = app.emit(
+            "power-suspend",
+            SuspendEvent {
+                auto_paused: false,
+                position: None,
+            },
+        );
+        return;
+    }
+
+    if let Err(e) = mpv.pause() {
+        log::warn!("Failed to pause for suspend: {}", e);
+        return;
+    }
+    power.auto_paused.store(true, Ordering::SeqCst);
+    recent::save_progress(app);
+    cast::sync_mirror(app, CastTransport::Pause);
+
+    let position = mpv.get_position().ok();
+    log::info!("System suspending, paused playback at {:?}", position);
+    let _ = app.emit(
+        "power-suspend",
+        SuspendEvent {
+            auto_paused: true,
+            position,
+        },
+    );
+}
+
+/// Resume playback paused by `on_suspend`, after the network had time to return
+fn on_resume(app: &AppHandle) {
+    let Some(power) = app.try_state::<PowerState>() else {
+        return;
+    };
+    if !power.auto_paused.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::sleep(RESUME_DELAY);
+    let mpv = app.state::<MpvState>();
+    // The user may have resumed or stopped playback in the meantime
+    let resumed = mpv.is_initialized()
+        && mpv.get_state().is_ok_and(|state| state.is_paused)
+        && mpv.play().is_ok();
+    if resumed {
+        log::info!("System resumed, continuing playback");
+        cast::sync_mirror(app, CastTransport::Unpause);
+    }
+    let _ = app.emit("power-resume", resumed);
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::OnceLock;
+    use tauri::AppHandle;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        TranslateMessage, MSG, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND, WM_POWERBROADCAST,
+        WNDCLASSW, WS_OVERLAPPED,
+    };
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == WM_POWERBROADCAST {
+            if let Some(app) = APP.get() {
+                match wparam as u32 {
+                    PBT_APMSUSPEND => super::on_suspend(app),
+                    PBT_APMRESUMEAUTOMATIC => {
+                        // Don't hold up the message loop during the resume delay
+                        let app = app.clone();
+                        std::thread::spawn(move || super::on_resume(&app));
+                    }
+                    _ => {}
+                }
+            }
+            return 1;
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    /// Receive power broadcasts on a hidden top-level window (message-only
+    /// windows don't get broadcasts)
+    pub fn listen(app: &AppHandle) -> bool {
+        let _ = APP.set(app.clone());
+
+        std::thread::spawn(|| unsafe {
+            let class_name: Vec<u16> = "HubRemotePowerListener\0".encode_utf16().collect();
+            let mut class: WNDCLASSW = std::mem::zeroed();
+            class.lpfnWndProc = Some(window_proc);
+            class.lpszClassName = class_name.as_ptr();
+            if RegisterClassW(&class) == 0 {
+                log::warn!("Failed to register the power listener window class");
+                return;
+            }
+
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                class_name.as_ptr(),
+                WS_OVERLAPPED,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                log::warn!("Failed to create the power listener window");
+                return;
+            }
+
+            let mut msg: MSG = std::mem::zeroed();
+            while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+        true
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use tauri::AppHandle;
+
+    /// logind announces sleep with `PrepareForSleep(true)` and wake with
+    /// `PrepareForSleep(false)`
+    const MATCH_RULE: &str =
+        "type='signal',interface='org.freedesktop.login1.Manager',member='PrepareForSleep'";
+
+    /// A logind delay lock on sleep, held until dropped
+    ///
+    /// logind waits for it (up to `InhibitDelayMaxSec`) after announcing the
+    /// suspend. `systemd-inhibit` runs `cat` on our stdin pipe, so closing the
+    /// pipe ends both and releases the lock.
+    struct DelayInhibitor(Child);
+
+    impl DelayInhibitor {
+        fn take() -> Option<Self> {
+            let child = Command::new("systemd-inhibit")
+                .args([
+                    "--what=sleep",
+                    "--mode=delay",
+                    "--who=HubRemote",
+                    "--why=Pausing playback",
+                    "cat",
+                ])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            match child {
+                Ok(child) => Some(Self(child)),
+                Err(e) => {
+                    log::warn!("Can't delay suspend (systemd-inhibit: {})", e);
+                    None
+                }
+            }
+        }
+    }
+
+    impl Drop for DelayInhibitor {
+        fn drop(&mut self) {
+            drop(self.0.stdin.take());
+            let _ = self.0.wait();
+        }
+    }
+
+    pub fn listen(app: &AppHandle) -> bool {
+        // Taken before listening, so no announced suspend finds it missing
+        let mut inhibitor = DelayInhibitor::take();
+
+        let child = Command::new("dbus-monitor")
+            .args(["--system", MATCH_RULE])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                log::warn!("Can't watch for suspend (dbus-monitor: {})", e);
+                return false;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return false;
+        };
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                match line.trim() {
+                    "boolean true" => {
+                        super::on_suspend(&app);
+                        // Paused; let the system go to sleep
+                        inhibitor = None;
+                    }
+                    "boolean false" => {
+                        inhibitor = inhibitor.or_else(DelayInhibitor::take);
+                        super::on_resume(&app);
+                    }
+                    _ => {}
+                }
+            }
+            let _ = child.wait();
+            drop(inhibitor);
+            log::warn!("Suspend listener exited");
+        });
+        true
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn listen(_app: &AppHandle) -> bool {
+        false
+    }
+}
+
+/// Start listening for suspend/resume if pausing on suspend is enabled
+///
+/// The listener stays up once started; the setting is checked per event.
+pub fn start(app: &AppHandle) {
+    if !pause_on_suspend_enabled(app) {
+        return;
+    }
+    let Some(power) = app.try_state::<PowerState>() else {
+        return;
+    };
+    if power.listening.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    if platform::listen(app) {
+        log::info!("Listening for system suspend");
+    } else {
+        power.listening.store(false, Ordering::SeqCst);
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Pause playback before the system sleeps and resume it on wake
+///
+/// Supported on Windows and Linux (systemd-logind). Emits `power-suspend`
+/// and `power-resume`.
+#[tauri::command]
+pub fn set_pause_on_suspend(app: AppHandle, enabled: bool) -> CommandResult<bool> {
+    if enabled && cfg!(not(any(windows, target_os = "linux"))) {
+        return CommandResult::err("Pausing on suspend isn't supported on this platform");
+    }
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available");
+    };
+    if let Err(e) = settings.update(|s| s.pause_on_suspend = enabled) {
+        return CommandResult::err(e);
+    }
+
+    start(&app);
+    CommandResult::ok(enabled)
+}
+
+/// Get whether playback is paused on system suspend
+#[tauri::command]
+pub fn get_pause_on_suspend(app: AppHandle) -> CommandResult<bool> {
+    CommandResult::ok(pause_on_suspend_enabled(&app))
+}
@@ -0,0 +1,132 @@
+//! Process privilege checks
+//!
+//! Some features behave differently with admin (Windows) or root (Unix)
+//! rights: adding a firewall rule needs them, while a mount created by an
+//! elevated process is hidden from the user's normal, non-elevated apps.
+//! Privileges can't change while the process runs, so they're detected once.
+
+use crate::commands::CommandResult;
+use crate::rclone;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// How the process's privileges affect a configured feature
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivilegeImpact {
+    /// "firewall" or "mount"
+    pub feature: &'static str,
+    /// The feature needs rights the process doesn't have
+    pub needs_elevation: bool,
+    pub message: String,
+}
+
+/// Whether the app runs elevated and what that means for its features
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivilegeStatus {
+    /// Admin on Windows, root on Unix
+    pub elevated: bool,
+    /// Features affected by the current privileges (empty = nothing to warn about)
+    pub impacts: Vec<PrivilegeImpact>,
+}
+
+#[cfg(windows)]
+fn detect_elevated() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    // The process token says whether UAC elevated it
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut returned = 0u32;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as *mut _,
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+        CloseHandle(token);
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+#[cfg(not(windows))]
+fn detect_elevated() -> bool {
+    extern "C" {
+        fn geteuid() -> u32;
+    }
+    // Always succeeds; the effective user decides what the process may do
+    unsafe { geteuid() == 0 }
+}
+
+/// Whether the process runs as admin/root (detected on first use)
+pub fn is_elevated() -> bool {
+    static ELEVATED: OnceLock<bool> = OnceLock::new();
+    *ELEVATED.get_or_init(detect_elevated)
+}
+
+fn impacts(elevated: bool) -> Vec<PrivilegeImpact> {
+    let mut impacts = Vec::new();
+    let mount_configured = rclone::last_config().is_some();
+
+    if cfg!(windows) && !elevated {
+        impacts.push(PrivilegeImpact {
+            feature: "firewall",
+            needs_elevation: true,
+            message: "Adding a firewall rule for the streaming server needs administrator \
+                      rights."
+                .to_string(),
+        });
+    }
+
+    if mount_configured && elevated {
+        let message = if cfg!(windows) {
+            "Drives mounted while HubRemote runs as administrator don't show up in Explorer \
+             or other apps started normally. Run HubRemote without admin rights to share \
+             the mount."
+        } else {
+            "Mounts created as root are only accessible to root. Run HubRemote as your own \
+             user to access the mount normally."
+        };
+        impacts.push(PrivilegeImpact {
+            feature: "mount",
+            needs_elevation: false,
+            message: message.to_string(),
+        });
+    }
+
+    impacts
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Report whether the app runs elevated and which features that affects
+///
+/// Read-only; the elevation check runs once per process.
+#[tauri::command]
+pub async fn get_privilege_status() -> CommandResult<PrivilegeStatus> {
+    let task = tauri::async_runtime::spawn_blocking(|| {
+        let elevated = is_elevated();
+        PrivilegeStatus {
+            elevated,
+            impacts: impacts(elevated),
+        }
+    })
+    .await;
+
+    match task {
+        Ok(status) => CommandResult::ok(status),
+        Err(e) => CommandResult::err(format!("Privilege check failed: {}", e)),
+    }
+}
@@ -0,0 +1,280 @@
+//! Media track probing via ffprobe
+//!
+//! Lists the video, audio and subtitle streams of a local or mounted file
+//! without involving mpv, so track options can be shown before casting,
+//! estimates the bitrate a cast target has to sustain, and spots HDR video
+//! that would look washed out on an SDR TV.
+
+use crate::commands::{file_size, CommandResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// A stream as reported by ffprobe
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbedTrack {
+    /// Stream index within the container
+    pub index: u32,
+    pub codec: Option<String>,
+    /// Language tag, e.g. "eng"
+    pub language: Option<String>,
+    pub title: Option<String>,
+    /// Audio channel count
+    pub channels: Option<u32>,
+    /// Audio channel layout, e.g. "5.1(side)"
+    pub channel_layout: Option<String>,
+    /// Video width/height
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Video transfer characteristic, e.g. "bt709" or "smpte2084"
+    pub color_transfer: Option<String>,
+    /// Video uses an HDR transfer (PQ or HLG)
+    pub is_hdr: bool,
+    pub default: bool,
+    pub forced: bool,
+}
+
+/// Tracks of a file grouped by type
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbedTracks {
+    pub video: Vec<ProbedTrack>,
+    pub audio: Vec<ProbedTrack>,
+    pub subtitles: Vec<ProbedTrack>,
+    /// Any video track is HDR
+    pub is_hdr: bool,
+    /// Transfer characteristic of the first HDR video track
+    pub hdr_transfer: Option<String>,
+}
+
+/// Average bitrate of a file, for comparing against measured LAN bandwidth
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamBitrate {
+    /// Average bitrate in Mbps, rounded to 0.1
+    pub mbps: f64,
+    pub bits_per_second: u64,
+    pub file_size: u64,
+    pub duration_secs: Option<f64>,
+    /// "ffprobe" (container bitrate) or "sizeDuration" (file size ÷ duration)
+    pub source: &'static str,
+}
+
+/// `ffprobe -of json` output (only the fields we use)
+#[derive(Debug, Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+/// Container info; ffprobe reports numbers as strings here
+#[derive(Debug, Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeStream {
+    index: u32,
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    channels: Option<u32>,
+    channel_layout: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    color_transfer: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    disposition: HashMap<String, i64>,
+}
+
+impl From<FfprobeStream> for ProbedTrack {
+    fn from(stream: FfprobeStream) -> Self {
+        // Tag keys vary in case between containers
+        let tag = |key: &str| {
+            stream
+                .tags
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.clone())
+        };
+        let flag = |key: &str| stream.disposition.get(key).is_some_and(|v| *v != 0);
+
+        Self {
+            index: stream.index,
+            codec: stream.codec_name.clone(),
+            language: tag("language"),
+            title: tag("title"),
+            channels: stream.channels,
+            channel_layout: stream.channel_layout.clone(),
+            width: stream.width,
+            height: stream.height,
+            is_hdr: stream.color_transfer.as_deref().is_some_and(is_hdr_transfer),
+            color_transfer: stream.color_transfer.clone(),
+            default: flag("default"),
+            forced: flag("forced"),
+        }
+    }
+}
+
+/// Whether a transfer characteristic is HDR: PQ (HDR10, HDR10+, most Dolby
+/// Vision) or HLG
+pub fn is_hdr_transfer(transfer: &str) -> bool {
+    matches!(transfer, "smpte2084" | "arib-std-b67")
+}
+
+/// Build an ffprobe command that doesn't flash a console window on Windows
+fn ffprobe_command() -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new("ffprobe");
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW = 0x08000000
+        cmd.creation_flags(0x08000000);
+    }
+
+    cmd
+}
+
+/// Run ffprobe with `args` on a file and parse its JSON output
+fn run_ffprobe(path: &Path, args: &[&str]) -> Result<FfprobeOutput, String> {
+    if !path.exists() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    let output = ffprobe_command()
+        .args(["-v", "error"])
+        .args(args)
+        .args(["-of", "json"])
+        .arg(path)
+        .output()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                "ffprobe not found. Please install FFmpeg and ensure ffprobe is in your PATH."
+                    .to_string()
+            } else {
+                format!("Failed to run ffprobe: {}", e)
+            }
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ffprobe failed: {}", stderr.trim()));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))
+}
+
+/// Run ffprobe on a file and group its streams by type
+pub fn probe(path: &Path) -> Result<ProbedTracks, String> {
+    let parsed = run_ffprobe(path, &["-show_streams"])?;
+
+    let mut tracks = ProbedTracks::default();
+    for stream in parsed.streams {
+        let list = match stream.codec_type.as_deref() {
+            Some("video") => &mut tracks.video,
+            Some("audio") => &mut tracks.audio,
+            Some("subtitle") => &mut tracks.subtitles,
+            // Data and attachment streams (fonts, cover art metadata)
+            _ => continue,
+        };
+        list.push(stream.into());
+    }
+
+    tracks.hdr_transfer = tracks
+        .video
+        .iter()
+        .find(|track| track.is_hdr)
+        .and_then(|track| track.color_transfer.clone());
+    tracks.is_hdr = tracks.hdr_transfer.is_some();
+    Ok(tracks)
+}
+
+/// HDR transfer characteristic of a file's first video stream (None = SDR or
+/// no video)
+pub fn hdr_transfer(path: &Path) -> Result<Option<String>, String> {
+    let parsed = run_ffprobe(
+        path,
+        &["-select_streams", "v:0", "-show_entries", "stream=index,color_transfer"],
+    )?;
+    Ok(parsed
+        .streams
+        .into_iter()
+        .next()
+        .and_then(|stream| stream.color_transfer)
+        .filter(|transfer| is_hdr_transfer(transfer)))
+}
+
+/// Average bitrate of a file: the container bitrate from ffprobe, or file
+/// size ÷ duration when ffprobe isn't available and `known_duration` is given
+pub fn bitrate(path: &Path, known_duration: Option<f64>) -> Result<StreamBitrate, String> {
+    let size = file_size(path)?;
+    let format = run_ffprobe(path, &["-show_entries", "format=duration,bit_rate"])
+        .map(|output| output.format)
+        .unwrap_or_else(|e| {
+            log::debug!("ffprobe bitrate lookup failed, estimating: {}", e);
+            None
+        });
+
+    let parse = |value: Option<&String>| value.and_then(|v| v.trim().parse::<f64>().ok());
+    let duration = parse(format.as_ref().and_then(|f| f.duration.as_ref()))
+        .or(known_duration)
+        .filter(|d| *d > 0.0);
+    let probed = parse(format.as_ref().and_then(|f| f.bit_rate.as_ref())).filter(|b| *b > 0.0);
+
+    let (bits_per_second, source) = match (probed, duration) {
+        (Some(bits), _) => (bits, "ffprobe"),
+        (None, Some(duration)) => (size as f64 * 8.0 / duration, "sizeDuration"),
+        (None, None) => {
+            return Err(
+                "Can't determine the bitrate: ffprobe is unavailable and the duration is unknown"
+                    .to_string(),
+            )
+        }
+    };
+
+    Ok(StreamBitrate {
+        mbps: (bits_per_second / 100_000.0).round() / 10.0,
+        bits_per_second: bits_per_second as u64,
+        file_size: size,
+        duration_secs: duration,
+        source,
+    })
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Get a file's average bitrate in Mbps to warn before casting over a slow
+/// network
+///
+/// Pass `duration_secs` (e.g. from the server's metadata) so the size-based
+/// estimate works without ffprobe.
+#[tauri::command]
+pub fn get_stream_bitrate(
+    path: String,
+    duration_secs: Option<f64>,
+) -> CommandResult<StreamBitrate> {
+    match bitrate(Path::new(&path), duration_secs) {
+        Ok(bitrate) => CommandResult::ok(bitrate),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// List the video, audio and subtitle streams of a local or mounted file
+#[tauri::command]
+pub fn probe_tracks(path: String) -> CommandResult<ProbedTracks> {
+    match probe(Path::new(&path)) {
+        Ok(tracks) => CommandResult::ok(tracks),
+        Err(e) => CommandResult::err(e),
+    }
+}
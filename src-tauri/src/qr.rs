@@ -0,0 +1,605 @@
+//! QR codes for pairing URLs
+//!
+//! Encodes a stream or web-remote URL as a QR code so a phone or TV browser
+//! can open it by scanning the screen. URLs are short, so this is a minimal
+//! encoder: byte mode at error correction level M, versions 1-10 (up to 213
+//! bytes), rendered as SVG.
+
+use crate::commands::CommandResult;
+use serde::Serialize;
+
+/// Longest URL accepted; version 10-M holds 213 bytes in byte mode
+const MAX_URL_LEN: usize = 200;
+
+/// Largest version the encoder produces
+const MAX_VERSION: usize = 10;
+
+/// Error correction codewords per block at level M, by version
+const ECC_CODEWORDS_PER_BLOCK: [usize; MAX_VERSION] = [10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+
+/// Error correction blocks at level M, by version
+const ECC_BLOCKS: [usize; MAX_VERSION] = [1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+/// Light modules around the symbol, as the spec requires
+const QUIET_ZONE: usize = 4;
+
+/// A rendered QR code
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QrCode {
+    /// Standalone SVG document, scalable to any size
+    pub svg: String,
+    /// Modules per side, without the quiet zone
+    pub modules: usize,
+    pub version: usize,
+}
+
+/// Module grid being built
+struct Symbol {
+    size: usize,
+    modules: Vec<Vec<bool>>,
+    /// Finder, timing, alignment and format modules that data and masks skip
+    function: Vec<Vec<bool>>,
+}
+
+impl Symbol {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        let mut symbol = Self {
+            size,
+            modules: vec![vec![false; size]; size],
+            function: vec![vec![false; size]; size],
+        };
+        symbol.draw_function_patterns(version);
+        symbol
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y][x] = dark;
+        self.function[y][x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            self.draw_finder(x, y);
+        }
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // These corners hold finder patterns
+                let on_finder = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !on_finder {
+                    self.draw_alignment(x, y);
+                }
+            }
+        }
+
+        // Reserve the format areas; the real bits are drawn with the mask
+        self.draw_format_bits(0);
+        if version >= 7 {
+            self.draw_version_bits(version);
+        }
+    }
+
+    /// 7x7 finder with its light separator, clipped at the edges
+    fn draw_finder(&mut self, x: usize, y: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+                    let distance = dx.abs().max(dy.abs());
+                    self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, x: usize, y: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let dark = dx.abs().max(dy.abs()) != 1;
+                self.set_function((x as i32 + dx) as usize, (y as i32 + dy) as usize, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: usize) {
+        let bits = format_bits(mask);
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        // Always dark
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version_bits(&mut self, version: usize) {
+        let data = version as u32;
+        let mut remainder = data;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+        }
+        let bits = (data << 12) | remainder;
+
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Place codewords in the two-column zigzag, skipping function modules
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut bit = 0;
+        let mut right = size - 1;
+
+        loop {
+            // The vertical timing column is skipped entirely
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward { size - 1 - vertical } else { vertical };
+                for x in [right, right - 1] {
+                    if !self.function[y][x] && bit < total_bits {
+                        self.modules[y][x] = (codewords[bit / 8] >> (7 - bit % 8)) & 1 != 0;
+                        bit += 1;
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// XOR a mask pattern over the data modules (applying twice undoes it)
+    fn apply_mask(&mut self, mask: usize) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.function[y][x] {
+                    self.modules[y][x] = !self.modules[y][x];
+                }
+            }
+        }
+    }
+
+    /// Penalty score from the spec; the mask with the lowest one is used
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+
+        for horizontal in [true, false] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| {
+                        if horizontal {
+                            self.modules[a][b]
+                        } else {
+                            self.modules[b][a]
+                        }
+                    })
+                    .collect();
+
+                // Runs of five or more same-colored modules
+                let mut run = 1;
+                for b in 1..size {
+                    if line[b] == line[b - 1] {
+                        run += 1;
+                        penalty += match run {
+                            5 => 3,
+                            r if r > 5 => 1,
+                            _ => 0,
+                        };
+                    } else {
+                        run = 1;
+                    }
+                }
+
+                penalty += finder_like_patterns(&line) * 40;
+            }
+        }
+
+        // 2x2 blocks of one color
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.modules[y][x];
+                if color == self.modules[y][x + 1]
+                    && color == self.modules[y + 1][x]
+                    && color == self.modules[y + 1][x + 1]
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Dark/light imbalance, 10 points per 5% away from half
+        let total = (size * size) as i64;
+        let dark = self.modules.iter().flatten().filter(|dark| **dark).count() as i64;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + (k.max(0) as usize) * 10
+    }
+
+    fn to_svg(&self) -> String {
+        let dimension = self.size + QUIET_ZONE * 2;
+        let mut path = String::new();
+        for (y, row) in self.modules.iter().enumerate() {
+            for (x, dark) in row.iter().enumerate() {
+                if *dark {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE));
+                }
+            }
+        }
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {0} {0}\" \
+             shape-rendering=\"crispEdges\"><rect width=\"{0}\" height=\"{0}\" \
+             fill=\"#ffffff\"/><path d=\"{1}\" fill=\"#000000\"/></svg>",
+            dimension, path
+        )
+    }
+}
+
+/// Count runs in a row or column that scanners could mistake for a finder
+/// pattern: dark-light-dark-light-dark at 1:1:3:1:1 with four widths of light
+/// on one side, at any scale. The quiet zone counts as light.
+fn finder_like_patterns(line: &[bool]) -> usize {
+    let size = line.len();
+    // Alternating run lengths, starting and ending with light
+    let mut runs = vec![size];
+    for &dark in line {
+        if dark == (runs.len() % 2 == 0) {
+            *runs.last_mut().unwrap() += 1;
+        } else {
+            runs.push(1);
+        }
+    }
+    if runs.len() % 2 == 0 {
+        runs.push(size);
+    } else {
+        *runs.last_mut().unwrap() += size;
+    }
+
+    runs.windows(7)
+        .step_by(2)
+        .map(|w| {
+            let n = w[1];
+            let core = w[2] == n && w[3] == n * 3 && w[4] == n && w[5] == n;
+            usize::from(core && w[0] >= n * 4 && w[6] >= n)
+                + usize::from(core && w[6] >= n * 4 && w[0] >= n)
+        })
+        .sum()
+}
+
+/// Error correction level M (format bits 00) and the mask, BCH-protected
+fn format_bits(mask: usize) -> u32 {
+    let data = mask as u32;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    ((data << 10) | remainder) ^ 0x5412
+}
+
+/// Centers of the alignment patterns along each axis
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let size = version * 4 + 17;
+    let step = (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Codewords (data and error correction) that fit in a version
+fn raw_codewords(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_codewords(version) - ECC_CODEWORDS_PER_BLOCK[version - 1] * ECC_BLOCKS[version - 1]
+}
+
+/// Bits of the byte mode character count field
+fn count_bits(version: usize) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Multiply in GF(2^8) modulo the QR polynomial x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1D);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+/// Reed-Solomon generator polynomial of `degree`, highest term omitted
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0u8; degree];
+    divisor[degree - 1] = 1;
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0u8; divisor.len()];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (r, d) in remainder.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(*d, factor);
+        }
+    }
+    remainder
+}
+
+/// Append the low `count` bits of `value`, most significant first
+fn push_bits(bits: &mut Vec<bool>, value: usize, count: usize) {
+    bits.extend((0..count).rev().map(|i| (value >> i) & 1 != 0));
+}
+
+/// Data codewords for `text` in byte mode, padded to the version's capacity
+fn encode_data(text: &[u8], version: usize) -> Vec<u8> {
+    let capacity = data_codewords(version) * 8;
+    let mut bits = Vec::with_capacity(capacity);
+    push_bits(&mut bits, 0b0100, 4);
+    push_bits(&mut bits, text.len(), count_bits(version));
+    for byte in text {
+        push_bits(&mut bits, *byte as usize, 8);
+    }
+
+    // Terminator, then byte alignment
+    let terminator = (capacity - bits.len()).min(4);
+    bits.resize(bits.len() + terminator, false);
+    bits.resize(bits.len().div_ceil(8) * 8, false);
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |byte, bit| (byte << 1) | u8::from(*bit)))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() * 8 >= capacity {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+/// Split data into blocks, add error correction and interleave
+fn add_error_correction(data: &[u8], version: usize) -> Vec<u8> {
+    let blocks = ECC_BLOCKS[version - 1];
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[version - 1];
+    let raw = raw_codewords(version);
+    // Later blocks hold one more data codeword when the split is uneven
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+    let divisor = rs_divisor(ecc_len);
+
+    let mut split = Vec::with_capacity(blocks);
+    let mut offset = 0;
+    for i in 0..blocks {
+        let data_len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let mut block = data[offset..offset + data_len].to_vec();
+        offset += data_len;
+        let ecc = rs_remainder(&block, &divisor);
+        // Placeholder so all blocks line up; skipped when interleaving
+        if i < short_blocks {
+            block.push(0);
+        }
+        block.extend(ecc);
+        split.push(block);
+    }
+
+    let mut interleaved = Vec::with_capacity(raw);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                interleaved.push(block[i]);
+            }
+        }
+    }
+    interleaved
+}
+
+/// Encode `text` as a QR code, or None if it doesn't fit in version 10
+fn encode(text: &[u8]) -> Option<(Symbol, usize)> {
+    let version = (1..=MAX_VERSION)
+        .find(|&v| 4 + count_bits(v) + text.len() * 8 <= data_codewords(v) * 8)?;
+    let codewords = add_error_correction(&encode_data(text, version), version);
+
+    let mut symbol = Symbol::new(version);
+    symbol.draw_codewords(&codewords);
+
+    let mut best = (usize::MAX, 0);
+    for mask in 0..8 {
+        symbol.apply_mask(mask);
+        symbol.draw_format_bits(mask);
+        let penalty = symbol.penalty();
+        if penalty < best.0 {
+            best = (penalty, mask);
+        }
+        symbol.apply_mask(mask);
+    }
+    symbol.apply_mask(best.1);
+    symbol.draw_format_bits(best.1);
+
+    Some((symbol, version))
+}
+
+/// Accept http(s) URLs with a host, no whitespace, up to `MAX_URL_LEN` bytes
+fn validate_url(url: &str) -> Result<&str, String> {
+    let url = url.trim();
+    if url.len() > MAX_URL_LEN {
+        return Err(format!("URL is too long for a QR code (max {} characters)", MAX_URL_LEN));
+    }
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or_else(|| format!("Not an http(s) URL: {}", url))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if host.is_empty() || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Invalid URL: {}", url));
+    }
+    Ok(url)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Render a stream or web-remote URL as a QR code (SVG) for pairing a device
+#[tauri::command]
+pub fn get_url_qr(url: String) -> CommandResult<QrCode> {
+    let url = match validate_url(&url) {
+        Ok(url) => url,
+        Err(e) => return CommandResult::err(e),
+    };
+
+    match encode(url.as_bytes()) {
+        Some((symbol, version)) => CommandResult::ok(QrCode {
+            svg: symbol.to_svg(),
+            modules: symbol.size,
+            version,
+        }),
+        None => CommandResult::err("URL is too long for a QR code"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_the_spec_error_correction_example() {
+        // "01234567" at 1-M, from the worked example in ISO/IEC 18004
+        let data = [
+            0x10, 0x20, 0x0C, 0x56, 0x61, 0x80, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11,
+            0xEC, 0x11,
+        ];
+        assert_eq!(
+            rs_remainder(&data, &rs_divisor(10)),
+            [0xA5, 0x24, 0xD4, 0xC1, 0xED, 0x36, 0xC7, 0x87, 0x2C, 0x55]
+        );
+    }
+
+    #[test]
+    fn computes_format_bits_for_every_mask() {
+        let expected = [0x5412, 0x5125, 0x5E7C, 0x5B4B, 0x45F9, 0x40CE, 0x4F97, 0x4AA0];
+        for (mask, bits) in expected.into_iter().enumerate() {
+            assert_eq!(format_bits(mask), bits, "mask {}", mask);
+        }
+    }
+
+    #[test]
+    fn matches_a_reference_encoder() {
+        // Module matrix from Nayuki's qrcodegen (byte mode, level M, no ECC boost)
+        const EXPECTED: [&str; 29] = [
+            "#######..#.##.#....##.#######",
+            "#.....#...#.#...#.#.#.#.....#",
+            "#.###.#..#..#.##.####.#.###.#",
+            "#.###.#..#..##..#..##.#.###.#",
+            "#.###.#...#.#.......#.#.###.#",
+            "#.....#.####...##.###.#.....#",
+            "#######.#.#.#.#.#.#.#.#######",
+            "............##.####..........",
+            "#..#.##.###.#.#.#.####.#.....",
+            "#...##.#.#.#.#.##.....##.#..#",
+            "###..###..######.#.###.#####.",
+            "....##.....###..#.#.###..###.",
+            ".###.##..####.##.#.#..##.#.##",
+            ".#####..#..#########...#..#..",
+            "##.#..#.##.#.##...#....#..###",
+            "..#.##.#...#...#.#.#.#..##.#.",
+            "#..#..###.#..#...#.#.....#.#.",
+            "..#..#.#..#.#####...###.....#",
+            "#.###.##.##...###....#...#.##",
+            "..#.#....#.##.#..##..#.#...##",
+            "#.#.#.####.##..##...#####.#..",
+            "........###...##....#...#.###",
+            "#######..####...#...#.#.#..#.",
+            "#.....#.#.##..###.###...###.#",
+            "#.###.#...#.....##..######.#.",
+            "#.###.#.#...........###.#...#",
+            "#.###.#....#.#..#..###.###..#",
+            "#.....#.......########...#.#.",
+            "#######.##.#..#.##.###.....#.",
+        ];
+
+        let (symbol, version) = encode(b"http://192.168.1.10:8765/remote").unwrap();
+        assert_eq!(version, 3);
+        let rows: Vec<String> = symbol
+            .modules
+            .iter()
+            .map(|row| row.iter().map(|dark| if *dark { '#' } else { '.' }).collect())
+            .collect();
+        assert_eq!(rows, EXPECTED);
+    }
+}
@@ -3,16 +3,59 @@
 //! Handles automatic mounting/unmounting of cloud storage via rclone.
 //! Supports Google Drive and other rclone-compatible remotes.
 
+use crate::errors::{self, Subsystem};
+use crate::settings;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use std::process::{Child, Command};
-use std::sync::Mutex;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 
 /// Global rclone process handle
 static RCLONE_PROCESS: Mutex<Option<Child>> = Mutex::new(None);
 
+/// Global `rclone serve http` process handle and its port
+static RCLONE_SERVE_PROCESS: Mutex<Option<(Child, u16)>> = Mutex::new(None);
+
+/// Running `rclone copy` process, if any
+static RCLONE_COPY_PROCESS: Mutex<Option<Child>> = Mutex::new(None);
+
+/// Set when the running copy is cancelled, so its exit isn't reported as a failure
+static COPY_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Configuration of the last mount attempt, for settings export
+static LAST_CONFIG: Mutex<Option<RcloneConfig>> = Mutex::new(None);
+
+/// Last stderr lines of the mount process, for classifying failures
+static MOUNT_STDERR: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// How many stderr lines of the mount process to keep
+const MOUNT_STDERR_LINES: usize = 20;
+
+/// Cancel flags of mounts still waiting to become ready, keyed by mount point
+static PENDING_MOUNTS: Mutex<Vec<(String, Arc<AtomicBool>)>> = Mutex::new(Vec::new());
+
+/// Default port for `rclone serve http`
+const DEFAULT_SERVE_PORT: u16 = 8766;
+
+/// Default amount of a file to read when pre-warming the VFS cache
+const DEFAULT_PREWARM_MB: u64 = 32;
+
+/// Default time limit for pre-warming a file
+const DEFAULT_PREWARM_TIMEOUT_SECS: u64 = 15;
+
+/// Tail of the file to read as well, since players look for the index there
+/// (e.g. the MP4 `moov` atom or MKV cues)
+const PREWARM_TAIL_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Default free space below which the cache volume is reported as low
+/// (roughly one large 4K remux)
+const DEFAULT_CACHE_MIN_FREE_GB: u64 = 50;
+
 /// Mount configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +66,8 @@ pub struct RcloneConfig {
     pub mount_point: String,
     pub vfs_cache_mode: String,
     pub auto_mount: bool,
+    /// Upper bound for the VFS cache (`--vfs-cache-max-size`, e.g. "20G")
+    pub vfs_cache_max_size: Option<String>,
 }
 
 impl Default for RcloneConfig {
@@ -34,10 +79,26 @@ impl Default for RcloneConfig {
             mount_point: "G:".to_string(),
             vfs_cache_mode: "full".to_string(),
             auto_mount: true,
+            vfs_cache_max_size: None,
         }
     }
 }
 
+/// Free space on the volume holding the rclone VFS cache
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheDiskSpace {
+    pub cache_dir: String,
+    pub total: Option<u64>,
+    pub used: Option<u64>,
+    pub free: Option<u64>,
+    /// Configured `--vfs-cache-max-size`, if any
+    pub cache_max_size: Option<String>,
+    /// Free space is below the threshold and the cache isn't bounded below it
+    pub low_space: bool,
+    pub warning: Option<String>,
+}
+
 /// Mount status
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -49,12 +110,152 @@ pub struct MountStatus {
     pub error: Option<String>,
 }
 
+/// Storage quota reported by `rclone about` (bytes; None if the backend doesn't report it)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteQuota {
+    pub total: Option<u64>,
+    pub used: Option<u64>,
+    pub free: Option<u64>,
+    pub trashed: Option<u64>,
+    pub other: Option<u64>,
+}
+
+/// Entry returned by `rclone lsjson`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "PascalCase"))]
+pub struct RemoteEntry {
+    /// Path relative to the listed folder
+    pub path: String,
+    pub name: String,
+    /// Size in bytes (-1 for directories or when unknown)
+    pub size: i64,
+    pub is_dir: bool,
+    pub mod_time: Option<String>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+/// Result of pre-warming a file in the VFS cache
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrewarmResult {
+    pub bytes_read: u64,
+    pub target_bytes: u64,
+    pub file_size: u64,
+    /// The target was reached before the timeout
+    pub complete: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Event payload for `rclone-copy-progress`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyProgress {
+    pub transferred_bytes: u64,
+    pub total_bytes: u64,
+    pub percent: u8,
+    /// Estimated seconds remaining, if rclone knows
+    pub eta_secs: Option<u64>,
+}
+
+/// Result of a finished `rclone copy`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyResult {
+    pub destination: String,
+    pub transferred_bytes: u64,
+    pub elapsed_ms: u64,
+}
+
+/// Whether the filesystem driver rclone mount needs is installed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MountPrerequisites {
+    pub available: bool,
+    /// Driver rclone mount depends on (WinFsp, FUSE, macFUSE)
+    pub component: String,
+    /// How to install it when missing
+    pub install_hint: String,
+}
+
+/// Likely cause of a failed mount, so the UI can suggest a fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MountErrorKind {
+    /// rclone isn't installed or not at the configured path
+    RcloneNotFound,
+    /// WinFsp (or FUSE/macFUSE) isn't installed
+    WinFspMissing,
+    /// The remote's OAuth token expired or was revoked (re-run `rclone config`)
+    AuthExpired,
+    /// The remote isn't configured or the folder doesn't exist
+    RemoteNotFound,
+    /// The drive letter or directory is already in use
+    MountPointBusy,
+    /// rclone didn't bring the mount up in time
+    Timeout,
+    Other,
+}
+
+impl MountErrorKind {
+    /// Classify a mount error from our message and rclone's stderr
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let has = |phrases: &[&str]| phrases.iter().any(|phrase| message.contains(phrase));
+
+        if has(&["rclone not found", "failed to start rclone"]) {
+            MountErrorKind::RcloneNotFound
+        } else if has(&[
+            "winfsp",
+            "cgofuse",
+            "fusermount",
+            "fuse: device not found",
+            "macfuse",
+            "osxfuse",
+        ]) {
+            MountErrorKind::WinFspMissing
+        } else if has(&[
+            "invalid_grant",
+            "token expired",
+            "couldn't fetch token",
+            "failed to get token",
+            "unauthorized",
+            "401",
+        ]) {
+            MountErrorKind::AuthExpired
+        } else if has(&[
+            "didn't find section in config file",
+            "directory not found",
+            "not found in config",
+            "no such remote",
+        ]) {
+            MountErrorKind::RemoteNotFound
+        } else if has(&[
+            "already in use",
+            "already mounted",
+            "is not empty",
+            "resource busy",
+            "file exists",
+        ]) {
+            MountErrorKind::MountPointBusy
+        } else if has(&["timeout waiting for mount"]) {
+            MountErrorKind::Timeout
+        } else {
+            MountErrorKind::Other
+        }
+    }
+}
+
 /// Command result type
 #[derive(Serialize)]
 pub struct CommandResult<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Cause of a failed mount (mount commands only)
+    #[serde(rename = "errorKind", skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<MountErrorKind>,
 }
 
 impl<T> CommandResult<T> {
@@ -63,6 +264,7 @@ impl<T> CommandResult<T> {
             success: true,
             data: Some(data),
             error: None,
+            error_kind: None,
         }
     }
 
@@ -71,6 +273,15 @@ impl<T> CommandResult<T> {
             success: false,
             data: None,
             error: Some(error),
+            error_kind: None,
+        }
+    }
+
+    /// Failed mount with its classified cause
+    pub fn mount_err(error: String, kind: MountErrorKind) -> Self {
+        Self {
+            error_kind: Some(kind),
+            ..Self::err(error)
         }
     }
 }
@@ -91,6 +302,156 @@ pub fn is_path_mounted(mount_point: &str) -> bool {
     path.exists()
 }
 
+/// Validate a mount point and normalize it to the form rclone expects
+///
+/// On Windows, drive letters are canonicalized to `X:` (`g:\` becomes `G:`).
+/// On Unix, `~` and relative paths are expanded to absolute paths.
+/// Trailing separators are removed on both.
+pub fn normalize_mount_point(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err("Mount point is empty".to_string());
+    }
+
+    let normalized = normalize_platform_path(trimmed)?;
+
+    if Path::new(&normalized).is_file() {
+        return Err(format!("Mount point {} is a file, not a directory", normalized));
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(windows)]
+fn normalize_platform_path(input: &str) -> Result<String, String> {
+    // "*" lets rclone pick the next free drive letter
+    if input == "*" {
+        return Ok(input.to_string());
+    }
+
+    let path = input.replace('/', "\\");
+    let mut chars = path.chars();
+    let (letter, colon) = (chars.next(), chars.next());
+
+    match (letter, colon) {
+        (Some(letter), Some(':')) if letter.is_ascii_alphabetic() => {
+            let rest = chars.as_str().trim_end_matches('\\');
+            if rest.is_empty() {
+                // Bare drive letter: "g:", "G:\" -> "G:"
+                Ok(format!("{}:", letter.to_ascii_uppercase()))
+            } else if rest.starts_with('\\') {
+                Ok(format!("{}:{}", letter.to_ascii_uppercase(), rest))
+            } else {
+                Err(format!("Invalid mount point: {}", input))
+            }
+        }
+        (Some(letter), None) if letter.is_ascii_alphabetic() => {
+            Ok(format!("{}:", letter.to_ascii_uppercase()))
+        }
+        _ => Err(format!(
+            "Invalid mount point: {} (expected a drive letter like G: or an absolute path)",
+            input
+        )),
+    }
+}
+
+#[cfg(not(windows))]
+fn normalize_platform_path(input: &str) -> Result<String, String> {
+    let expanded = if input == "~" || input.starts_with("~/") {
+        let home = std::env::var_os("HOME").ok_or("Cannot expand ~: HOME is not set")?;
+        PathBuf::from(home).join(input.trim_start_matches('~').trim_start_matches('/'))
+    } else {
+        PathBuf::from(input)
+    };
+
+    let absolute = if expanded.is_absolute() {
+        expanded
+    } else {
+        std::env::current_dir()
+            .map_err(|e| format!("Cannot resolve relative mount point: {}", e))?
+            .join(expanded)
+    };
+
+    let normalized = absolute.to_string_lossy().trim_end_matches('/').to_string();
+    if normalized.is_empty() {
+        return Err("Cannot mount over the root directory".to_string());
+    }
+
+    Ok(normalized)
+}
+
+/// Check that a mount target can be mounted over
+///
+/// rclone on Unix refuses non-empty directories unless `--allow-non-empty` is given.
+fn validate_mount_target(mount_point: &str) -> Result<(), String> {
+    if cfg!(windows) {
+        return Ok(());
+    }
+
+    let path = PathBuf::from(mount_point);
+    if path.is_dir() {
+        let has_entries = std::fs::read_dir(&path)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false);
+        if has_entries {
+            return Err(format!(
+                "Mount point {} is not empty; rclone requires an empty directory",
+                mount_point
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse an rclone size like "20G", "512M" or "1.5T" into bytes (plain numbers are KiB)
+fn parse_rclone_size(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let (number, unit) = match input.char_indices().find(|(_, c)| c.is_ascii_alphabetic()) {
+        Some((index, _)) => input.split_at(index),
+        None => (input, "K"),
+    };
+    let unit = unit.to_ascii_uppercase();
+    let multiplier: u64 = match unit.trim_end_matches('B').trim_end_matches('I') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        "P" => 1 << 50,
+        _ => return None,
+    };
+    let value: f64 = number.trim().parse().ok().filter(|v: &f64| *v >= 0.0)?;
+    Some((value * multiplier as f64) as u64)
+}
+
+/// Normalize the mount point and drop an empty cache size limit
+fn normalize_config(mut config: RcloneConfig) -> Result<RcloneConfig, String> {
+    config.mount_point = normalize_mount_point(&config.mount_point)?;
+    config.vfs_cache_max_size = config
+        .vfs_cache_max_size
+        .map(|size| size.trim().to_string())
+        .filter(|size| !size.is_empty());
+    if let Some(size) = &config.vfs_cache_max_size {
+        if parse_rclone_size(size).is_none() {
+            return Err(format!("Invalid VFS cache size: {} (expected e.g. 20G)", size));
+        }
+    }
+    Ok(config)
+}
+
+/// Remember a mount configuration so it's included in settings exports
+pub fn remember_config(config: &RcloneConfig) {
+    if let Ok(mut last) = LAST_CONFIG.lock() {
+        *last = Some(config.clone());
+    }
+}
+
+/// Get the configuration of the last mount attempt or import
+pub fn last_config() -> Option<RcloneConfig> {
+    LAST_CONFIG.lock().ok().and_then(|last| last.clone())
+}
+
 /// Check if rclone is installed and accessible
 pub fn check_rclone_installed(rclone_path: &str) -> Result<String, String> {
     let output = Command::new(rclone_path)
@@ -113,6 +474,221 @@ pub fn check_rclone_installed(rclone_path: &str) -> Result<String, String> {
     }
 }
 
+/// Build an rclone command that doesn't open a console window on Windows
+fn rclone_command(rclone_path: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(rclone_path);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW = 0x08000000
+        cmd.creation_flags(0x08000000);
+    }
+
+    cmd
+}
+
+/// Run a one-shot rclone command and return its stdout
+fn run_rclone(rclone_path: &str, args: &[&str]) -> Result<String, String> {
+    let output = rclone_command(rclone_path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run rclone: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().last().unwrap_or("unknown error").trim();
+        Err(format!("rclone {} failed: {}", args.first().unwrap_or(&""), message))
+    }
+}
+
+/// Build `remote:folder[/subpath]`, rejecting paths that escape the configured folder
+fn remote_path(config: &RcloneConfig, subpath: Option<&str>) -> Result<String, String> {
+    let folder = config.remote_folder.trim_matches('/');
+    let subpath = subpath.unwrap_or("").trim_matches('/');
+
+    if subpath.split('/').any(|segment| segment == "..") {
+        return Err(format!("Invalid remote path: {}", subpath));
+    }
+
+    let path = match (folder.is_empty(), subpath.is_empty()) {
+        (_, true) => folder.to_string(),
+        (true, false) => subpath.to_string(),
+        (false, false) => format!("{}/{}", folder, subpath),
+    };
+
+    Ok(format!("{}:{}", config.remote_name, path))
+}
+
+/// List the direct children of a remote folder without mounting
+pub fn list_remote(config: &RcloneConfig, subpath: Option<&str>) -> Result<Vec<RemoteEntry>, String> {
+    let remote = remote_path(config, subpath)?;
+    let stdout = run_rclone(&config.rclone_path, &["lsjson", &remote])?;
+
+    let mut entries: Vec<RemoteEntry> = serde_json::from_str(&stdout)
+        .map_err(|e| format!("Failed to parse rclone lsjson output: {}", e))?;
+
+    // Folders first, then by name
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// Size in bytes of a file on the remote, from `rclone lsjson --stat`
+pub fn remote_file_size(config: &RcloneConfig, subpath: &str) -> Result<u64, String> {
+    let remote = remote_path(config, Some(subpath))?;
+    let stdout = run_rclone(&config.rclone_path, &["lsjson", "--stat", &remote])?;
+
+    let entry: RemoteEntry = serde_json::from_str(&stdout)
+        .map_err(|e| format!("Failed to parse rclone lsjson output: {}", e))?;
+    if entry.is_dir {
+        return Err(format!("Not a file: {}", remote));
+    }
+    u64::try_from(entry.size)
+        .map_err(|_| format!("The remote doesn't report a size for {}", remote))
+}
+
+/// Query storage quota for the configured remote
+pub fn get_quota(config: &RcloneConfig) -> Result<RemoteQuota, String> {
+    let remote = format!("{}:", config.remote_name);
+    let stdout = run_rclone(&config.rclone_path, &["about", &remote, "--json"]).map_err(|e| {
+        let lower = e.to_lowercase();
+        if lower.contains("not supported") || lower.contains("doesn't support") {
+            format!("Storage quota is not supported by the {} backend", config.remote_name)
+        } else {
+            e
+        }
+    })?;
+
+    serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse rclone about output: {}", e))
+}
+
+/// Directory rclone keeps its VFS cache in, from `rclone config paths`
+fn cache_dir(rclone_path: &str) -> Result<PathBuf, String> {
+    let stdout = run_rclone(rclone_path, &["config", "paths"])?;
+    stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Cache dir:"))
+        .map(|dir| PathBuf::from(dir.trim()).join("vfs"))
+        .ok_or_else(|| "rclone didn't report its cache directory".to_string())
+}
+
+/// Report free space on the volume holding the VFS cache
+///
+/// Uses `rclone about` on the local path so it works the same on every
+/// platform. `min_free_bytes` is the threshold for `low_space`.
+pub fn cache_disk_space(
+    config: &RcloneConfig,
+    min_free_bytes: u64,
+) -> Result<CacheDiskSpace, String> {
+    let cache_dir = cache_dir(&config.rclone_path)?;
+
+    // The cache directory only exists after the first mount; query the nearest
+    // existing ancestor, which is on the same volume
+    let existing = cache_dir
+        .ancestors()
+        .find(|path| path.is_dir())
+        .ok_or_else(|| format!("No existing directory above {:?}", cache_dir))?;
+    let stdout = run_rclone(
+        &config.rclone_path,
+        &["about", &existing.to_string_lossy(), "--json"],
+    )?;
+    let quota: RemoteQuota = serde_json::from_str(&stdout)
+        .map_err(|e| format!("Failed to parse rclone about output: {}", e))?;
+
+    // A cache limit below the free space keeps the disk from filling up
+    let bounded = config
+        .vfs_cache_max_size
+        .as_deref()
+        .and_then(parse_rclone_size)
+        .zip(quota.free)
+        .is_some_and(|(max, free)| max < free);
+    let low_space = config.vfs_cache_mode == "full"
+        && !bounded
+        && quota.free.is_some_and(|free| free < min_free_bytes);
+    let warning = low_space.then(|| {
+        format!(
+            "Only {:.1} GB free for the rclone cache; large files may fill the disk. \
+             Set a VFS cache size limit or free up space.",
+            quota.free.unwrap_or_default() as f64 / (1u64 << 30) as f64
+        )
+    });
+
+    Ok(CacheDiskSpace {
+        cache_dir: cache_dir.to_string_lossy().into_owned(),
+        total: quota.total,
+        used: quota.used,
+        free: quota.free,
+        cache_max_size: config.vfs_cache_max_size.clone(),
+        low_space,
+        warning,
+    })
+}
+
+/// Check if an executable exists in PATH
+#[cfg(not(any(windows, target_os = "macos")))]
+fn find_in_path(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+/// Detect the filesystem driver rclone mount requires on this platform
+pub fn check_prerequisites() -> MountPrerequisites {
+    #[cfg(windows)]
+    {
+        let program_files = std::env::var("ProgramFiles(x86)")
+            .unwrap_or_else(|_| r"C:\Program Files (x86)".to_string());
+        let dll_installed = Path::new(&program_files)
+            .join(r"WinFsp\bin\winfsp-x64.dll")
+            .exists();
+
+        // Fall back to the registry in case WinFsp was installed elsewhere
+        let registry_installed = || {
+            rclone_command("reg")
+                .args(["query", r"HKLM\SOFTWARE\WOW6432Node\WinFsp", "/v", "InstallDir"])
+                .output()
+                .map(|out| out.status.success())
+                .unwrap_or(false)
+        };
+
+        MountPrerequisites {
+            available: dll_installed || registry_installed(),
+            component: "WinFsp".to_string(),
+            install_hint: "Install WinFsp from https://winfsp.dev/rel/ and restart HubRemote"
+                .to_string(),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let available = Path::new("/Library/Filesystems/macfuse.fs").exists()
+            || Path::new("/Library/Application Support/fuse-t").exists();
+
+        MountPrerequisites {
+            available,
+            component: "macFUSE".to_string(),
+            install_hint: "Install macFUSE from https://osxfuse.github.io/ (or FUSE-T) and allow its system extension"
+                .to_string(),
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let available = Path::new("/dev/fuse").exists()
+            && (find_in_path("fusermount3") || find_in_path("fusermount"));
+
+        MountPrerequisites {
+            available,
+            component: "FUSE".to_string(),
+            install_hint: "Install FUSE with your package manager (e.g. `sudo apt install fuse3`)"
+                .to_string(),
+        }
+    }
+}
+
 /// Start rclone mount process
 pub fn start_mount(config: &RcloneConfig) -> Result<(), String> {
     // Check if already mounted
@@ -121,6 +697,8 @@ pub fn start_mount(config: &RcloneConfig) -> Result<(), String> {
         return Ok(());
     }
 
+    validate_mount_target(&config.mount_point)?;
+
     // Check if rclone is installed
     check_rclone_installed(&config.rclone_path)?;
 
@@ -138,6 +716,14 @@ pub fn start_mount(config: &RcloneConfig) -> Result<(), String> {
         .arg(&config.vfs_cache_mode)
         .arg("--network-mode");  // Makes mount visible to ALL processes (required for Jellyfin transcoding)
 
+    // Bound the cache so full mode can't fill the drive
+    if let Some(size) = &config.vfs_cache_max_size {
+        cmd.arg("--vfs-cache-max-size").arg(size);
+    }
+
+    // Keep stderr to explain failures
+    cmd.stderr(Stdio::piped());
+
     // Windows-specific options
     #[cfg(windows)]
     {
@@ -147,7 +733,10 @@ pub fn start_mount(config: &RcloneConfig) -> Result<(), String> {
     }
 
     // Start the process
-    let child = cmd.spawn().map_err(|e| format!("Failed to start rclone: {}", e))?;
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start rclone: {}", e))?;
+    if let Some(stderr) = child.stderr.take() {
+        capture_mount_stderr(stderr);
+    }
 
     // Store the process handle
     let mut process = RCLONE_PROCESS.lock().map_err(|e| format!("Lock error: {}", e))?;
@@ -156,8 +745,66 @@ pub fn start_mount(config: &RcloneConfig) -> Result<(), String> {
     Ok(())
 }
 
-/// Wait for mount to become available
-pub fn wait_for_mount(mount_point: &str, timeout_secs: u64) -> Result<(), String> {
+/// Collect the mount process's stderr in the background
+///
+/// The pipe has to be drained for as long as rclone runs, or it blocks once
+/// the buffer fills.
+fn capture_mount_stderr(stderr: std::process::ChildStderr) {
+    if let Ok(mut lines) = MOUNT_STDERR.lock() {
+        lines.clear();
+    }
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            log::debug!("rclone mount: {}", line);
+            if let Ok(mut lines) = MOUNT_STDERR.lock() {
+                if lines.len() == MOUNT_STDERR_LINES {
+                    lines.pop_front();
+                }
+                lines.push_back(line);
+            }
+        }
+    });
+}
+
+/// Last stderr lines of the mount process
+fn mount_stderr() -> Vec<String> {
+    MOUNT_STDERR
+        .lock()
+        .map(|lines| lines.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Exit status of the mount process if it has already exited (without reaping it)
+fn mount_process_exit() -> Option<ExitStatus> {
+    RCLONE_PROCESS.lock().ok()?.as_mut()?.try_wait().ok().flatten()
+}
+
+/// Build a mount error message from our message and rclone's last error line,
+/// with its classified cause
+fn mount_error(message: String) -> (String, MountErrorKind) {
+    let stderr = mount_stderr();
+    let kind = MountErrorKind::classify(&format!("{}\n{}", message, stderr.join("\n")));
+
+    // rclone prefixes errors with a timestamp and level, e.g. "2024/01/01 12:00:00 ERROR : ..."
+    let detail = stderr
+        .iter()
+        .rev()
+        .find(|line| line.contains("ERROR") || line.contains("CRITICAL") || line.contains("Fatal"))
+        .map(|line| line.trim());
+    let message = match detail {
+        Some(detail) => format!("{} ({})", message, detail),
+        None => message,
+    };
+    (message, kind)
+}
+
+/// Wait for mount to become available, giving up early if `cancelled` is set
+pub fn wait_for_mount(
+    mount_point: &str,
+    timeout_secs: u64,
+    cancelled: &AtomicBool,
+) -> Result<(), String> {
     let start = Instant::now();
     let timeout = Duration::from_secs(timeout_secs);
     let poll_interval = Duration::from_millis(500);
@@ -169,6 +816,12 @@ pub fn wait_for_mount(mount_point: &str, timeout_secs: u64) -> Result<(), String
             log::info!("Mount ready at {}", mount_point);
             return Ok(());
         }
+        if cancelled.load(Ordering::SeqCst) {
+            return Err(format!("Mount at {} was cancelled", mount_point));
+        }
+        if let Some(status) = mount_process_exit() {
+            return Err(format!("rclone exited before the mount was ready ({})", status));
+        }
         std::thread::sleep(poll_interval);
     }
 
@@ -178,6 +831,239 @@ pub fn wait_for_mount(mount_point: &str, timeout_secs: u64) -> Result<(), String
     ))
 }
 
+/// Serve the remote over HTTP with `rclone serve http` (no WinFsp/FUSE needed)
+///
+/// Returns the base URL; files are available at `<base>/<path relative to remote_folder>`.
+pub fn start_serve(config: &RcloneConfig, port: u16) -> Result<String, String> {
+    let base_url = format!("http://127.0.0.1:{}", port);
+
+    {
+        let mut serve = RCLONE_SERVE_PROCESS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some((child, serve_port)) = serve.as_mut() {
+            // Still running on the requested port: reuse it
+            if *serve_port == port && matches!(child.try_wait(), Ok(None)) {
+                return Ok(base_url);
+            }
+        }
+        if let Some((mut child, _)) = serve.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    check_rclone_installed(&config.rclone_path)?;
+
+    let remote = remote_path(config, None)?;
+    log::info!("Starting rclone serve http: {} on port {}", remote, port);
+
+    let child = rclone_command(&config.rclone_path)
+        .arg("serve")
+        .arg("http")
+        .arg(&remote)
+        .arg("--addr")
+        .arg(format!(":{}", port))
+        .arg("--read-only")
+        .arg("--vfs-cache-mode")
+        .arg(&config.vfs_cache_mode)
+        .spawn()
+        .map_err(|e| format!("Failed to start rclone serve: {}", e))?;
+
+    *RCLONE_SERVE_PROCESS.lock().map_err(|e| format!("Lock error: {}", e))? = Some((child, port));
+
+    // Wait for the HTTP server to accept connections
+    let start = Instant::now();
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    while start.elapsed() < Duration::from_secs(15) {
+        if std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok() {
+            log::info!("rclone serve ready at {}", base_url);
+            return Ok(base_url);
+        }
+        std::thread::sleep(Duration::from_millis(300));
+    }
+
+    stop_serve();
+    Err(format!("Timeout waiting for rclone serve on port {}", port))
+}
+
+/// Stop `rclone serve http` if running. Returns whether a process was stopped.
+pub fn stop_serve() -> bool {
+    let Ok(mut serve) = RCLONE_SERVE_PROCESS.lock() else {
+        return false;
+    };
+
+    match serve.take() {
+        Some((mut child, port)) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            log::info!("Stopped rclone serve on port {}", port);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Parse an rclone duration like "8s", "1m2s" or "1d2h" into seconds ("-" = unknown)
+fn parse_rclone_eta(input: &str) -> Option<u64> {
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in input.trim().chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let multiplier = match c {
+            'd' => 86_400.0,
+            'h' => 3_600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return None,
+        };
+        let value: f64 = number.parse().ok()?;
+        total += (value * multiplier) as u64;
+        number.clear();
+    }
+    number.is_empty().then_some(total)
+}
+
+/// Parse a `--stats-one-line` line such as
+/// "NOTICE:    1.234 MiB / 10 MiB, 12%, 1.1 MiB/s, ETA 8s"
+fn parse_copy_stats(line: &str) -> Option<CopyProgress> {
+    let stats = line.rsplit_once("NOTICE:").map_or(line, |(_, stats)| stats);
+    let mut fields = stats.split(',').map(str::trim);
+
+    let (transferred, total) = fields.next()?.split_once(" / ")?;
+    let size = |value: &str| parse_rclone_size(&value.replace(' ', ""));
+    let percent = fields.next()?.strip_suffix('%')?.parse::<u8>().ok()?;
+    let eta_secs = fields
+        .find_map(|field| field.strip_prefix("ETA "))
+        .and_then(parse_rclone_eta);
+
+    Some(CopyProgress {
+        transferred_bytes: size(transferred)?,
+        total_bytes: size(total)?,
+        percent: percent.min(100),
+        eta_secs,
+    })
+}
+
+/// Copy a file or folder from the remote to a local folder with `rclone copy`,
+/// emitting `rclone-copy-progress` as it goes. Blocks until the copy finishes.
+pub fn copy_from_remote(
+    app: &AppHandle,
+    config: &RcloneConfig,
+    remote_subpath: &str,
+    local_dest: &str,
+) -> Result<CopyResult, String> {
+    if remote_subpath.trim_matches('/').is_empty() {
+        return Err("No remote path to copy".to_string());
+    }
+    let destination = Path::new(local_dest.trim());
+    if !destination.is_absolute() {
+        return Err(format!("Destination must be an absolute path: {}", local_dest));
+    }
+    check_rclone_installed(&config.rclone_path)?;
+    let remote = remote_path(config, Some(remote_subpath))?;
+
+    let stderr = {
+        let mut copy = RCLONE_COPY_PROCESS.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(child) = copy.as_mut() {
+            if matches!(child.try_wait(), Ok(None)) {
+                return Err("Another copy is already running".to_string());
+            }
+        }
+
+        log::info!("Copying {} to {}", remote, destination.display());
+        let mut child = rclone_command(&config.rclone_path)
+            .arg("copy")
+            .arg(&remote)
+            .arg(destination)
+            .arg("--stats")
+            .arg("1s")
+            .arg("--stats-one-line")
+            .arg("--stats-log-level")
+            .arg("NOTICE")
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start rclone copy: {}", e))?;
+
+        COPY_CANCELLED.store(false, Ordering::SeqCst);
+        let stderr = child.stderr.take();
+        *copy = Some(child);
+        stderr
+    };
+
+    let start = Instant::now();
+    let mut last_progress: Option<CopyProgress> = None;
+    let mut last_error = None;
+    if let Some(stderr) = stderr {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            match parse_copy_stats(&line) {
+                Some(progress) if last_progress.as_ref() != Some(&progress) => {
+                    let _ = app.emit("rclone-copy-progress", &progress);
+                    last_progress = Some(progress);
+                }
+                Some(_) => {}
+                None if line.contains("ERROR") => last_error = Some(line.trim().to_string()),
+                None => {}
+            }
+        }
+    }
+
+    let status = RCLONE_COPY_PROCESS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .take()
+        .map(|mut child| child.wait());
+
+    if COPY_CANCELLED.swap(false, Ordering::SeqCst) {
+        log::info!("Copy of {} cancelled", remote);
+        return Err("Copy cancelled".to_string());
+    }
+    match status {
+        Some(Ok(status)) if status.success() => {}
+        Some(Ok(status)) => {
+            let message = last_error.unwrap_or_else(|| format!("exit status {}", status));
+            return Err(format!("rclone copy failed: {}", message));
+        }
+        Some(Err(e)) => return Err(format!("Failed to wait for rclone copy: {}", e)),
+        None => return Err("rclone copy process was lost".to_string()),
+    }
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    log::info!("Copied {} to {} in {} ms", remote, destination.display(), elapsed_ms);
+    Ok(CopyResult {
+        destination: destination.display().to_string(),
+        transferred_bytes: last_progress.map_or(0, |progress| progress.transferred_bytes),
+        elapsed_ms,
+    })
+}
+
+/// Kill the running `rclone copy`. Returns whether one was running.
+pub fn cancel_copy() -> bool {
+    let Ok(mut copy) = RCLONE_COPY_PROCESS.lock() else {
+        return false;
+    };
+    let Some(child) = copy.as_mut() else {
+        return false;
+    };
+    if !matches!(child.try_wait(), Ok(None)) {
+        return false;
+    }
+
+    COPY_CANCELLED.store(true, Ordering::SeqCst);
+    let _ = child.kill();
+    true
+}
+
+/// Forget the mount process if it exited on its own and return its exit status
+pub fn reap_mount_process() -> Option<ExitStatus> {
+    let mut process = RCLONE_PROCESS.lock().ok()?;
+    let status = process.as_mut()?.try_wait().ok().flatten()?;
+    *process = None;
+    Some(status)
+}
+
 /// Stop rclone mount process
 pub fn stop_mount(config: &RcloneConfig) -> Result<(), String> {
     log::info!("Stopping rclone mount at {}", config.mount_point);
@@ -242,54 +1128,255 @@ pub fn get_mount_status(config: &RcloneConfig) -> MountStatus {
     }
 }
 
+/// Read the start (and tail) of a file on the mount so rclone fetches it into
+/// the VFS cache before mpv opens it
+///
+/// Our mounts don't run the remote control API, so this is a plain read. With
+/// `--vfs-cache-mode full` the data stays cached; with other modes it only
+/// warms rclone's read buffers and the remote connection.
+pub fn prewarm(path: &str, target_bytes: u64, timeout: Duration) -> Result<PrewarmResult, String> {
+    let start = Instant::now();
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read metadata for {}: {}", path, e))?
+        .len();
+    let target_bytes = target_bytes.min(file_size);
+
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut bytes_read = 0u64;
+    while bytes_read < target_bytes && start.elapsed() < timeout {
+        let want = (target_bytes - bytes_read).min(buffer.len() as u64) as usize;
+        match file.read(&mut buffer[..want]) {
+            Ok(0) => break,
+            Ok(n) => bytes_read += n as u64,
+            Err(e) => return Err(format!("Failed to read {}: {}", path, e)),
+        }
+    }
+    let complete = bytes_read >= target_bytes;
+
+    // The container index is often at the end; fetch it too if time allows
+    let read_tail = complete
+        && file_size > target_bytes + PREWARM_TAIL_BYTES
+        && start.elapsed() < timeout
+        && file.seek(SeekFrom::End(-(PREWARM_TAIL_BYTES as i64))).is_ok();
+    if read_tail {
+        let mut remaining = PREWARM_TAIL_BYTES;
+        while remaining > 0 && start.elapsed() < timeout {
+            let want = remaining.min(buffer.len() as u64) as usize;
+            match file.read(&mut buffer[..want]) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => remaining -= n as u64,
+            }
+        }
+    }
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    log::info!(
+        "Pre-warmed {} ({} of {} bytes in {} ms)",
+        path,
+        bytes_read,
+        target_bytes,
+        elapsed_ms
+    );
+
+    Ok(PrewarmResult {
+        bytes_read,
+        target_bytes,
+        file_size,
+        complete,
+        elapsed_ms,
+    })
+}
+
 // ============================================
 // Tauri Commands
 // ============================================
 
-/// Mount the drive with given configuration
-#[tauri::command]
-pub fn mount_drive(app: AppHandle, config: RcloneConfig) -> CommandResult<MountStatus> {
-    // Check if already mounted
+/// Register a cancel flag for a mount that's starting
+fn begin_pending_mount(mount_point: &str) -> Arc<AtomicBool> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    if let Ok(mut pending) = PENDING_MOUNTS.lock() {
+        pending.retain(|(point, _)| point != mount_point);
+        pending.push((mount_point.to_string(), cancelled.clone()));
+    }
+    cancelled
+}
+
+/// Drop the cancel flag once a mount has finished either way
+fn end_pending_mount(mount_point: &str) {
+    if let Ok(mut pending) = PENDING_MOUNTS.lock() {
+        pending.retain(|(point, _)| point != mount_point);
+    }
+}
+
+/// Start rclone for a mount and register its cancel flag
+///
+/// Returns None if the drive is already mounted.
+fn begin_mount(
+    app: &AppHandle,
+    config: &RcloneConfig,
+) -> Result<Option<Arc<AtomicBool>>, (String, MountErrorKind)> {
     if is_path_mounted(&config.mount_point) {
-        return CommandResult::ok(MountStatus {
-            is_mounted: true,
-            mount_point: config.mount_point.clone(),
-            remote_name: config.remote_name.clone(),
-            remote_folder: config.remote_folder.clone(),
-            error: None,
-        });
+        return Ok(None);
     }
 
     // Emit starting event
     let _ = app.emit("rclone-status", "mounting");
 
-    // Start the mount
-    if let Err(e) = start_mount(&config) {
+    if let Err(e) = start_mount(config) {
         let _ = app.emit("rclone-status", "error");
-        return CommandResult::err(e);
+        let kind = MountErrorKind::classify(&e);
+        errors::report(Subsystem::Rclone, "mount_failed", e.clone(), true);
+        return Err((e, kind));
     }
 
-    // Wait for it to be ready
-    if let Err(e) = wait_for_mount(&config.mount_point, 30) {
-        // Try to clean up
-        let _ = stop_mount(&config);
+    Ok(Some(begin_pending_mount(&config.mount_point)))
+}
+
+/// Wait for a started mount to be ready, cleaning up on timeout or cancellation
+///
+/// The error carries the classified cause, or None if the mount was cancelled.
+fn finish_mount(
+    app: &AppHandle,
+    config: &RcloneConfig,
+    cancelled: &AtomicBool,
+) -> Result<(), (String, Option<MountErrorKind>)> {
+    let result = wait_for_mount(&config.mount_point, 30, cancelled);
+    end_pending_mount(&config.mount_point);
+
+    if let Err(e) = result {
+        if cancelled.load(Ordering::SeqCst) {
+            let _ = stop_mount(config);
+            log::info!("{}", e);
+            let _ = app.emit("rclone-status", "cancelled");
+            return Err((e, None));
+        }
+
+        // Read stderr before cleaning up
+        let (e, kind) = mount_error(e);
+        let _ = stop_mount(config);
         let _ = app.emit("rclone-status", "error");
-        return CommandResult::err(e);
+        let code = if kind == MountErrorKind::Timeout {
+            "mount_timeout"
+        } else {
+            "mount_failed"
+        };
+        errors::report(Subsystem::Rclone, code, e.clone(), true);
+        return Err((e, Some(kind)));
     }
 
     let _ = app.emit("rclone-status", "mounted");
-    CommandResult::ok(MountStatus {
-        is_mounted: true,
-        mount_point: config.mount_point,
-        remote_name: config.remote_name,
-        remote_folder: config.remote_folder,
-        error: None,
-    })
+    Ok(())
+}
+
+/// Start the mount and wait for it, cleaning up on timeout or cancellation
+fn mount_and_wait(app: &AppHandle, config: RcloneConfig) -> CommandResult<MountStatus> {
+    let config = match normalize_config(config) {
+        Ok(config) => config,
+        Err(e) => return CommandResult::err(e),
+    };
+    remember_config(&config);
+    settings::save_integrations(app);
+
+    let cancelled = match begin_mount(app, &config) {
+        Ok(Some(cancelled)) => cancelled,
+        Ok(None) => return CommandResult::ok(get_mount_status(&config)),
+        Err((e, kind)) => return CommandResult::mount_err(e, kind),
+    };
+
+    match finish_mount(app, &config, &cancelled) {
+        Ok(()) => CommandResult::ok(get_mount_status(&config)),
+        Err((e, Some(kind))) => CommandResult::mount_err(e, kind),
+        Err((e, None)) => CommandResult::err(e),
+    }
+}
+
+/// Mount the drive with given configuration
+///
+/// Runs off the main thread so `cancel_mount` can interrupt the wait.
+#[tauri::command]
+pub async fn mount_drive(app: AppHandle, config: RcloneConfig) -> CommandResult<MountStatus> {
+    match tauri::async_runtime::spawn_blocking(move || mount_and_wait(&app, config)).await {
+        Ok(result) => result,
+        Err(e) => CommandResult::err(format!("Mount task failed: {}", e)),
+    }
+}
+
+/// Start mounting the drive without waiting for it to be ready
+///
+/// Returns once rclone is running (`isMounted` false) or if the drive is
+/// already mounted. Readiness is reported through `rclone-status`
+/// (`mounted`, `error` or `cancelled`) and failures also as `backend-error`.
+/// `cancel_mount` works the same as for `mount_drive`.
+#[tauri::command]
+pub async fn mount_drive_async(
+    app: AppHandle,
+    config: RcloneConfig,
+) -> CommandResult<MountStatus> {
+    let config = match normalize_config(config) {
+        Ok(config) => config,
+        Err(e) => return CommandResult::err(e),
+    };
+    remember_config(&config);
+    settings::save_integrations(&app);
+
+    let handle = app.clone();
+    let start_config = config.clone();
+    let started =
+        tauri::async_runtime::spawn_blocking(move || begin_mount(&handle, &start_config)).await;
+
+    let cancelled = match started {
+        Ok(Ok(Some(cancelled))) => cancelled,
+        Ok(Ok(None)) => return CommandResult::ok(get_mount_status(&config)),
+        Ok(Err((e, kind))) => return CommandResult::mount_err(e, kind),
+        Err(e) => return CommandResult::err(format!("Mount task failed: {}", e)),
+    };
+
+    let status = get_mount_status(&config);
+    tauri::async_runtime::spawn_blocking(move || {
+        // Outcome is reported through events
+        let _ = finish_mount(&app, &config, &cancelled);
+    });
+
+    CommandResult::ok(status)
+}
+
+/// Cancel a mount that's still waiting to become ready
+///
+/// The rclone process is killed and `mount_drive` returns an error after
+/// emitting `rclone-status: "cancelled"`. Returns false if no mount was pending
+/// for `mount_point`. Unmounting takes well under a second and isn't cancellable.
+#[tauri::command]
+pub fn cancel_mount(mount_point: String) -> CommandResult<bool> {
+    let mount_point = match normalize_mount_point(&mount_point) {
+        Ok(mount_point) => mount_point,
+        Err(e) => return CommandResult::err(e),
+    };
+
+    let Ok(pending) = PENDING_MOUNTS.lock() else {
+        return CommandResult::err("Lock error".to_string());
+    };
+
+    match pending.iter().find(|(point, _)| *point == mount_point) {
+        Some((_, cancelled)) => {
+            log::info!("Cancelling mount at {}", mount_point);
+            cancelled.store(true, Ordering::SeqCst);
+            CommandResult::ok(true)
+        }
+        None => CommandResult::ok(false),
+    }
 }
 
 /// Unmount the drive
 #[tauri::command]
 pub fn unmount_drive(app: AppHandle, config: RcloneConfig) -> CommandResult<bool> {
+    let config = match normalize_config(config) {
+        Ok(config) => config,
+        Err(e) => return CommandResult::err(e),
+    };
+
     let _ = app.emit("rclone-status", "unmounting");
 
     match stop_mount(&config) {
@@ -307,7 +1394,19 @@ pub fn unmount_drive(app: AppHandle, config: RcloneConfig) -> CommandResult<bool
 /// Check if the drive is currently mounted
 #[tauri::command]
 pub fn check_mount_status(config: RcloneConfig) -> CommandResult<MountStatus> {
-    CommandResult::ok(get_mount_status(&config))
+    match normalize_config(config) {
+        Ok(config) => CommandResult::ok(get_mount_status(&config)),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Validate a mount point and return its normalized form
+#[tauri::command]
+pub fn validate_mount_point(mount_point: String) -> CommandResult<String> {
+    match normalize_mount_point(&mount_point) {
+        Ok(normalized) => CommandResult::ok(normalized),
+        Err(e) => CommandResult::err(e),
+    }
 }
 
 /// Check if rclone is installed
@@ -319,6 +1418,131 @@ pub fn check_rclone(rclone_path: String) -> CommandResult<String> {
     }
 }
 
+/// Get storage quota (total/used/free/trashed bytes) for the remote
+#[tauri::command]
+pub fn get_remote_quota(config: RcloneConfig) -> CommandResult<RemoteQuota> {
+    match get_quota(&config) {
+        Ok(quota) => CommandResult::ok(quota),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Check free space on the volume holding the rclone cache before enabling
+/// full VFS caching
+///
+/// `min_free_gb` (default 50) is the threshold for the low-space warning.
+#[tauri::command]
+pub async fn check_cache_disk_space(
+    config: RcloneConfig,
+    min_free_gb: Option<u64>,
+) -> CommandResult<CacheDiskSpace> {
+    let config = match normalize_config(config) {
+        Ok(config) => config,
+        Err(e) => return CommandResult::err(e),
+    };
+    let min_free_bytes = min_free_gb.unwrap_or(DEFAULT_CACHE_MIN_FREE_GB) << 30;
+
+    match tauri::async_runtime::spawn_blocking(move || cache_disk_space(&config, min_free_bytes))
+        .await
+    {
+        Ok(Ok(space)) => CommandResult::ok(space),
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Disk space check failed: {}", e)),
+    }
+}
+
+/// List files in a remote folder (one level deep) via rclone, without mounting
+#[tauri::command]
+pub fn list_remote_files(
+    config: RcloneConfig,
+    subpath: Option<String>,
+) -> CommandResult<Vec<RemoteEntry>> {
+    match list_remote(&config, subpath.as_deref()) {
+        Ok(entries) => CommandResult::ok(entries),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get the size in bytes of a file on the remote without mounting
+///
+/// `path` is relative to the configured remote folder.
+#[tauri::command]
+pub fn get_remote_file_size(config: RcloneConfig, path: String) -> CommandResult<u64> {
+    match remote_file_size(&config, &path) {
+        Ok(size) => CommandResult::ok(size),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Serve the remote over HTTP instead of mounting it, returning the base URL
+#[tauri::command]
+pub fn start_rclone_serve(config: RcloneConfig, port: Option<u16>) -> CommandResult<String> {
+    match start_serve(&config, port.unwrap_or(DEFAULT_SERVE_PORT)) {
+        Ok(url) => CommandResult::ok(url),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Pre-warm the VFS cache for a file on the mount before playing it
+///
+/// Reads the first `target_mb` (default 32) MB, giving up after `timeout_secs`
+/// (default 15). Runs off the main thread since cold reads can be slow.
+#[tauri::command]
+pub async fn prewarm_file(
+    path: String,
+    target_mb: Option<u64>,
+    timeout_secs: Option<u64>,
+) -> CommandResult<PrewarmResult> {
+    let target_bytes = target_mb.unwrap_or(DEFAULT_PREWARM_MB) * 1024 * 1024;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_PREWARM_TIMEOUT_SECS));
+
+    match tauri::async_runtime::spawn_blocking(move || prewarm(&path, target_bytes, timeout)).await
+    {
+        Ok(Ok(result)) => CommandResult::ok(result),
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Pre-warm task failed: {}", e)),
+    }
+}
+
+/// Copy a file or folder from the remote to a local folder for offline viewing
+///
+/// Emits `rclone-copy-progress` about once a second and returns when the copy
+/// finishes. Only one copy runs at a time.
+#[tauri::command]
+pub async fn rclone_copy(
+    app: AppHandle,
+    config: RcloneConfig,
+    remote_subpath: String,
+    local_dest: String,
+) -> CommandResult<CopyResult> {
+    let task = tauri::async_runtime::spawn_blocking(move || {
+        copy_from_remote(&app, &config, &remote_subpath, &local_dest)
+    });
+    match task.await {
+        Ok(Ok(result)) => CommandResult::ok(result),
+        Ok(Err(e)) => CommandResult::err(e),
+        Err(e) => CommandResult::err(format!("Copy task failed: {}", e)),
+    }
+}
+
+/// Cancel the running `rclone_copy`. Returns whether a copy was running.
+#[tauri::command]
+pub fn cancel_rclone_copy() -> CommandResult<bool> {
+    CommandResult::ok(cancel_copy())
+}
+
+/// Stop serving the remote over HTTP
+#[tauri::command]
+pub fn stop_rclone_serve() -> CommandResult<bool> {
+    CommandResult::ok(stop_serve())
+}
+
+/// Check whether WinFsp/FUSE is installed before attempting a mount
+#[tauri::command]
+pub fn check_mount_prerequisites() -> CommandResult<MountPrerequisites> {
+    CommandResult::ok(check_prerequisites())
+}
+
 /// Get default configuration
 #[tauri::command]
 pub fn get_default_rclone_config() -> CommandResult<RcloneConfig> {
@@ -336,4 +1560,97 @@ pub fn cleanup() {
             let _ = child.wait();
         }
     }
+
+    if let Ok(mut copy) = RCLONE_COPY_PROCESS.lock() {
+        if let Some(mut child) = copy.take() {
+            log::info!("Killing rclone copy on exit");
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    stop_serve();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mount_point_rejects_empty_input() {
+        assert!(normalize_mount_point("").is_err());
+        assert!(normalize_mount_point("   ").is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn mount_point_normalizes_drive_letters() {
+        assert_eq!(normalize_mount_point("g").unwrap(), "G:");
+        assert_eq!(normalize_mount_point("g:").unwrap(), "G:");
+        assert_eq!(normalize_mount_point("G:\\").unwrap(), "G:");
+        assert_eq!(normalize_mount_point(" g:/ ").unwrap(), "G:");
+        assert_eq!(normalize_mount_point("*").unwrap(), "*");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn mount_point_normalizes_windows_paths() {
+        assert_eq!(normalize_mount_point("c:/mnt/drive/").unwrap(), "C:\\mnt\\drive");
+        assert_eq!(normalize_mount_point("C:\\mnt\\drive\\").unwrap(), "C:\\mnt\\drive");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn mount_point_rejects_invalid_windows_input() {
+        for input in ["1", "1:", "gg", "g:mnt", "\\\\server\\share", "mnt\\drive"] {
+            assert!(normalize_mount_point(input).is_err(), "{:?} should be rejected", input);
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn home() -> String {
+        let home = std::env::var("HOME").expect("HOME is set");
+        home.trim_end_matches('/').to_string()
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn mount_point_expands_home() {
+        assert_eq!(normalize_mount_point("~").unwrap(), home());
+        assert_eq!(normalize_mount_point("~/").unwrap(), home());
+        assert_eq!(
+            normalize_mount_point("~/mnt/drive").unwrap(),
+            format!("{}/mnt/drive", home())
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn mount_point_resolves_relative_paths() {
+        let cwd = std::env::current_dir().unwrap();
+        let expected = cwd.join("mnt/drive").to_string_lossy().into_owned();
+        assert_eq!(normalize_mount_point("mnt/drive").unwrap(), expected);
+        assert_eq!(normalize_mount_point("mnt/drive/").unwrap(), expected);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn mount_point_strips_trailing_slashes() {
+        assert_eq!(normalize_mount_point("/mnt/drive/").unwrap(), "/mnt/drive");
+        assert_eq!(normalize_mount_point(" /mnt/drive/// ").unwrap(), "/mnt/drive");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn mount_point_rejects_invalid_unix_input() {
+        assert!(normalize_mount_point("/").is_err());
+        assert!(normalize_mount_point("///").is_err());
+
+        let file = std::env::temp_dir()
+            .join(format!("hubremote-mount-test-{}", std::process::id()));
+        std::fs::write(&file, b"").unwrap();
+        let result = normalize_mount_point(&file.to_string_lossy());
+        let _ = std::fs::remove_file(&file);
+        assert!(result.is_err(), "a file can't be a mount point");
+    }
 }
@@ -0,0 +1,220 @@
+//! Recently played media
+//!
+//! Keeps a small persisted list of what was played and where playback was
+//! left off, so the app can offer to pick up where the user stopped.
+
+use crate::commands::{play_with_options, CommandResult, PlayOptions};
+use crate::mpv::MpvState;
+use crate::net;
+use crate::settings::{config_file_path, read_json, write_json};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+/// Recent media file name inside the app config directory
+const RECENT_FILE: &str = "recent.json";
+
+/// Maximum number of remembered entries
+const MAX_RECENT: usize = 20;
+
+/// How long to wait when checking if a remote URL is reachable
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A recently played file or URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentMedia {
+    pub url: String,
+    pub title: Option<String>,
+    /// Last known position in seconds
+    pub position: f64,
+    /// Duration in seconds (0 if unknown)
+    pub duration: f64,
+    /// Unix timestamp (seconds) of the last update
+    pub last_played: u64,
+}
+
+/// Global recent media state
+pub struct RecentState {
+    entries: RwLock<Vec<RecentMedia>>,
+    path: RwLock<Option<PathBuf>>,
+}
+
+impl RecentState {
+    pub fn new() -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+            path: RwLock::new(None),
+        }
+    }
+
+    /// Load the recent list from the app config directory
+    pub fn load(&self, app: &AppHandle) {
+        let Some(path) = config_file_path(app, RECENT_FILE) else {
+            return;
+        };
+
+        if let Some(entries) = read_json::<Vec<RecentMedia>>(&path) {
+            *self.entries.write() = entries;
+        }
+        *self.path.write() = Some(path);
+    }
+
+    /// Get all entries, most recent first
+    pub fn entries(&self) -> Vec<RecentMedia> {
+        self.entries.read().clone()
+    }
+
+    /// Get the most recent entry
+    pub fn latest(&self) -> Option<RecentMedia> {
+        self.entries.read().first().cloned()
+    }
+
+    /// Move `url` to the front of the list, applying `f` to its entry
+    fn touch<F>(&self, url: &str, f: F)
+    where
+        F: FnOnce(&mut RecentMedia),
+    {
+        let mut entries = self.entries.write();
+
+        let mut entry = match entries.iter().position(|e| e.url == url) {
+            Some(index) => entries.remove(index),
+            None => RecentMedia {
+                url: url.to_string(),
+                title: None,
+                position: 0.0,
+                duration: 0.0,
+                last_played: 0,
+            },
+        };
+
+        f(&mut entry);
+        entry.last_played = unix_now();
+        entries.insert(0, entry);
+        entries.truncate(MAX_RECENT);
+
+        if let Some(path) = self.path.read().as_ref() {
+            if let Err(e) = write_json(path, &*entries) {
+                log::warn!("Failed to save recent media: {}", e);
+            }
+        }
+    }
+}
+
+impl Default for RecentState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Record that `url` started playing
+pub fn record_play(app: &AppHandle, url: &str, start_position: Option<f64>) {
+    if let Some(state) = app.try_state::<RecentState>() {
+        state.touch(url, |entry| {
+            if let Some(position) = start_position {
+                entry.position = position;
+            }
+        });
+    }
+}
+
+/// Save the current playback position and title of the loaded file
+pub fn save_progress(app: &AppHandle) {
+    let (Some(recent), Some(mpv)) = (app.try_state::<RecentState>(), app.try_state::<MpvState>())
+    else {
+        return;
+    };
+
+    // Nothing loaded (idle or not initialized)
+    let Ok(Some(url)) = mpv.get_path() else {
+        return;
+    };
+    let Ok(playback) = mpv.get_state() else {
+        return;
+    };
+
+    recent.touch(&url, |entry| {
+        entry.position = playback.position;
+        if playback.duration > 0.0 {
+            entry.duration = playback.duration;
+        }
+        if playback.media_title.is_some() {
+            entry.title = playback.media_title.clone();
+        }
+    });
+}
+
+/// Check if a local path or http(s) URL looks reachable
+pub fn is_reachable(url: &str) -> bool {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        net::is_host_reachable(url, REACHABILITY_TIMEOUT)
+    } else {
+        Path::new(url).exists()
+    }
+}
+
+/// Reload the most recent media paused at its saved position
+pub fn resume_latest(
+    app: &AppHandle,
+    auth_token: Option<&str>,
+) -> Result<Option<RecentMedia>, String> {
+    let Some(recent) = app.try_state::<RecentState>() else {
+        return Ok(None);
+    };
+    let Some(entry) = recent.latest() else {
+        return Ok(None);
+    };
+
+    if !is_reachable(&entry.url) {
+        return Err(format!(
+            "Last played media is no longer reachable: {}",
+            entry.title.as_deref().unwrap_or(&entry.url)
+        ));
+    }
+
+    // Same path as any other play, opened paused instead of auto-playing
+    let options = PlayOptions {
+        url: entry.url.clone(),
+        start_position: Some(entry.position),
+        auth_token: auth_token.map(str::to_string),
+        start_paused: true,
+        ..PlayOptions::default()
+    };
+    play_with_options(app, &app.state::<MpvState>(), &options)?;
+
+    log::info!("Resumed {} at {:.0}s", entry.url, entry.position);
+    Ok(Some(entry))
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Get recently played media, most recent first
+#[tauri::command]
+pub fn get_recent_media(state: State<RecentState>) -> CommandResult<Vec<RecentMedia>> {
+    CommandResult::ok(state.entries())
+}
+
+/// Reload the last played media paused at its saved position
+///
+/// Returns None when nothing has been played yet.
+#[tauri::command]
+pub fn resume_last_session(
+    app: AppHandle,
+    auth_token: Option<String>,
+) -> CommandResult<Option<RecentMedia>> {
+    match resume_latest(&app, auth_token.as_deref()) {
+        Ok(entry) => CommandResult::ok(entry),
+        Err(e) => CommandResult::err(e),
+    }
+}
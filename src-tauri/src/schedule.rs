@@ -0,0 +1,188 @@
+//! Scheduled playback
+//!
+//! Starts a file at a wall-clock time, e.g. for a synchronized "watch party"
+//! start. One playback can be scheduled at a time; scheduling another
+//! replaces it. The schedule lives in memory only, so quitting the app
+//! cancels it.
+
+use crate::commands::{self, CommandResult, PlayOptions};
+use crate::mpv::MpvState;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Longest sleep between clock checks, so clock changes and cancellation
+/// are noticed
+const MAX_SLEEP: Duration = Duration::from_secs(1);
+
+/// A pending scheduled playback
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPlayback {
+    pub id: u64,
+    pub url: String,
+    /// Target start time (Unix epoch milliseconds)
+    pub at_epoch_ms: u64,
+    /// When it was scheduled (Unix epoch milliseconds)
+    pub scheduled_at_ms: u64,
+}
+
+/// Scheduled playback state
+pub struct ScheduleState {
+    /// The pending playback and the options to play it with
+    pending: Mutex<Option<(ScheduledPlayback, PlayOptions)>>,
+    /// Bumped for each schedule; a timer only fires if its ID is still pending
+    next_id: AtomicU64,
+}
+
+impl ScheduleState {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Take the pending playback if it's still the one with this ID
+    fn take_if(&self, id: u64) -> Option<(ScheduledPlayback, PlayOptions)> {
+        let mut pending = self.pending.lock();
+        if pending.as_ref().is_some_and(|(scheduled, _)| scheduled.id == id) {
+            pending.take()
+        } else {
+            None
+        }
+    }
+
+    fn is_pending(&self, id: u64) -> bool {
+        self.pending
+            .lock()
+            .as_ref()
+            .is_some_and(|(scheduled, _)| scheduled.id == id)
+    }
+}
+
+impl Default for ScheduleState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Wait for the target time, then play unless cancelled or replaced
+async fn run_timer(app: AppHandle, id: u64, at_epoch_ms: u64) {
+    loop {
+        if !app.state::<ScheduleState>().is_pending(id) {
+            return;
+        }
+        let remaining = Duration::from_millis(at_epoch_ms.saturating_sub(now_ms()));
+        if remaining.is_zero() {
+            break;
+        }
+        tokio::time::sleep(remaining.min(MAX_SLEEP)).await;
+    }
+
+    let Some((scheduled, options)) = app.state::<ScheduleState>().take_if(id) else {
+        return;
+    };
+
+    let handle = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mpv = handle.state::<MpvState>();
+        commands::play_with_options(&handle, &mpv, &options)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(_)) => {
+            log::info!("Scheduled playback {} started: {}", scheduled.id, scheduled.url);
+            let _ = app.emit("scheduled-playback-started", &scheduled);
+        }
+        Ok(Err(e)) => {
+            log::warn!("Scheduled playback {} failed: {}", scheduled.id, e);
+            let _ = app.emit("scheduled-playback-failed", e);
+        }
+        Err(e) => {
+            let _ = app.emit("scheduled-playback-failed", e.to_string());
+        }
+    }
+}
+
+/// Drop the pending playback at shutdown
+pub fn cancel(app: &AppHandle) {
+    if let Some(state) = app.try_state::<ScheduleState>() {
+        if let Some((scheduled, _)) = state.pending.lock().take() {
+            log::info!("Cancelled scheduled playback {} on exit", scheduled.id);
+        }
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Start playing `url` at `at_epoch_ms` (Unix epoch milliseconds)
+///
+/// `options` are the usual play options (its `url` is ignored). A time in
+/// the past starts playback right away. Replaces any pending schedule and
+/// emits `scheduled-playback-started` when playback begins.
+#[tauri::command]
+pub fn schedule_playback(
+    app: AppHandle,
+    state: State<ScheduleState>,
+    url: String,
+    at_epoch_ms: u64,
+    options: Option<PlayOptions>,
+) -> CommandResult<ScheduledPlayback> {
+    if url.trim().is_empty() {
+        return CommandResult::err("URL is empty");
+    }
+
+    let scheduled = ScheduledPlayback {
+        id: state.next_id.fetch_add(1, Ordering::SeqCst),
+        url: url.clone(),
+        at_epoch_ms,
+        scheduled_at_ms: now_ms(),
+    };
+    let options = PlayOptions {
+        url,
+        ..options.unwrap_or_default()
+    };
+
+    if let Some((replaced, _)) = state.pending.lock().replace((scheduled.clone(), options)) {
+        log::info!("Scheduled playback {} replaced", replaced.id);
+    }
+    log::info!(
+        "Playback of {} scheduled in {}ms",
+        scheduled.url,
+        at_epoch_ms.saturating_sub(scheduled.scheduled_at_ms)
+    );
+
+    tauri::async_runtime::spawn(run_timer(app, scheduled.id, at_epoch_ms));
+    CommandResult::ok(scheduled)
+}
+
+/// Cancel the pending scheduled playback. Returns what was cancelled.
+#[tauri::command]
+pub fn cancel_scheduled_playback(
+    state: State<ScheduleState>,
+) -> CommandResult<Option<ScheduledPlayback>> {
+    let cancelled = state.pending.lock().take().map(|(scheduled, _)| scheduled);
+    CommandResult::ok(cancelled)
+}
+
+/// Get the pending scheduled playback, if any
+#[tauri::command]
+pub fn get_scheduled_playback(
+    state: State<ScheduleState>,
+) -> CommandResult<Option<ScheduledPlayback>> {
+    let pending = state.pending.lock().as_ref().map(|(scheduled, _)| scheduled.clone());
+    CommandResult::ok(pending)
+}
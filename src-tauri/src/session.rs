@@ -0,0 +1,253 @@
+//! Crash recovery snapshots
+//!
+//! While something is playing the player monitor periodically writes the full
+//! player state to disk. The snapshot is deleted on a clean stop or exit, so if
+//! one is found at startup the previous run ended abnormally and the UI can
+//! offer to restore it. Unlike the recent media list, this also keeps tracks,
+//! speed, volume and loop modes.
+
+use crate::commands::{ensure_player, CommandResult};
+use crate::mpv::MpvState;
+use crate::settings::{config_file_path, read_json, write_json};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+/// Snapshot file name inside the app config directory
+const SESSION_FILE: &str = "session.json";
+
+/// Player state captured for crash recovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSnapshot {
+    pub url: String,
+    pub title: Option<String>,
+    /// Position in seconds
+    pub position: f64,
+    /// Audio track ID (None = mpv default)
+    pub audio_track: Option<i64>,
+    /// Subtitle track ID (Some(0) = subtitles off)
+    pub subtitle_track: Option<i64>,
+    pub speed: f64,
+    pub volume: i64,
+    pub muted: bool,
+    /// mpv `loop-file` value ("no", "inf" or a count)
+    pub loop_file: String,
+    /// mpv `loop-playlist` value
+    pub loop_playlist: String,
+    /// Unix timestamp (seconds) of the snapshot
+    pub saved_at: u64,
+}
+
+/// Global crash recovery state
+pub struct SessionState {
+    /// Snapshot left behind by the previous run, if it didn't exit cleanly
+    recoverable: Mutex<Option<SessionSnapshot>>,
+    path: RwLock<Option<PathBuf>>,
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self {
+            recoverable: Mutex::new(None),
+            path: RwLock::new(None),
+        }
+    }
+
+    /// Pick up a snapshot left by an abnormal exit
+    pub fn load(&self, app: &AppHandle) {
+        let Some(path) = config_file_path(app, SESSION_FILE) else {
+            return;
+        };
+
+        if let Some(snapshot) = read_json::<SessionSnapshot>(&path) {
+            log::info!("Found recoverable session for {}", snapshot.url);
+            *self.recoverable.lock() = Some(snapshot);
+        }
+        *self.path.write() = Some(path);
+    }
+
+    /// Get the recoverable snapshot from the previous run
+    pub fn recoverable(&self) -> Option<SessionSnapshot> {
+        self.recoverable.lock().clone()
+    }
+
+    /// Write the current snapshot
+    fn save(&self, snapshot: &SessionSnapshot) {
+        if let Some(path) = self.path.read().as_ref() {
+            if let Err(e) = write_json(path, snapshot) {
+                log::warn!("Failed to save session snapshot: {}", e);
+            }
+        }
+    }
+
+    /// Delete the snapshot on disk (clean stop or exit)
+    pub fn clear(&self) {
+        if let Some(path) = self.path.read().as_ref() {
+            if path.exists() {
+                if let Err(e) = std::fs::remove_file(path) {
+                    log::warn!("Failed to remove session snapshot: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read a track property: a track ID, `false` for "no" (0), or None when unset
+fn track_id(value: Option<Value>) -> Option<i64> {
+    match value? {
+        Value::Number(id) => id.as_i64(),
+        Value::Bool(false) => Some(0),
+        _ => None,
+    }
+}
+
+/// mpv loop properties are "no", "inf" or a number
+fn loop_value(value: Option<Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s,
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Bool(true)) => "inf".to_string(),
+        _ => "no".to_string(),
+    }
+}
+
+/// Capture the current player state (None if nothing is loaded)
+fn capture(mpv: &MpvState) -> Option<SessionSnapshot> {
+    mpv.with_player(|ipc| {
+        let Some(url) = ipc.get_path()? else {
+            return Ok(None);
+        };
+        let state = ipc.get_playback_state()?;
+
+        Ok(Some(SessionSnapshot {
+            url,
+            title: state.media_title,
+            position: state.position,
+            audio_track: track_id(ipc.get_property("aid").ok()),
+            subtitle_track: track_id(ipc.get_property("sid").ok()),
+            speed: ipc.get_property("speed").unwrap_or(1.0),
+            volume: state.volume,
+            muted: state.is_muted,
+            loop_file: loop_value(ipc.get_property("loop-file").ok()),
+            loop_playlist: loop_value(ipc.get_property("loop-playlist").ok()),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }))
+    })
+    .ok()
+    .flatten()
+}
+
+/// Snapshot the player if something is loaded. Called periodically by the player monitor.
+pub fn snapshot(app: &AppHandle) {
+    let (Some(session), Some(mpv)) = (app.try_state::<SessionState>(), app.try_state::<MpvState>())
+    else {
+        return;
+    };
+
+    if !mpv.is_initialized() {
+        return;
+    }
+    if let Some(snapshot) = capture(&mpv) {
+        session.save(&snapshot);
+    }
+}
+
+/// Forget the snapshot after a clean stop or exit
+pub fn clear(app: &AppHandle) {
+    if let Some(session) = app.try_state::<SessionState>() {
+        session.clear();
+    }
+}
+
+/// Reload a snapshot with its position, tracks, speed, volume and loop modes
+fn restore(
+    app: &AppHandle,
+    snapshot: &SessionSnapshot,
+    auth_token: Option<&str>,
+) -> Result<(), String> {
+    let mpv = app.state::<MpvState>();
+    ensure_player(app, &mpv).map_err(|e| format!("Failed to initialize player: {}", e))?;
+
+    mpv.with_player(|ipc| {
+        let header = auth_token
+            .map(|token| format!("X-Emby-Token: {}", token))
+            .unwrap_or_default();
+        ipc.set_property("http-header-fields", header)?;
+
+        ipc.set_property("speed", snapshot.speed)?;
+        ipc.set_volume(snapshot.volume)?;
+        ipc.set_mute(snapshot.muted)?;
+        ipc.set_property("loop-file", snapshot.loop_file.as_str())?;
+        ipc.set_property("loop-playlist", snapshot.loop_playlist.as_str())?;
+
+        // Tracks only exist once the file is open, so pass them as per-file options
+        let mut options = vec![format!("start={}", snapshot.position)];
+        if let Some(aid) = snapshot.audio_track {
+            options.push(format!("aid={}", aid));
+        }
+        match snapshot.subtitle_track {
+            Some(0) => options.push("sid=no".to_string()),
+            Some(sid) => options.push(format!("sid={}", sid)),
+            None => {}
+        }
+
+        ipc.load_file_with_file_options(&snapshot.url, &options.join(","))
+    })
+    .map_err(|e| e.to_string())
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Get the session left behind by an abnormal exit (None after a clean exit)
+#[tauri::command]
+pub fn get_recoverable_session(
+    state: State<SessionState>,
+) -> CommandResult<Option<SessionSnapshot>> {
+    CommandResult::ok(state.recoverable())
+}
+
+/// Restore the session left behind by an abnormal exit
+///
+/// Returns the restored snapshot, or None if there was nothing to restore.
+#[tauri::command]
+pub fn restore_session(
+    app: AppHandle,
+    state: State<SessionState>,
+    auth_token: Option<String>,
+) -> CommandResult<Option<SessionSnapshot>> {
+    let Some(snapshot) = state.recoverable() else {
+        return CommandResult::ok(None);
+    };
+
+    match restore(&app, &snapshot, auth_token.as_deref()) {
+        Ok(()) => {
+            log::info!("Restored session {} at {:.0}s", snapshot.url, snapshot.position);
+            *state.recoverable.lock() = None;
+            CommandResult::ok(Some(snapshot))
+        }
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Discard the recoverable session without restoring it
+#[tauri::command]
+pub fn discard_session(state: State<SessionState>) -> CommandResult<()> {
+    *state.recoverable.lock() = None;
+    state.clear();
+    CommandResult::ok_empty()
+}
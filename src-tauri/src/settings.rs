@@ -0,0 +1,931 @@
+//! Persisted application settings
+//!
+//! Settings are stored as JSON in the app config directory and loaded once
+//! at startup. Every field has a default so older settings files keep working.
+
+use crate::commands::{
+    apply_always_on_top, apply_player_settings, apply_stream_hostname, apply_stream_ip_allowlist,
+    apply_title_format, validate_player_settings, CommandResult,
+};
+use crate::mpv::MpvState;
+use crate::rclone::{self, RcloneConfig};
+use crate::shortcuts::{self, ShortcutConfig};
+use crate::tray::{self, TrayState};
+use crate::{av_sync, power, source_profiles, streaming};
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+/// Settings file name inside the app config directory
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Tray, shortcut and mount configuration file inside the app config directory
+const INTEGRATIONS_FILE: &str = "integrations.json";
+
+/// Identifies exported configuration files
+const CONFIG_APP_NAME: &str = "HubRemote";
+
+/// Current export format version; bump when a change can't be read by older builds
+const CONFIG_VERSION: u32 = 1;
+
+/// How audio-only files are presented by the player
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioOnlyBehavior {
+    /// Same as video: fullscreen with OSC
+    Fullscreen,
+    /// Stay windowed and show cover art if the file has any
+    #[default]
+    Windowed,
+    /// Stay windowed and render an audio visualizer
+    Visualizer,
+}
+
+/// What a left click on the tray icon does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayClickAction {
+    /// Show the main window, or hide it if it's visible
+    #[default]
+    ToggleWindow,
+    /// Show and focus the main window
+    ShowWindow,
+    /// Toggle playback (sent to the frontend as a `tray-command`)
+    PlayPause,
+    /// Do nothing; the menu is still on right click
+    None,
+}
+
+/// Screenshot output options applied to mpv
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenshotOptions {
+    /// Image format: png, jpg, jpeg or webp
+    pub format: String,
+    /// Output directory (None = mpv's working directory)
+    pub directory: Option<String>,
+    /// mpv filename template, e.g. "%F-%P" (None = mpv default)
+    pub template: Option<String>,
+}
+
+impl Default for ScreenshotOptions {
+    fn default() -> Self {
+        Self {
+            format: "jpg".to_string(),
+            directory: None,
+            template: None,
+        }
+    }
+}
+
+/// mpv on-screen controller behavior
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OscSettings {
+    /// Show mpv's OSC; when off mpv is spawned with `--no-osc`
+    pub enabled: bool,
+    /// Hide the OSC after this many ms without mouse movement
+    pub hide_delay_ms: u32,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hide_delay_ms: 500,
+        }
+    }
+}
+
+/// What mpv does at the end of a file (`--keep-open`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeepOpen {
+    /// Stay on the last frame unless there's a next playlist entry
+    #[default]
+    Yes,
+    /// Go idle (or advance to the next entry)
+    No,
+    /// Always stay on the last frame, even with a next entry
+    Always,
+}
+
+impl KeepOpen {
+    /// mpv option value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeepOpen::Yes => "yes",
+            KeepOpen::No => "no",
+            KeepOpen::Always => "always",
+        }
+    }
+}
+
+/// Rendering quality preset for mpv's scalers and debanding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QualityProfile {
+    /// Cheapest scaling for weak GPUs
+    Performance,
+    /// Close to mpv's defaults
+    Balanced,
+    /// Expensive scaling and debanding for capable GPUs
+    Quality,
+}
+
+impl QualityProfile {
+    /// mpv properties set by this profile
+    pub fn mpv_properties(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            QualityProfile::Performance => &[
+                ("scale", "bilinear"),
+                ("cscale", "bilinear"),
+                ("dscale", "bilinear"),
+                ("deband", "no"),
+                ("sigmoid-upscaling", "no"),
+            ],
+            QualityProfile::Balanced => &[
+                ("scale", "spline36"),
+                ("cscale", "spline36"),
+                ("dscale", "mitchell"),
+                ("deband", "no"),
+                ("sigmoid-upscaling", "yes"),
+            ],
+            QualityProfile::Quality => &[
+                ("scale", "ewa_lanczossharp"),
+                ("cscale", "ewa_lanczossharp"),
+                ("dscale", "mitchell"),
+                ("deband", "yes"),
+                ("sigmoid-upscaling", "yes"),
+            ],
+        }
+    }
+}
+
+/// Frame timing mode for smooth film playback on displays that can't match 24fps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MotionSmoothing {
+    /// Sync video to audio (mpv default; 3:2 pulldown judder on 60Hz displays)
+    #[default]
+    Off,
+    /// Resample audio to lock frames to the display refresh rate
+    DisplayResample,
+    /// Display resampling plus frame blending for in-between refreshes
+    InterpolateMotion,
+}
+
+impl MotionSmoothing {
+    /// mpv properties set by this mode
+    pub fn mpv_properties(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            MotionSmoothing::Off => &[("video-sync", "audio"), ("interpolation", "no")],
+            MotionSmoothing::DisplayResample => &[
+                ("video-sync", "display-resample"),
+                ("interpolation", "no"),
+            ],
+            MotionSmoothing::InterpolateMotion => &[
+                ("video-sync", "display-resample"),
+                ("interpolation", "yes"),
+                ("tscale", "oversample"),
+            ],
+        }
+    }
+}
+
+/// Deblocking/denoising for heavily compressed sources such as old web rips
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SourceCleanup {
+    #[default]
+    Off,
+    /// Mild temporal denoise; keeps detail on decent sources
+    Light,
+    /// Weak deblocking plus hqdn3d's default denoise strength
+    Medium,
+}
+
+impl SourceCleanup {
+    /// lavfi filter graph for this level (None = no filter). Strengths stay at
+    /// or below FFmpeg's defaults so clean sources aren't smeared.
+    pub fn filter_graph(&self) -> Option<&'static str> {
+        match self {
+            SourceCleanup::Off => None,
+            SourceCleanup::Light => Some("hqdn3d=2:1.5:3:2.25"),
+            SourceCleanup::Medium => Some("deblock=filter=weak:block=8,hqdn3d=4:3:6:4.5"),
+        }
+    }
+}
+
+/// Demuxer readahead and cache-pause tuning, mainly for slow rclone mounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BufferingStrategy {
+    /// Short readahead and no cache pauses: fast starts and seeks, may stutter
+    LowLatency,
+    /// Moderate readahead, pause briefly to refill when the cache runs dry
+    Smooth,
+    /// Long readahead and a full initial fill, for mounts that stall often
+    Aggressive,
+}
+
+impl BufferingStrategy {
+    /// mpv properties set by this strategy
+    pub fn mpv_properties(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            BufferingStrategy::LowLatency => &[
+                ("demuxer-thread", "yes"),
+                ("demuxer-readahead-secs", "5"),
+                ("cache-pause", "no"),
+                ("cache-pause-initial", "no"),
+            ],
+            BufferingStrategy::Smooth => &[
+                ("demuxer-thread", "yes"),
+                ("demuxer-readahead-secs", "30"),
+                ("cache-pause", "yes"),
+                ("cache-pause-wait", "2"),
+                ("cache-pause-initial", "no"),
+            ],
+            BufferingStrategy::Aggressive => &[
+                ("demuxer-thread", "yes"),
+                ("demuxer-readahead-secs", "120"),
+                ("cache-pause", "yes"),
+                ("cache-pause-wait", "5"),
+                ("cache-pause-initial", "yes"),
+            ],
+        }
+    }
+}
+
+/// How far the viewer sits from the screen, for sizing subtitles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ViewingDistance {
+    /// Desk or laptop
+    Near,
+    /// mpv's default subtitle size
+    #[default]
+    Normal,
+    /// Couch in front of a TV
+    Far,
+}
+
+impl ViewingDistance {
+    /// mpv `sub-scale` and `sub-font-size` for this distance
+    pub fn sub_size(&self) -> (f64, u32) {
+        match self {
+            ViewingDistance::Near => (0.9, 46),
+            ViewingDistance::Normal => (1.0, 55),
+            ViewingDistance::Far => (1.25, 60),
+        }
+    }
+}
+
+/// Saved subtitle sizing for `set_subtitle_scale_for_viewing`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleViewing {
+    pub distance: ViewingDistance,
+    /// Scale with the output height relative to 1080p instead of the window
+    pub match_resolution: bool,
+}
+
+/// Preferred track languages, in priority order (e.g. ["ja", "en"])
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrackPreferences {
+    pub audio_languages: Vec<String>,
+    /// Tried in order; the first language with a track wins
+    pub subtitle_languages: Vec<String>,
+    /// Turn on the first subtitle track when none matches `subtitle_languages`
+    pub enable_any_subtitle: bool,
+    /// Show forced subtitles in the audio's language when subtitles are off
+    pub honor_forced_subtitles: bool,
+}
+
+impl TrackPreferences {
+    pub fn is_empty(&self) -> bool {
+        self.audio_languages.is_empty()
+            && self.subtitle_languages.is_empty()
+            && !self.enable_any_subtitle
+            && !self.honor_forced_subtitles
+    }
+}
+
+/// Automatic skipping of intro/credits chapters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoSkipSettings {
+    pub enabled: bool,
+    /// Case-insensitive substrings matched against chapter titles
+    pub patterns: Vec<String>,
+}
+
+impl Default for AutoSkipSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            patterns: ["Intro", "Opening", "Credits", "Ending"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Volume and mute state the last player was left at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastVolume {
+    pub volume: i64,
+    pub muted: bool,
+}
+
+/// Kind of source a file is played from, each with its own mpv overrides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SourceProfile {
+    /// HTTP streams (Jellyfin and other network URLs)
+    Jellyfin,
+    /// Files on local disks
+    Local,
+    /// Files on the rclone mount
+    Mount,
+}
+
+/// What the stall watchdog does when playback freezes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StallRecovery {
+    /// Only emit `playback-stalled`
+    #[default]
+    Notify,
+    /// Seek to the current position to restart the demuxer's reads
+    Seek,
+    /// Reload the file at the current position
+    Reload,
+}
+
+/// Stall watchdog configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StallWatchdog {
+    /// Seconds `time-pos` may stay frozen while playing before it's a stall
+    pub timeout_secs: u64,
+    pub recovery: StallRecovery,
+}
+
+/// Shortest stall timeout; the player monitor only polls every few seconds
+const MIN_STALL_TIMEOUT_SECS: u64 = 10;
+
+/// Persisted backend settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// Reload the most recent media (paused) when the app starts
+    pub resume_on_startup: bool,
+    /// Player layout for audio-only files
+    pub audio_only_behavior: AudioOnlyBehavior,
+    /// Seconds to rewind before a saved resume position (0 = resume exactly)
+    pub resume_offset_secs: f64,
+    /// Screenshot format, directory and filename template
+    pub screenshot: ScreenshotOptions,
+    /// mpv OSC visibility and auto-hide delay
+    pub osc: OscSettings,
+    /// Shut mpv down after this many idle seconds (None = keep it running)
+    pub idle_destroy_timeout_secs: Option<u64>,
+    /// Detect playback frozen without mpv pausing for cache (None = off)
+    pub stall_watchdog: Option<StallWatchdog>,
+    /// End-of-file behavior
+    pub keep_open: KeepOpen,
+    /// HTTP User-Agent for mpv network streams (None = mpv default)
+    pub http_user_agent: Option<String>,
+    /// HTTP Referer for mpv network streams (None = not sent)
+    pub http_referrer: Option<String>,
+    /// Start the streaming server when the app launches
+    pub auto_start_streaming: bool,
+    /// Preferred streaming server port (None = default port)
+    pub streaming_port: Option<u16>,
+    /// mpv `audio-channels` for local playback, e.g. "stereo" (None = mpv default)
+    pub audio_channels: Option<String>,
+    /// mpv window title template, e.g. "${media-title} [${percent-pos}%]"
+    pub title_format: Option<String>,
+    /// Chapter auto-skip
+    pub auto_skip: AutoSkipSettings,
+    /// Scaler/deband preset (None = mpv defaults)
+    pub quality_profile: Option<QualityProfile>,
+    /// Video sync/interpolation mode for judder reduction
+    pub motion_smoothing: MotionSmoothing,
+    /// Deblock/denoise filter level for poor sources
+    pub source_cleanup: SourceCleanup,
+    /// mpv property overrides per source kind, set before each load
+    pub source_profiles: BTreeMap<SourceProfile, BTreeMap<String, String>>,
+    /// Audio/subtitle languages to select, kept when the track set changes
+    pub track_preferences: TrackPreferences,
+    /// Readahead/cache-pause preset (None = mpv defaults)
+    pub buffering_strategy: Option<BufferingStrategy>,
+    /// Subtitle size preset (None = mpv defaults)
+    pub subtitle_viewing: Option<SubtitleViewing>,
+    /// mpv `--vo` driver for new players, e.g. "gpu-next" (None = gpu)
+    pub video_output: Option<String>,
+    /// mpv `tone-mapping` curve for HDR video (None = mpv default)
+    pub tone_mapping: Option<String>,
+    /// Compress audio dynamic range for quiet late-night listening
+    pub night_mode: bool,
+    /// Show the OSD progress bar on the player when seeking from the remote
+    pub seek_osd: bool,
+    /// mpv `osd-level` (None = mpv default, 1)
+    pub osd_level: Option<u8>,
+    /// mpv `osd-duration` in milliseconds (None = mpv default, 1000)
+    pub osd_duration_ms: Option<u32>,
+    /// Pause playback when the system suspends and resume it on wake
+    pub pause_on_suspend: bool,
+    /// Video codecs to always software-decode (hardware decoding broke them)
+    pub software_decode_codecs: Vec<String>,
+    /// Volume/mute of the last player, restored when a new one starts
+    pub last_volume: Option<LastVolume>,
+    /// Start new players at mpv's default volume instead of the last one
+    pub reset_volume_on_start: bool,
+    /// Directory for mpv's on-disk demuxer cache (None = mpv default)
+    pub mpv_cache_dir: Option<String>,
+    /// Keep mpv's demuxer cache on disk instead of in memory
+    pub mpv_cache_on_disk: bool,
+    /// Audio/subtitle sync nudge step in ms (None = 50ms)
+    pub sync_step_ms: Option<u32>,
+    /// Kill mpv processes left behind by crashed instances at startup
+    pub cleanup_orphans_on_startup: bool,
+    /// Hostname advertised in stream URLs instead of the LAN IP
+    pub stream_hostname: Option<String>,
+    /// Client IP ranges (CIDR) allowed to fetch streams (empty = any client)
+    pub stream_ip_allowlist: Vec<String>,
+    /// Keep the main window and the mpv window above other windows
+    pub always_on_top: bool,
+    /// Stop global shortcuts from firing while the main window is focused
+    pub pause_shortcuts_when_focused: bool,
+    /// Left click on the tray icon
+    pub tray_left_click_action: TrayClickAction,
+}
+
+impl AppSettings {
+    /// Apply the resume offset to a saved position, clamped at 0
+    pub fn resume_position(&self, saved: f64) -> f64 {
+        (saved - self.resume_offset_secs.max(0.0)).max(0.0)
+    }
+}
+
+/// Full configuration for moving a setup between machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HubRemoteConfig {
+    /// Always "HubRemote"
+    pub app: String,
+    pub version: u32,
+    /// Close button hides the window to the tray
+    pub minimize_to_tray: bool,
+    /// Global shortcuts (None = never registered on the exporting machine)
+    pub shortcuts: Option<ShortcutConfig>,
+    pub shortcuts_enabled: bool,
+    /// Last used mount configuration (None = never mounted)
+    pub rclone: Option<RcloneConfig>,
+    /// Player and app preferences
+    pub settings: AppSettings,
+}
+
+/// Configuration kept by the tray, shortcuts and rclone modules rather than
+/// `AppSettings`, saved so it survives a restart
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SavedIntegrations {
+    /// None = never changed (the tray default applies)
+    minimize_to_tray: Option<bool>,
+    shortcuts: Option<ShortcutConfig>,
+    shortcuts_enabled: bool,
+    rclone: Option<RcloneConfig>,
+}
+
+/// What `import_settings` applied
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub config: HubRemoteConfig,
+    /// Parts that were saved but couldn't be applied live
+    pub warnings: Vec<String>,
+}
+
+/// Global settings state
+pub struct SettingsState {
+    settings: RwLock<AppSettings>,
+    path: RwLock<Option<PathBuf>>,
+}
+
+impl SettingsState {
+    pub fn new() -> Self {
+        Self {
+            settings: RwLock::new(AppSettings::default()),
+            path: RwLock::new(None),
+        }
+    }
+
+    /// Load settings from the app config directory
+    pub fn load(&self, app: &AppHandle) {
+        let Some(path) = config_file_path(app, SETTINGS_FILE) else {
+            log::warn!("No config directory available, settings will not be persisted");
+            return;
+        };
+
+        if let Some(settings) = read_json::<AppSettings>(&path) {
+            *self.settings.write() = settings;
+        }
+        *self.path.write() = Some(path);
+    }
+
+    /// Get a copy of the current settings
+    pub fn get(&self) -> AppSettings {
+        self.settings.read().clone()
+    }
+
+    /// Replace all settings and persist them
+    pub fn replace(&self, settings: AppSettings) -> Result<(), String> {
+        self.update(|s| *s = settings).map(|_| ())
+    }
+
+    /// Modify settings and persist them
+    pub fn update<F>(&self, f: F) -> Result<AppSettings, String>
+    where
+        F: FnOnce(&mut AppSettings),
+    {
+        let mut settings = self.settings.write();
+        f(&mut settings);
+
+        if let Some(path) = self.path.read().as_ref() {
+            write_json(path, &*settings)?;
+        }
+
+        Ok(settings.clone())
+    }
+}
+
+impl Default for SettingsState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolve a file in the app config directory, creating the directory if needed
+pub fn config_file_path(app: &AppHandle, file_name: &str) -> Option<PathBuf> {
+    let dir = app.path().app_config_dir().ok()?;
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        log::error!("Failed to create config directory {:?}: {}", dir, e);
+        return None;
+    }
+    Some(dir.join(file_name))
+}
+
+/// Read a JSON file, returning None if it's missing or invalid
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("Ignoring invalid {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Write a value as pretty-printed JSON
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {:?}: {}", path, e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Save the current tray, shortcut and mount configuration
+///
+/// Called whenever one of them changes; failures are only logged.
+pub fn save_integrations(app: &AppHandle) {
+    let Some(path) = config_file_path(app, INTEGRATIONS_FILE) else {
+        return;
+    };
+    let saved = SavedIntegrations {
+        minimize_to_tray: Some(tray::should_minimize_to_tray(app)),
+        shortcuts: shortcuts::active_config(),
+        shortcuts_enabled: shortcuts::are_shortcuts_enabled_by_user(),
+        rclone: rclone::last_config(),
+    };
+    if let Err(e) = write_json(&path, &saved) {
+        log::warn!("Failed to save tray/shortcut/mount configuration: {}", e);
+    }
+}
+
+/// Restore the tray, shortcut and mount configuration saved by
+/// `save_integrations`, registering the shortcuts if they were enabled
+pub fn restore_integrations(app: &AppHandle) {
+    let Some(saved) = config_file_path(app, INTEGRATIONS_FILE)
+        .and_then(|path| read_json::<SavedIntegrations>(&path))
+    else {
+        return;
+    };
+
+    if let Some(minimize) = saved.minimize_to_tray {
+        if let Some(tray_state) = app.try_state::<TrayState>() {
+            if let Ok(mut current) = tray_state.minimize_to_tray.lock() {
+                *current = minimize;
+            }
+        }
+    }
+    if let Some(rclone) = saved.rclone.as_ref() {
+        rclone::remember_config(rclone);
+    }
+    if let Some(shortcut_config) = saved.shortcuts.as_ref() {
+        if saved.shortcuts_enabled {
+            if let Err(e) = shortcuts::replace_shortcuts(app, shortcut_config) {
+                log::warn!("Failed to restore shortcuts: {}", e);
+            }
+        } else {
+            shortcuts::remember_config(shortcut_config);
+        }
+    }
+}
+
+/// Collect the current configuration for export
+fn export_config(app: &AppHandle) -> HubRemoteConfig {
+    HubRemoteConfig {
+        app: CONFIG_APP_NAME.to_string(),
+        version: CONFIG_VERSION,
+        minimize_to_tray: tray::should_minimize_to_tray(app),
+        shortcuts: shortcuts::active_config(),
+        shortcuts_enabled: shortcuts::are_shortcuts_enabled_by_user(),
+        rclone: rclone::last_config(),
+        settings: app
+            .try_state::<SettingsState>()
+            .map(|settings| settings.get())
+            .unwrap_or_default(),
+    }
+}
+
+/// Parse an exported configuration, rejecting other apps, newer versions and invalid values
+fn parse_config(json: &str) -> Result<HubRemoteConfig, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Not a valid JSON file: {}", e))?;
+
+    if value.get("app").and_then(|app| app.as_str()) != Some(CONFIG_APP_NAME) {
+        return Err("Not a HubRemote configuration export".to_string());
+    }
+    let version = value
+        .get("version")
+        .and_then(|version| version.as_u64())
+        .ok_or_else(|| "Configuration export has no version".to_string())?;
+    if version == 0 || version > u64::from(CONFIG_VERSION) {
+        return Err(format!(
+            "Configuration version {} isn't supported by this version of HubRemote (expected 1-{})",
+            version, CONFIG_VERSION
+        ));
+    }
+
+    let mut config: HubRemoteConfig =
+        serde_json::from_value(value).map_err(|e| format!("Invalid configuration: {}", e))?;
+
+    if let Some(hostname) = config.settings.stream_hostname.as_deref() {
+        if !streaming::is_valid_hostname(hostname) {
+            return Err(format!("Invalid stream hostname: {}", hostname));
+        }
+    }
+    for range in &config.settings.stream_ip_allowlist {
+        streaming::IpRange::parse(range).map_err(|e| e.to_string())?;
+    }
+    validate_player_settings(&config.settings)?;
+    if let Some(step_ms) = config.settings.sync_step_ms {
+        av_sync::validate_sync_step(step_ms)?;
+    }
+    let offset = config.settings.resume_offset_secs;
+    if !offset.is_finite() || offset < 0.0 {
+        return Err(format!("Invalid resume offset: {}", offset));
+    }
+    for property in config.settings.source_profiles.values().flat_map(|p| p.keys()) {
+        source_profiles::validate_property(property)?;
+    }
+    if let Some(watchdog) = config.settings.stall_watchdog.as_mut() {
+        watchdog.timeout_secs = watchdog.timeout_secs.max(MIN_STALL_TIMEOUT_SECS);
+    }
+    if let Some(rclone) = config.rclone.as_mut() {
+        rclone.mount_point = rclone::normalize_mount_point(&rclone.mount_point)?;
+    }
+
+    config.version = CONFIG_VERSION;
+    Ok(config)
+}
+
+/// Persist an imported configuration and apply it to the running app
+///
+/// `AppSettings` go to the settings file; tray, shortcut and mount
+/// configuration are saved with `save_integrations`.
+fn apply_config(app: &AppHandle, config: &HubRemoteConfig) -> Result<Vec<String>, String> {
+    let Some(state) = app.try_state::<SettingsState>() else {
+        return Err("Settings not available".to_string());
+    };
+    state.replace(config.settings.clone())?;
+
+    let mut warnings = Vec::new();
+
+    if let Some(tray_state) = app.try_state::<TrayState>() {
+        if let Ok(mut minimize) = tray_state.minimize_to_tray.lock() {
+            *minimize = config.minimize_to_tray;
+        }
+    }
+
+    if let Some(rclone) = config.rclone.as_ref() {
+        rclone::remember_config(rclone);
+    }
+
+    match config.shortcuts.as_ref() {
+        Some(shortcut_config) if config.shortcuts_enabled => {
+            if let Err(e) = shortcuts::replace_shortcuts(app, shortcut_config) {
+                warnings.push(format!("Shortcuts: {}", e));
+            }
+        }
+        _ => {
+            if let Err(e) = shortcuts::unregister_all_shortcuts(app) {
+                warnings.push(format!("Shortcuts: {}", e));
+            }
+        }
+    }
+
+    apply_stream_hostname(app);
+    apply_stream_ip_allowlist(app);
+    apply_always_on_top(app);
+    power::start(app);
+
+    if let Some(mpv) = app.try_state::<MpvState>() {
+        if mpv.is_initialized() {
+            apply_player_settings(app, &mpv);
+            apply_title_format(app, &mpv);
+        }
+    }
+
+    save_integrations(app);
+    Ok(warnings)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Get all persisted settings
+#[tauri::command]
+pub fn get_settings(state: State<SettingsState>) -> CommandResult<AppSettings> {
+    CommandResult::ok(state.get())
+}
+
+/// Export the full configuration (tray, shortcuts, rclone, player and app
+/// preferences) as a JSON string
+#[tauri::command]
+pub fn export_settings(app: AppHandle) -> CommandResult<String> {
+    match serde_json::to_string_pretty(&export_config(&app)) {
+        Ok(json) => CommandResult::ok(json),
+        Err(e) => CommandResult::err(format!("Failed to serialize settings: {}", e)),
+    }
+}
+
+/// Import a configuration produced by `export_settings`, saving it and
+/// applying it live where possible
+///
+/// The rclone configuration is returned for the frontend to use; mounting
+/// isn't started automatically.
+#[tauri::command]
+pub fn import_settings(app: AppHandle, json: String) -> CommandResult<ImportResult> {
+    let config = match parse_config(&json) {
+        Ok(config) => config,
+        Err(e) => return CommandResult::err(e),
+    };
+
+    match apply_config(&app, &config) {
+        Ok(warnings) => {
+            log::info!("Imported settings ({} warnings)", warnings.len());
+            CommandResult::ok(ImportResult { config, warnings })
+        }
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Enable/disable resuming the last session at startup
+#[tauri::command]
+pub fn set_resume_on_startup(state: State<SettingsState>, enabled: bool) -> CommandResult<bool> {
+    match state.update(|s| s.resume_on_startup = enabled) {
+        Ok(_) => CommandResult::ok(enabled),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Enable/disable restoring the last volume and mute state for new players
+#[tauri::command]
+pub fn set_remember_volume(state: State<SettingsState>, enabled: bool) -> CommandResult<bool> {
+    match state.update(|s| s.reset_volume_on_start = !enabled) {
+        Ok(_) => CommandResult::ok(enabled),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get whether new players restore the last volume and mute state
+#[tauri::command]
+pub fn get_remember_volume(state: State<SettingsState>) -> CommandResult<bool> {
+    CommandResult::ok(!state.get().reset_volume_on_start)
+}
+
+/// Set how audio-only files are presented
+#[tauri::command]
+pub fn set_audio_only_behavior(
+    state: State<SettingsState>,
+    behavior: AudioOnlyBehavior,
+) -> CommandResult<AudioOnlyBehavior> {
+    match state.update(|s| s.audio_only_behavior = behavior) {
+        Ok(_) => CommandResult::ok(behavior),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get the number of seconds playback rewinds before a resume point
+#[tauri::command]
+pub fn get_resume_offset(state: State<SettingsState>) -> CommandResult<f64> {
+    CommandResult::ok(state.get().resume_offset_secs)
+}
+
+/// Set the number of seconds playback rewinds before a resume point
+#[tauri::command]
+pub fn set_resume_offset(state: State<SettingsState>, seconds: f64) -> CommandResult<f64> {
+    if !seconds.is_finite() || seconds < 0.0 {
+        return CommandResult::err(format!("Invalid resume offset: {}", seconds));
+    }
+
+    match state.update(|s| s.resume_offset_secs = seconds) {
+        Ok(_) => CommandResult::ok(seconds),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get the idle timeout after which mpv is shut down (None = disabled)
+#[tauri::command]
+pub fn get_idle_destroy_timeout(state: State<SettingsState>) -> CommandResult<Option<u64>> {
+    CommandResult::ok(state.get().idle_destroy_timeout_secs)
+}
+
+/// Set the idle timeout after which mpv is shut down (None or 0 = disabled)
+#[tauri::command]
+pub fn set_idle_destroy_timeout(
+    state: State<SettingsState>,
+    seconds: Option<u64>,
+) -> CommandResult<Option<u64>> {
+    let seconds = seconds.filter(|s| *s > 0);
+    match state.update(|s| s.idle_destroy_timeout_secs = seconds) {
+        Ok(_) => CommandResult::ok(seconds),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Get the stall watchdog configuration (None = disabled)
+#[tauri::command]
+pub fn get_stall_watchdog(state: State<SettingsState>) -> CommandResult<Option<StallWatchdog>> {
+    CommandResult::ok(state.get().stall_watchdog)
+}
+
+/// Configure the stall watchdog (None or 0 = disabled)
+///
+/// Playback that stops advancing for `timeout_secs` while neither paused nor
+/// buffering emits `playback-stalled`, then `recovery` is attempted. The
+/// timeout is raised to at least 10 seconds.
+#[tauri::command]
+pub fn set_stall_watchdog(
+    state: State<SettingsState>,
+    timeout_secs: Option<u64>,
+    recovery: Option<StallRecovery>,
+) -> CommandResult<Option<StallWatchdog>> {
+    let watchdog = timeout_secs.filter(|s| *s > 0).map(|secs| StallWatchdog {
+        timeout_secs: secs.max(MIN_STALL_TIMEOUT_SECS),
+        recovery: recovery.unwrap_or_default(),
+    });
+    match state.update(|s| s.stall_watchdog = watchdog) {
+        Ok(_) => CommandResult::ok(watchdog),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Enable/disable starting the streaming server at launch, optionally on a preferred port
+#[tauri::command]
+pub fn set_auto_start_streaming(
+    state: State<SettingsState>,
+    enabled: bool,
+    port: Option<u16>,
+) -> CommandResult<AppSettings> {
+    match state.update(|s| {
+        s.auto_start_streaming = enabled;
+        if port.is_some() {
+            s.streaming_port = port;
+        }
+    }) {
+        Ok(settings) => CommandResult::ok(settings),
+        Err(e) => CommandResult::err(e),
+    }
+}
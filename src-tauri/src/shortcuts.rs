@@ -3,14 +3,23 @@
 //! Handles registration and management of global media key shortcuts
 //! that work even when the application is not focused.
 
+use crate::errors::{self, Subsystem};
+use crate::settings::{self, SettingsState};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::{AppHandle, Emitter};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 /// Global shortcuts enabled state
 static SHORTCUTS_ENABLED: AtomicBool = AtomicBool::new(false);
 
+/// Shortcut handling was switched off because the main window gained focus
+static PAUSED_FOR_FOCUS: AtomicBool = AtomicBool::new(false);
+
+/// Last successfully registered shortcut configuration
+static ACTIVE_CONFIG: Mutex<Option<ShortcutConfig>> = Mutex::new(None);
+
 /// Shortcut action types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +31,8 @@ pub enum ShortcutAction {
     VolumeUp,
     VolumeDown,
     Mute,
+    Panic,
+    FocusNowPlaying,
     Custom(String),
 }
 
@@ -44,6 +55,12 @@ pub struct ShortcutConfig {
     pub volume_up: Option<String>,
     pub volume_down: Option<String>,
     pub mute: Option<String>,
+    /// Instantly hide, pause and mute (see `panic_hide`)
+    #[serde(default)]
+    pub panic: Option<String>,
+    /// Show the window and jump to the playing item (see `focus_now_playing`)
+    #[serde(default)]
+    pub focus_now_playing: Option<String>,
 }
 
 impl Default for ShortcutConfig {
@@ -56,6 +73,8 @@ impl Default for ShortcutConfig {
             volume_up: None,
             volume_down: None,
             mute: None,
+            panic: None,
+            focus_now_playing: None,
         }
     }
 }
@@ -81,6 +100,18 @@ pub fn register_shortcuts_with_config(app: &AppHandle, config: &ShortcutConfig)
             .on_shortcut(shortcut, move |_app, _shortcut, event| {
                 if event.state == ShortcutState::Pressed && SHORTCUTS_ENABLED.load(Ordering::Relaxed) {
                     log::info!("Global shortcut triggered: {:?}", action_clone);
+                    // Act on panic immediately instead of waiting on the frontend
+                    match action_clone {
+                        ShortcutAction::Panic => {
+                            crate::commands::activate_panic(&app_handle, true);
+                        }
+                        ShortcutAction::FocusNowPlaying => {
+                            if let Err(e) = crate::tray::reveal_now_playing(&app_handle) {
+                                log::warn!("Failed to reveal now playing: {}", e);
+                            }
+                        }
+                        _ => {}
+                    }
                     let _ = app_handle.emit("global-shortcut", ShortcutEvent {
                         action: action_clone.clone(),
                         shortcut: key_str.clone(),
@@ -114,8 +145,17 @@ pub fn register_shortcuts_with_config(app: &AppHandle, config: &ShortcutConfig)
     if let Some(ref key) = config.mute {
         register_shortcut(key, ShortcutAction::Mute)?;
     }
+    if let Some(ref key) = config.panic {
+        register_shortcut(key, ShortcutAction::Panic)?;
+    }
+    if let Some(ref key) = config.focus_now_playing {
+        register_shortcut(key, ShortcutAction::FocusNowPlaying)?;
+    }
 
     SHORTCUTS_ENABLED.store(true, Ordering::Relaxed);
+    if let Ok(mut active) = ACTIVE_CONFIG.lock() {
+        *active = Some(config.clone());
+    }
     log::info!("Global shortcuts registered successfully");
 
     Ok(())
@@ -129,22 +169,75 @@ pub fn unregister_all_shortcuts(app: &AppHandle) -> Result<(), String> {
         .map_err(|e| format!("Failed to unregister shortcuts: {:?}", e))?;
 
     SHORTCUTS_ENABLED.store(false, Ordering::Relaxed);
+    PAUSED_FOR_FOCUS.store(false, Ordering::Relaxed);
     log::info!("Global shortcuts unregistered");
 
     Ok(())
 }
 
+/// Replace the registered shortcuts with `config`
+///
+/// The old shortcuts are unregistered first, so on failure none are active.
+pub fn replace_shortcuts(app: &AppHandle, config: &ShortcutConfig) -> Result<(), String> {
+    if let Err(e) = unregister_all_shortcuts(app) {
+        log::warn!("Failed to unregister existing shortcuts: {}", e);
+    }
+
+    register_shortcuts_with_config(app, config).map_err(|e| {
+        // The old shortcuts are gone too, so none are active now
+        errors::report(Subsystem::Shortcuts, "register_failed", e.clone(), true);
+        e
+    })
+}
+
+/// Keep `config` as the configuration to export without registering it
+pub fn remember_config(config: &ShortcutConfig) {
+    if let Ok(mut active) = ACTIVE_CONFIG.lock() {
+        *active = Some(config.clone());
+    }
+}
+
+/// Get the last registered shortcut configuration (None if shortcuts were never registered)
+pub fn active_config() -> Option<ShortcutConfig> {
+    ACTIVE_CONFIG.lock().ok().and_then(|active| active.clone())
+}
+
 /// Check if shortcuts are enabled
 pub fn are_shortcuts_enabled() -> bool {
     SHORTCUTS_ENABLED.load(Ordering::Relaxed)
 }
 
+/// Check if the user has shortcuts enabled, counting a pause while the main
+/// window is focused as enabled
+pub fn are_shortcuts_enabled_by_user() -> bool {
+    are_shortcuts_enabled() || PAUSED_FOR_FOCUS.load(Ordering::Relaxed)
+}
+
 /// Enable/disable shortcut handling (shortcuts stay registered but events are not emitted)
 pub fn set_shortcuts_enabled(enabled: bool) {
     SHORTCUTS_ENABLED.store(enabled, Ordering::Relaxed);
     log::info!("Global shortcuts {}", if enabled { "enabled" } else { "disabled" });
 }
 
+fn pause_when_focused(app: &AppHandle) -> bool {
+    app.try_state::<SettingsState>()
+        .is_some_and(|settings| settings.get().pause_shortcuts_when_focused)
+}
+
+/// Pause shortcut handling while the main window is focused, if enabled
+///
+/// Only undoes its own pause on blur, so shortcuts the user turned off stay off.
+pub fn handle_focus_change(app: &AppHandle, focused: bool) {
+    if focused {
+        if pause_when_focused(app) && are_shortcuts_enabled() {
+            PAUSED_FOR_FOCUS.store(true, Ordering::Relaxed);
+            set_shortcuts_enabled(false);
+        }
+    } else if PAUSED_FOR_FOCUS.swap(false, Ordering::Relaxed) {
+        set_shortcuts_enabled(true);
+    }
+}
+
 // ============================================
 // Tauri Commands
 // ============================================
@@ -179,7 +272,10 @@ impl<T> CommandResult<T> {
 #[tauri::command]
 pub fn enable_global_shortcuts(app: AppHandle) -> CommandResult<bool> {
     match register_media_shortcuts(&app) {
-        Ok(()) => CommandResult::ok(true),
+        Ok(()) => {
+            settings::save_integrations(&app);
+            CommandResult::ok(true)
+        }
         Err(e) => CommandResult::err(e),
     }
 }
@@ -188,7 +284,10 @@ pub fn enable_global_shortcuts(app: AppHandle) -> CommandResult<bool> {
 #[tauri::command]
 pub fn disable_global_shortcuts(app: AppHandle) -> CommandResult<bool> {
     match unregister_all_shortcuts(&app) {
-        Ok(()) => CommandResult::ok(true),
+        Ok(()) => {
+            settings::save_integrations(&app);
+            CommandResult::ok(true)
+        }
         Err(e) => CommandResult::err(e),
     }
 }
@@ -201,24 +300,49 @@ pub fn get_shortcuts_enabled() -> CommandResult<bool> {
 
 /// Set shortcuts enabled state (without re-registering)
 #[tauri::command]
-pub fn set_shortcuts_active(enabled: bool) -> CommandResult<bool> {
+pub fn set_shortcuts_active(app: AppHandle, enabled: bool) -> CommandResult<bool> {
+    // An explicit choice replaces any pause for focus
+    PAUSED_FOR_FOCUS.store(false, Ordering::Relaxed);
     set_shortcuts_enabled(enabled);
+    settings::save_integrations(&app);
     CommandResult::ok(enabled)
 }
 
 /// Register shortcuts with custom configuration
 #[tauri::command]
 pub fn register_custom_shortcuts(app: AppHandle, config: ShortcutConfig) -> CommandResult<bool> {
-    // First unregister existing shortcuts
-    if let Err(e) = unregister_all_shortcuts(&app) {
-        log::warn!("Failed to unregister existing shortcuts: {}", e);
+    match replace_shortcuts(&app, &config) {
+        Ok(()) => {
+            settings::save_integrations(&app);
+            CommandResult::ok(true)
+        }
+        Err(e) => CommandResult::err(e),
     }
+}
 
-    // Register new shortcuts
-    match register_shortcuts_with_config(&app, &config) {
-        Ok(()) => CommandResult::ok(true),
-        Err(e) => CommandResult::err(e),
+/// Get whether global shortcuts are paused while HubRemote's window is focused
+#[tauri::command]
+pub fn get_pause_shortcuts_when_focused(app: AppHandle) -> CommandResult<bool> {
+    CommandResult::ok(pause_when_focused(&app))
+}
+
+/// Pause global shortcuts while HubRemote's window is focused, so media keys
+/// pressed while typing in the app don't also fire globally
+#[tauri::command]
+pub fn set_pause_shortcuts_when_focused(app: AppHandle, enabled: bool) -> CommandResult<bool> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available".to_string());
+    };
+    if let Err(e) = settings.update(|s| s.pause_shortcuts_when_focused = enabled) {
+        return CommandResult::err(e);
     }
+
+    // Don't leave shortcuts paused after turning the behavior off
+    if !enabled && PAUSED_FOR_FOCUS.swap(false, Ordering::Relaxed) {
+        set_shortcuts_enabled(true);
+    }
+
+    CommandResult::ok(enabled)
 }
 
 /// Get default shortcut configuration
@@ -0,0 +1,200 @@
+//! Per-source mpv property profiles
+//!
+//! Network streams, local files and files on the rclone mount want different
+//! cache and network tuning. Each source profile holds mpv property overrides
+//! that are set before a file of that kind is loaded. Properties a profile
+//! changed are put back to their previous values when the next file uses a
+//! profile that doesn't set them, so one profile's tuning doesn't leak into
+//! the next file.
+
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use crate::rclone;
+use crate::settings::{SettingsState, SourceProfile};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
+
+/// Properties that control the player process itself rather than playback
+const BLOCKED_PROPERTIES: &[&str] = &[
+    "config",
+    "config-dir",
+    "idle",
+    "include",
+    "input-conf",
+    "input-ipc-server",
+    "load-scripts",
+    "script",
+    "scripts",
+    "wid",
+];
+
+/// A profile and its property overrides
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceProfileEntry {
+    pub name: SourceProfile,
+    pub properties: BTreeMap<String, String>,
+}
+
+/// Source profile tracking for the running player
+pub struct SourceProfileState {
+    /// Values properties had before a profile overrode them
+    originals: Mutex<HashMap<String, serde_json::Value>>,
+}
+
+impl SourceProfileState {
+    pub fn new() -> Self {
+        Self {
+            originals: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for SourceProfileState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pick the profile for a path or URL: network URLs are `jellyfin`, paths on
+/// the last rclone mount are `mount`, everything else is `local`
+pub fn detect(url: &str) -> SourceProfile {
+    if url.contains("://") && !url.starts_with("file://") {
+        return SourceProfile::Jellyfin;
+    }
+
+    let path = Path::new(url.strip_prefix("file://").unwrap_or(url));
+    let on_mount = rclone::last_config().is_some_and(|config| {
+        !config.mount_point.is_empty() && path.starts_with(&config.mount_point)
+    });
+    if on_mount {
+        SourceProfile::Mount
+    } else {
+        SourceProfile::Local
+    }
+}
+
+pub(crate) fn validate_property(name: &str) -> Result<(), String> {
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '/');
+    if !valid_name {
+        return Err(format!("Invalid mpv property name: {:?}", name));
+    }
+    if BLOCKED_PROPERTIES.contains(&name) {
+        return Err(format!("{} can't be set from a source profile", name));
+    }
+    Ok(())
+}
+
+/// Set the profile's properties before `url` is loaded, restoring ones the
+/// previous profile changed. `explicit` overrides the detected profile.
+pub fn apply(app: &AppHandle, state: &MpvState, url: &str, explicit: Option<SourceProfile>) {
+    let (Some(settings), Some(profiles)) = (
+        app.try_state::<SettingsState>(),
+        app.try_state::<SourceProfileState>(),
+    ) else {
+        return;
+    };
+
+    let profile = explicit.unwrap_or_else(|| detect(url));
+    let properties = settings
+        .get()
+        .source_profiles
+        .remove(&profile)
+        .unwrap_or_default();
+
+    let mut originals = profiles.originals.lock();
+    let result = state.with_player(|ipc| {
+        // Put back what the previous profile changed and this one doesn't set
+        let stale: Vec<String> = originals
+            .keys()
+            .filter(|name| !properties.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in stale {
+            if let Some(value) = originals.remove(&name) {
+                ipc.set_property(&name, value)?;
+            }
+        }
+
+        for (name, value) in &properties {
+            if !originals.contains_key(name) {
+                let original = ipc.get_property::<serde_json::Value>(name)?;
+                originals.insert(name.clone(), original);
+            }
+            ipc.set_property(name, value)?;
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(()) if !properties.is_empty() => {
+            log::info!("Applied {:?} source profile ({} properties)", profile, properties.len());
+        }
+        Ok(()) => {}
+        Err(e) => log::warn!("Failed to apply {:?} source profile: {}", profile, e),
+    }
+}
+
+/// Forget the saved originals when the player they were read from goes away
+pub fn reset(app: &AppHandle) {
+    if let Some(profiles) = app.try_state::<SourceProfileState>() {
+        profiles.originals.lock().clear();
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Set the mpv property overrides of a source profile (empty = no overrides)
+///
+/// Values use mpv's option syntax, e.g. `{"cache-secs": "60",
+/// "network-timeout": "30"}`. Takes effect from the next load.
+#[tauri::command]
+pub fn set_source_profile(
+    state: State<SettingsState>,
+    name: SourceProfile,
+    properties: BTreeMap<String, String>,
+) -> CommandResult<SourceProfileEntry> {
+    let properties: BTreeMap<String, String> = properties
+        .into_iter()
+        .map(|(name, value)| (name.trim().to_string(), value))
+        .collect();
+    if let Some(e) = properties.keys().find_map(|name| validate_property(name).err()) {
+        return CommandResult::err(e);
+    }
+
+    let result = state.update(|s| {
+        if properties.is_empty() {
+            s.source_profiles.remove(&name);
+        } else {
+            s.source_profiles.insert(name, properties.clone());
+        }
+    });
+    match result {
+        Ok(_) => CommandResult::ok(SourceProfileEntry { name, properties }),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// List all source profiles with their overrides
+#[tauri::command]
+pub fn list_source_profiles(
+    state: State<SettingsState>,
+) -> CommandResult<Vec<SourceProfileEntry>> {
+    let mut saved = state.get().source_profiles;
+    let entries = [SourceProfile::Jellyfin, SourceProfile::Local, SourceProfile::Mount]
+        .into_iter()
+        .map(|name| SourceProfileEntry {
+            name,
+            properties: saved.remove(&name).unwrap_or_default(),
+        })
+        .collect();
+    CommandResult::ok(entries)
+}
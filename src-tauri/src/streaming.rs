@@ -2,25 +2,39 @@
 //!
 //! Provides local HTTP streaming for media files, enabling Cast to TV functionality.
 //! Supports Range requests for video seeking.
+//!
+//! A file stream can be switched to transcoded serving for TVs that can't
+//! decode the original codecs: one ffmpeg process per mode switch re-encodes
+//! it to H.264/AAC MPEG-TS under the same stream URL. A client that
+//! reconnects picks the output up where the previous connection left it.
+//! Transcoded streams can't seek; Range requests past the start get 416.
 
+use crate::errors::{self, Subsystem};
 use axum::{
     body::Body,
-    extract::{Path, State},
-    http::{header, HeaderMap, StatusCode},
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     response::{IntoResponse, Response},
     routing::get,
     Router,
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
-use tokio::sync::oneshot;
-use tower_http::cors::{Any, CorsLayer};
+use tokio::process::ChildStdout;
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
 #[derive(Error, Debug)]
 pub enum StreamError {
@@ -30,46 +44,532 @@ pub enum StreamError {
     NotRunning,
     #[error("Failed to start server: {0}")]
     StartError(String),
+    #[error("Port {0} is still held by a previous HubRemote instance (pid {1}); close it or choose another port")]
+    PortHeldByStaleInstance(u16, u32),
+    #[error("Port {0} is in use by another application; choose another port")]
+    PortInUse(u16),
+    #[error("Invalid hostname: {0}")]
+    InvalidHostname(String),
+    #[error("Invalid IP range: {0} (expected an address or CIDR like 192.168.1.0/24)")]
+    InvalidIpRange(String),
+    #[error("Stream data is too large ({0} bytes, limit {1})")]
+    DataTooLarge(usize, usize),
     #[error("File not found: {0}")]
     FileNotFound(String),
+    #[error("Stream not found: {0}")]
+    StreamNotFound(String),
+    #[error("In-memory streams can only be served directly")]
+    InMemoryStream,
+    #[error("Invalid chunk size {0} bytes (expected {1} to {2} bytes)")]
+    InvalidChunkSize(usize, usize, usize),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
+/// Port used when the frontend doesn't pick one
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Read buffer size per connection for content that's neither audio nor video
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Read buffer for audio: small reads get the first bytes out sooner
+const AUDIO_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Read buffer for video: large reads keep high-bitrate files flowing
+const VIDEO_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Granularity of read buffer accounting; chunk sizes are multiples of it
+const BUFFER_UNIT: usize = 4 * 1024;
+
+/// Largest read buffer a stream may ask for
+const MAX_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Payload served to a bandwidth test client; the test ends it early
+const SPEED_TEST_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Generated payload chunk for bandwidth tests
+static SPEED_TEST_CHUNK: [u8; CHUNK_SIZE] = [0; CHUNK_SIZE];
+
+/// Largest in-memory stream that's registered as-is; bigger data goes to a temp file
+const MEMORY_STREAM_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// Cap on in-memory stream data across all streams
+const MAX_MEMORY_STREAMS: usize = 64 * 1024 * 1024;
+
+/// Largest accepted byte stream (memory or temp file)
+pub const MAX_BYTES_STREAM: usize = 512 * 1024 * 1024;
+
+/// Cap on read buffer memory across all connections (32MB = 128 video
+/// connections at `VIDEO_CHUNK_SIZE`, more for audio and other content).
+/// Connections beyond this wait until another one finishes.
+pub const MAX_BUFFER_MEMORY: usize = 32 * 1024 * 1024;
+
+/// Read buffer memory usage
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferUsage {
+    /// Bytes reserved by active connections
+    pub in_use: usize,
+    /// Maximum bytes that may be reserved
+    pub limit: usize,
+    /// Connections currently holding a buffer
+    pub active_connections: usize,
+}
+
+/// Per-stream transfer counters, updated by the handlers
+#[derive(Debug, Default)]
+pub struct StreamStats {
+    /// Total bytes sent to clients
+    pub bytes_served: AtomicU64,
+    /// Number of requests received
+    pub requests: AtomicU64,
+    /// Unix timestamp (seconds) of the last request (0 = never accessed)
+    pub last_access: AtomicU64,
+}
+
+/// How a file stream is served
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamMode {
+    /// The file as-is, with Range support
+    #[default]
+    Direct,
+    /// Re-encoded by ffmpeg to H.264/AAC MPEG-TS (no seeking)
+    Transcode,
+}
+
+/// A registered stream
+#[derive(Debug, Clone)]
+pub struct StreamEntry {
+    /// File to serve, or just the display name for in-memory streams
+    pub path: PathBuf,
+    /// Contents served from memory instead of `path`
+    pub data: Option<bytes::Bytes>,
+    /// Content type to send instead of guessing from the extension
+    pub content_type: Option<String>,
+    /// `path` is a temp file owned by the stream, deleted when it's removed
+    pub temp_file: bool,
+    pub mode: StreamMode,
+    /// Read buffer size for this stream's connections
+    pub chunk_size: usize,
+    /// Unix timestamp (seconds) when the stream was registered
+    pub created_at: u64,
+    pub stats: Arc<StreamStats>,
+}
+
+/// Read-only snapshot of a stream for listing
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamSummary {
+    pub stream_id: String,
+    pub path: String,
+    pub filename: String,
+    pub created_at: u64,
+    pub bytes_served: u64,
+    pub requests: u64,
+    pub last_access: Option<u64>,
+    pub mode: StreamMode,
+    pub chunk_size: usize,
+}
+
+/// Read buffer memory shared by all connections
+struct ReadBuffers {
+    /// One permit per `BUFFER_UNIT` of `MAX_BUFFER_MEMORY`
+    permits: Arc<Semaphore>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl ReadBuffers {
+    fn new() -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(MAX_BUFFER_MEMORY / BUFFER_UNIT)),
+            connections: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Reserve a read buffer of `chunk_size` bytes, waiting for memory to free
+    /// up. None if the server is shutting down.
+    async fn lease(&self, chunk_size: usize) -> Option<BufferLease> {
+        let units = chunk_size.div_ceil(BUFFER_UNIT).max(1) as u32;
+        let permit = self.permits.clone().acquire_many_owned(units).await.ok()?;
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        Some(BufferLease {
+            _permit: permit,
+            connections: self.connections.clone(),
+        })
+    }
+}
+
+/// A connection's read buffer reservation, released on drop
+struct BufferLease {
+    _permit: OwnedSemaphorePermit,
+    connections: Arc<AtomicUsize>,
+}
+
+impl Drop for BufferLease {
+    fn drop(&mut self) {
+        self.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Default read buffer size for a content type
+fn chunk_size_for(content_type: &str) -> usize {
+    if content_type.starts_with("audio/") {
+        AUDIO_CHUNK_SIZE
+    } else if content_type.starts_with("video/") {
+        VIDEO_CHUNK_SIZE
+    } else {
+        CHUNK_SIZE
+    }
+}
+
+/// Check a requested read buffer size, rounding it up to the accounting unit
+fn validate_chunk_size(chunk_size: usize) -> Result<usize, StreamError> {
+    if !(BUFFER_UNIT..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+        return Err(StreamError::InvalidChunkSize(chunk_size, BUFFER_UNIT, MAX_CHUNK_SIZE));
+    }
+    Ok(chunk_size.div_ceil(BUFFER_UNIT) * BUFFER_UNIT)
+}
+
+/// A client address range in CIDR notation, e.g. "192.168.1.0/24"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    /// Network address with the host bits cleared
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    /// Parse "addr/prefix", or a bare address as a single-host range
+    pub fn parse(input: &str) -> Result<Self, StreamError> {
+        let input = input.trim();
+        let invalid = || StreamError::InvalidIpRange(input.to_string());
+        let (address, prefix) = match input.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (input, None),
+        };
+
+        let address: IpAddr = address.parse().map_err(|_| invalid())?;
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(invalid)?,
+            None => max_prefix,
+        };
+
+        Ok(Self {
+            network: mask_address(address, prefix),
+            prefix,
+        })
+    }
+
+    /// Check whether an address is in the range (IPv4-mapped IPv6 counts as IPv4)
+    pub fn contains(&self, address: IpAddr) -> bool {
+        let address = address.to_canonical();
+        address.is_ipv4() == self.network.is_ipv4()
+            && mask_address(address, self.prefix) == self.network
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Clear the bits of an address after the first `prefix`
+fn mask_address(address: IpAddr, prefix: u8) -> IpAddr {
+    match address {
+        IpAddr::V4(v4) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            IpAddr::V4((u32::from(v4) & mask).into())
+        }
+        IpAddr::V6(v6) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            IpAddr::V6((u128::from(v6) & mask).into())
+        }
+    }
+}
+
 /// Shared state for streaming server
+/// ffmpeg transcoding a stream, shared by the connections serving it
+struct Transcoder {
+    child: tokio::process::Child,
+    output: Arc<tokio::sync::Mutex<ChildStdout>>,
+    /// Incremented by each connection that takes over `output`
+    reader: Arc<AtomicU64>,
+}
+
 #[derive(Clone)]
 pub struct StreamingState {
-    /// Map of stream IDs to file paths
-    pub streams: Arc<RwLock<HashMap<String, PathBuf>>>,
+    /// Map of stream IDs to registered files
+    pub streams: Arc<RwLock<HashMap<String, StreamEntry>>>,
+    /// Bounds total read buffer memory
+    buffers: Arc<ReadBuffers>,
+    /// Running bandwidth tests and the bytes served for each
+    speed_tests: Arc<RwLock<HashMap<String, Arc<AtomicU64>>>>,
+    /// ffmpeg process of each transcoded stream
+    transcoders: Arc<Mutex<HashMap<String, Transcoder>>>,
+    /// Stream IDs registered together as a cast queue, by queue ID
+    queues: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Client ranges allowed to fetch streams (empty = any client)
+    ip_allowlist: Arc<RwLock<Vec<IpRange>>>,
 }
 
 impl StreamingState {
     pub fn new() -> Self {
         Self {
             streams: Arc::new(RwLock::new(HashMap::new())),
+            buffers: Arc::new(ReadBuffers::new()),
+            speed_tests: Arc::new(RwLock::new(HashMap::new())),
+            transcoders: Arc::new(Mutex::new(HashMap::new())),
+            queues: Arc::new(RwLock::new(HashMap::new())),
+            ip_allowlist: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Check a client against the allowlist; this machine is always allowed
+    /// so local previews and casting bridges keep working
+    fn allows_client(&self, address: IpAddr) -> bool {
+        let allowlist = self.ip_allowlist.read();
+        allowlist.is_empty()
+            || address.to_canonical().is_loopback()
+            || allowlist.iter().any(|range| range.contains(address))
+    }
+
+    /// Current read buffer memory usage
+    pub fn buffer_usage(&self) -> BufferUsage {
+        let total = MAX_BUFFER_MEMORY / BUFFER_UNIT;
+        let used = total - self.buffers.permits.available_permits();
+        BufferUsage {
+            in_use: used * BUFFER_UNIT,
+            limit: MAX_BUFFER_MEMORY,
+            active_connections: self.buffers.connections.load(Ordering::Relaxed),
         }
     }
 
     /// Register a file for streaming, returns stream ID
-    pub fn register_stream(&self, path: PathBuf) -> String {
+    pub fn register_stream(&self, path: PathBuf, chunk_size: usize) -> String {
+        self.insert_stream(StreamEntry {
+            path,
+            data: None,
+            content_type: None,
+            temp_file: false,
+            mode: StreamMode::Direct,
+            chunk_size,
+            created_at: unix_now(),
+            stats: Arc::new(StreamStats::default()),
+        })
+    }
+
+    /// Register generated content for streaming, returns stream ID
+    ///
+    /// Small payloads are kept in memory; larger ones, or ones that would push
+    /// in-memory streams past their cap, are written to a temp file.
+    pub fn register_bytes_stream(
+        &self,
+        data: Vec<u8>,
+        content_type: &str,
+        filename: &str,
+    ) -> Result<String, StreamError> {
+        if data.len() > MAX_BYTES_STREAM {
+            return Err(StreamError::DataTooLarge(data.len(), MAX_BYTES_STREAM));
+        }
+
+        let in_memory: usize = self
+            .streams
+            .read()
+            .values()
+            .filter_map(|entry| entry.data.as_ref().map(|data| data.len()))
+            .sum();
+        let keep_in_memory = data.len() <= MEMORY_STREAM_THRESHOLD
+            && in_memory + data.len() <= MAX_MEMORY_STREAMS;
+
+        let id = uuid_simple();
+        let (path, data, temp_file) = if keep_in_memory {
+            (PathBuf::from(filename), Some(bytes::Bytes::from(data)), false)
+        } else {
+            let dir = std::env::temp_dir().join("hubremote-streams");
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join(format!("{}-{}", id, filename));
+            std::fs::write(&path, &data)?;
+            (path, None, true)
+        };
+
+        self.streams.write().insert(
+            id.clone(),
+            StreamEntry {
+                path,
+                data,
+                content_type: Some(content_type.to_string()),
+                temp_file,
+                mode: StreamMode::Direct,
+                chunk_size: chunk_size_for(content_type),
+                created_at: unix_now(),
+                stats: Arc::new(StreamStats::default()),
+            },
+        );
+        Ok(id)
+    }
+
+    fn insert_stream(&self, entry: StreamEntry) -> String {
         let id = uuid_simple();
-        self.streams.write().insert(id.clone(), path);
+        self.streams.write().insert(id.clone(), entry);
         id
     }
 
-    /// Get file path for stream ID
-    pub fn get_stream_path(&self, id: &str) -> Option<PathBuf> {
+    /// Get the registered entry for stream ID
+    pub fn get_stream(&self, id: &str) -> Option<StreamEntry> {
         self.streams.read().get(id).cloned()
     }
 
+    /// Snapshot all registered streams, oldest first
+    pub fn list_streams(&self) -> Vec<StreamSummary> {
+        let mut streams: Vec<StreamSummary> = self
+            .streams
+            .read()
+            .iter()
+            .map(|(id, entry)| {
+                let last_access = entry.stats.last_access.load(Ordering::Relaxed);
+                StreamSummary {
+                    stream_id: id.clone(),
+                    path: entry.path.to_string_lossy().into_owned(),
+                    filename: stream_filename(&entry.path),
+                    created_at: entry.created_at,
+                    bytes_served: entry.stats.bytes_served.load(Ordering::Relaxed),
+                    requests: entry.stats.requests.load(Ordering::Relaxed),
+                    last_access: (last_access > 0).then_some(last_access),
+                    mode: entry.mode,
+                    chunk_size: entry.chunk_size,
+                }
+            })
+            .collect();
+        streams.sort_by_key(|s| s.created_at);
+        streams
+    }
+
+    /// Switch a file stream between direct and transcoded serving
+    ///
+    /// A running transcode is stopped; the client reconnects to the same URL
+    /// and gets the new mode.
+    pub fn set_stream_mode(&self, id: &str, mode: StreamMode) -> Result<(), StreamError> {
+        {
+            let mut streams = self.streams.write();
+            let entry = streams
+                .get_mut(id)
+                .ok_or_else(|| StreamError::StreamNotFound(id.to_string()))?;
+            if entry.data.is_some() && mode != StreamMode::Direct {
+                return Err(StreamError::InMemoryStream);
+            }
+            entry.mode = mode;
+        }
+        self.stop_transcoder(id);
+        Ok(())
+    }
+
+    /// Kill the ffmpeg process serving a stream, if any
+    fn stop_transcoder(&self, id: &str) {
+        if let Some(mut transcoder) = self.transcoders.lock().remove(id) {
+            let _ = transcoder.child.start_kill();
+        }
+    }
+
+    /// Output of the stream's running ffmpeg and its reader generation,
+    /// starting ffmpeg if there's none
+    fn transcoder_output(
+        &self,
+        id: &str,
+        path: &std::path::Path,
+    ) -> Result<(Arc<tokio::sync::Mutex<ChildStdout>>, Arc<AtomicU64>), String> {
+        let mut transcoders = self.transcoders.lock();
+        if let Some(transcoder) = transcoders.get_mut(id) {
+            if matches!(transcoder.child.try_wait(), Ok(None)) {
+                return Ok((transcoder.output.clone(), transcoder.reader.clone()));
+            }
+        }
+
+        let mut child = spawn_transcoder(path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                "ffmpeg not found; install FFmpeg to transcode streams".to_string()
+            } else {
+                format!("Failed to start ffmpeg: {}", e)
+            }
+        })?;
+        let stdout = child.stdout.take().ok_or_else(|| "ffmpeg has no output".to_string())?;
+        let transcoder = Transcoder {
+            child,
+            output: Arc::new(tokio::sync::Mutex::new(stdout)),
+            reader: Arc::new(AtomicU64::new(0)),
+        };
+        let output = (transcoder.output.clone(), transcoder.reader.clone());
+        transcoders.insert(id.to_string(), transcoder);
+        log::info!("Transcoding stream {} from {:?}", id, path);
+        Ok(output)
+    }
+
+    /// Forget a transcoder whose output ended, unless it was already replaced
+    fn finish_transcoder(&self, id: &str, output: &Arc<tokio::sync::Mutex<ChildStdout>>) {
+        let mut transcoders = self.transcoders.lock();
+        if transcoders
+            .get(id)
+            .is_some_and(|transcoder| Arc::ptr_eq(&transcoder.output, output))
+        {
+            transcoders.remove(id);
+            log::info!("Transcode of stream {} finished", id);
+        }
+    }
+
     /// Remove a stream
     pub fn remove_stream(&self, id: &str) {
-        self.streams.write().remove(id);
+        if let Some(entry) = self.streams.write().remove(id) {
+            remove_temp_file(&entry);
+        }
+        self.stop_transcoder(id);
     }
 
     /// Clear all streams
     pub fn clear_streams(&self) {
-        self.streams.write().clear();
+        for (_, entry) in self.streams.write().drain() {
+            remove_temp_file(&entry);
+        }
+        self.speed_tests.write().clear();
+        for (_, mut transcoder) in self.transcoders.lock().drain() {
+            let _ = transcoder.child.start_kill();
+        }
+        self.queues.write().clear();
+    }
+
+    /// Group streams as a queue, returns the queue ID
+    pub fn register_queue(&self, stream_ids: Vec<String>) -> String {
+        let id = uuid_simple();
+        self.queues.write().insert(id.clone(), stream_ids);
+        id
+    }
+
+    /// Remove a queue and its streams. Returns how many streams were removed.
+    pub fn remove_queue(&self, id: &str) -> Option<usize> {
+        let stream_ids = self.queues.write().remove(id)?;
+        for stream_id in &stream_ids {
+            self.remove_stream(stream_id);
+        }
+        Some(stream_ids.len())
+    }
+
+    /// Start a bandwidth test, returning its ID and served-bytes counter
+    pub fn start_speed_test(&self) -> (String, Arc<AtomicU64>) {
+        let id = uuid_simple();
+        let bytes_served = Arc::new(AtomicU64::new(0));
+        self.speed_tests
+            .write()
+            .insert(id.clone(), bytes_served.clone());
+        (id, bytes_served)
+    }
+
+    /// End a bandwidth test; its transfer stops at the next chunk
+    pub fn end_speed_test(&self, id: &str) {
+        self.speed_tests.write().remove(id);
     }
 }
 
@@ -79,14 +579,213 @@ impl Default for StreamingState {
     }
 }
 
+/// Delete the temp file backing a byte stream
+fn remove_temp_file(entry: &StreamEntry) {
+    if entry.temp_file {
+        if let Err(e) = std::fs::remove_file(&entry.path) {
+            log::warn!("Failed to remove stream temp file {:?}: {}", entry.path, e);
+        }
+    }
+}
+
+/// Format the server base URL, bracketing IPv6 addresses
+pub fn format_base_url(ip: &str, port: u16) -> String {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V6(v6)) => format!("http://[{}]:{}", v6, port),
+        _ => format!("http://{}:{}", ip, port),
+    }
+}
+
+/// Percent-encode a URL path segment (RFC 3986 unreserved characters pass through)
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Format the URL for a stream under `base_url`
+///
+/// The filename is only a hint for receivers and is percent-encoded so names
+/// with spaces, `#`, `?` or non-ASCII characters still form a valid URL.
+pub fn format_stream_url(base_url: &str, stream_id: &str, filename: Option<&str>) -> String {
+    match filename {
+        Some(fname) => format!(
+            "{}/stream/{}/{}",
+            base_url,
+            stream_id,
+            encode_path_segment(fname)
+        ),
+        None => format!("{}/stream/{}", base_url, stream_id),
+    }
+}
+
+/// Filename used in stream URLs (helps TVs identify the content type)
+pub fn stream_filename(path: &std::path::Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "video.mp4".to_string())
+}
+
+fn unix_now() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 /// Simple UUID generator (no external dependency)
+///
+/// A counter is appended so IDs made within one clock tick (e.g. a batch of
+/// streams) don't collide.
 fn uuid_simple() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_nanos();
-    format!("{:x}", now)
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed) & 0xffff;
+    format!("{:x}{:04x}", now, seq)
+}
+
+/// Check a DNS hostname: dot-separated labels of letters, digits and inner hyphens
+pub fn is_valid_hostname(hostname: &str) -> bool {
+    !hostname.is_empty()
+        && hostname.len() <= 253
+        && hostname.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+}
+
+/// App name reported by `/version`, used to recognize our own servers
+const APP_NAME: &str = "HubRemote";
+
+/// How long to wait when probing a port held by another process
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Identity of this process, returned by `/version`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceInfo {
+    pub app: String,
+    pub version: String,
+    /// Random per-process ID, to tell instances apart
+    pub instance_id: String,
+    pub pid: u32,
+}
+
+/// This process's identity
+pub fn instance_info() -> &'static InstanceInfo {
+    static INSTANCE: OnceLock<InstanceInfo> = OnceLock::new();
+    INSTANCE.get_or_init(|| InstanceInfo {
+        app: APP_NAME.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        instance_id: uuid_simple(),
+        pid: std::process::id(),
+    })
+}
+
+/// Ask whatever is listening on `port` for its `/version`; Some if it's a HubRemote server
+fn probe_instance(port: u16) -> Option<InstanceInfo> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = std::net::TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(PROBE_TIMEOUT)).ok()?;
+
+    let request = format!(
+        "GET /version HTTP/1.0\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+        port
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (_, body) = response.split_once("\r\n\r\n")?;
+
+    serde_json::from_str::<InstanceInfo>(body)
+        .ok()
+        .filter(|info| info.app == APP_NAME)
+}
+
+/// Turn a bind failure into a specific error, identifying a stale HubRemote on the port
+fn bind_error(port: u16, error: std::io::Error) -> StreamError {
+    if error.kind() != std::io::ErrorKind::AddrInUse {
+        return StreamError::StartError(error.to_string());
+    }
+
+    let error = match probe_instance(port) {
+        Some(info) => StreamError::PortHeldByStaleInstance(port, info.pid),
+        None => StreamError::PortInUse(port),
+    };
+    let code = match error {
+        StreamError::PortHeldByStaleInstance(..) => "port_held_by_stale_instance",
+        _ => "port_in_use",
+    };
+    errors::report(Subsystem::Streaming, code, error.to_string(), true);
+    error
+}
+
+/// `GET /version`: identifies this server so a new instance can detect a stale one
+async fn version_handler() -> axum::Json<InstanceInfo> {
+    axum::Json(instance_info().clone())
+}
+
+/// How many times `wait_for_port` checks whether the old listener released the port
+const REBIND_ATTEMPTS: u32 = 20;
+
+/// Delay between those checks
+const REBIND_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Options for starting the streaming server
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub port: u16,
+    /// Bind 127.0.0.1 instead of all interfaces
+    pub loopback_only: bool,
+    /// Origins allowed by CORS (None = any origin, which most TVs need)
+    pub cors_allowed_origins: Option<Vec<String>>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT,
+            loopback_only: false,
+            cors_allowed_origins: None,
+        }
+    }
+}
+
+/// Build the CORS layer: wildcard by default, or only the given origins
+fn build_cors_layer(allowed_origins: Option<&[String]>) -> Result<CorsLayer, StreamError> {
+    let layer = CorsLayer::new().allow_methods(Any).allow_headers(Any);
+
+    match allowed_origins {
+        None => Ok(layer.allow_origin(Any)),
+        Some(origins) => {
+            let origins = origins
+                .iter()
+                .map(|origin| {
+                    HeaderValue::from_str(origin.trim().trim_end_matches('/')).map_err(|_| {
+                        StreamError::StartError(format!("Invalid CORS origin: {}", origin))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(layer.allow_origin(AllowOrigin::list(origins)))
+        }
+    }
 }
 
 /// Streaming server manager
@@ -95,6 +794,11 @@ pub struct StreamingServer {
     shutdown_tx: Option<oneshot::Sender<()>>,
     port: u16,
     local_ip: Option<String>,
+    loopback_only: bool,
+    /// Hostname used in URLs instead of the LAN IP (e.g. "desktop.local")
+    hostname: Option<String>,
+    /// Options the running server was started with
+    options: Option<ServerOptions>,
 }
 
 impl StreamingServer {
@@ -104,19 +808,54 @@ impl StreamingServer {
             shutdown_tx: None,
             port: 0,
             local_ip: None,
+            loopback_only: false,
+            hostname: None,
+            options: None,
         }
     }
 
+    /// Advertise a hostname in stream URLs instead of the numeric IP (None = use the IP)
+    ///
+    /// The server still binds all interfaces; the name just has to resolve to
+    /// this machine on the TV's side. Ignored in loopback-only mode.
+    pub fn set_hostname(&mut self, hostname: Option<String>) -> Result<(), StreamError> {
+        if let Some(hostname) = hostname.as_deref() {
+            if !is_valid_hostname(hostname) {
+                return Err(StreamError::InvalidHostname(hostname.to_string()));
+            }
+        }
+        self.hostname = hostname.map(|h| h.to_lowercase());
+        Ok(())
+    }
+
     /// Start the streaming server
-    pub async fn start(&mut self, port: u16) -> Result<(String, u16), StreamError> {
+    ///
+    /// With `loopback_only` the server binds 127.0.0.1 and is unreachable from
+    /// the LAN; only a casting bridge running on this machine can use it.
+    ///
+    /// Binds synchronously so bind errors are reported to the caller, then
+    /// serves on the Tauri async runtime. Safe to call from any thread.
+    pub fn start(&mut self, options: ServerOptions) -> Result<(String, u16), StreamError> {
         if self.shutdown_tx.is_some() {
             return Err(StreamError::AlreadyRunning);
         }
 
+        let started_with = options.clone();
+        let ServerOptions {
+            port,
+            loopback_only,
+            cors_allowed_origins,
+        } = options;
+        let cors = build_cors_layer(cors_allowed_origins.as_deref())?;
+
         // Get local IP address
-        let local_ip = local_ip_address::local_ip()
-            .map(|ip| ip.to_string())
-            .unwrap_or_else(|_| "127.0.0.1".to_string());
+        let local_ip = if loopback_only {
+            "127.0.0.1".to_string()
+        } else {
+            local_ip_address::local_ip()
+                .map(|ip| ip.to_string())
+                .unwrap_or_else(|_| "127.0.0.1".to_string())
+        };
 
         log::info!("Starting streaming server on {}:{}", local_ip, port);
 
@@ -125,42 +864,154 @@ impl StreamingServer {
 
         // Build router
         let app = Router::new()
-            .route("/stream/{id}", get(stream_handler))
-            .route("/stream/{id}/{filename}", get(stream_handler_with_filename))
+            .route("/version", get(version_handler))
+            .route("/stream/:id", get(stream_handler))
+            .route("/stream/:id/:filename", get(stream_handler_with_filename))
+            .route("/speedtest/:id", get(speed_test_handler))
             .with_state(state)
-            .layer(
-                CorsLayer::new()
-                    .allow_origin(Any)
-                    .allow_methods(Any)
-                    .allow_headers(Any),
-            );
+            .layer(cors);
 
         // Bind to address
-        let addr = SocketAddr::from(([0, 0, 0, 0], port));
-        let listener = tokio::net::TcpListener::bind(addr)
-            .await
-            .map_err(|e| StreamError::StartError(e.to_string()))?;
+        let bind_ip = if loopback_only { [127, 0, 0, 1] } else { [0, 0, 0, 0] };
+        let addr = SocketAddr::from((bind_ip, port));
+        let listener = std::net::TcpListener::bind(addr).map_err(|e| bind_error(port, e))?;
+        listener.set_nonblocking(true)?;
 
         let actual_port = listener.local_addr()?.port();
 
         // Spawn server task
-        tokio::spawn(async move {
-            axum::serve(listener, app)
+        tauri::async_runtime::spawn(async move {
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    errors::report(
+                        Subsystem::Streaming,
+                        "listener_failed",
+                        format!("Streaming server listener failed: {}", e),
+                        true,
+                    );
+                    return;
+                }
+            };
+            let app = app.into_make_service_with_connect_info::<SocketAddr>();
+            let result = axum::serve(listener, app)
                 .with_graceful_shutdown(async {
                     let _ = shutdown_rx.await;
                 })
-                .await
-                .ok();
+                .await;
+            if let Err(e) = result {
+                errors::report(
+                    Subsystem::Streaming,
+                    "server_stopped",
+                    format!("Streaming server stopped unexpectedly: {}", e),
+                    true,
+                );
+            }
         });
 
         self.shutdown_tx = Some(shutdown_tx);
         self.port = actual_port;
         self.local_ip = Some(local_ip.clone());
+        self.loopback_only = loopback_only;
+        self.options = Some(started_with);
 
         log::info!("Streaming server started on {}:{}", local_ip, actual_port);
         Ok((local_ip, actual_port))
     }
 
+    /// Move the running server to another port, keeping registered streams
+    ///
+    /// The new listener is bound first; if that fails the old one keeps
+    /// serving. The old listener then shuts down gracefully, so transfers
+    /// already in progress finish. Stream IDs are unchanged, only the port in
+    /// their URLs changes. Returns the new port.
+    pub fn change_port(&mut self, port: u16) -> Result<u16, StreamError> {
+        let Some(old_shutdown) = self.shutdown_tx.take() else {
+            return Err(StreamError::NotRunning);
+        };
+        if port == self.port {
+            self.shutdown_tx = Some(old_shutdown);
+            return Ok(port);
+        }
+
+        let mut options = self.options.clone().unwrap_or_default();
+        options.port = port;
+
+        match self.start(options) {
+            Ok((_, actual_port)) => {
+                let _ = old_shutdown.send(());
+                Ok(actual_port)
+            }
+            Err(e) => {
+                self.shutdown_tx = Some(old_shutdown);
+                Err(e)
+            }
+        }
+    }
+
+    /// LAN IP the server currently advertises, if the machine's IP has changed
+    /// since it started (None while stopped or loopback-only)
+    pub fn detect_ip_change(&self) -> Option<String> {
+        if self.shutdown_tx.is_none() || self.loopback_only {
+            return None;
+        }
+        let current = local_ip_address::local_ip().ok()?.to_string();
+        (self.local_ip.as_deref() != Some(current.as_str())).then_some(current)
+    }
+
+    /// LAN IP used in stream URLs (None while stopped)
+    pub fn local_ip(&self) -> Option<&str> {
+        self.local_ip.as_deref()
+    }
+
+    /// Shut the listener down for a rebind, keeping registered streams and
+    /// their IDs, and return the options to restart it with
+    ///
+    /// Used after a network switch: call `wait_for_port` (without holding any
+    /// lock around the server) and then `finish_rebind`.
+    pub fn begin_rebind(&mut self) -> Result<ServerOptions, StreamError> {
+        let Some(old_shutdown) = self.shutdown_tx.take() else {
+            return Err(StreamError::NotRunning);
+        };
+        let mut options = self.options.clone().unwrap_or_default();
+        options.port = self.port;
+        let _ = old_shutdown.send(());
+        Ok(options)
+    }
+
+    /// Wait for a shut down listener to release its port
+    ///
+    /// The old listener closes asynchronously; without this `start` would
+    /// report the port as held by another instance.
+    pub fn wait_for_port(options: &ServerOptions) {
+        let bind_ip = if options.loopback_only { [127, 0, 0, 1] } else { [0, 0, 0, 0] };
+        let addr = SocketAddr::from((bind_ip, options.port));
+        for _ in 0..REBIND_ATTEMPTS {
+            if std::net::TcpListener::bind(addr).is_ok() {
+                break;
+            }
+            std::thread::sleep(REBIND_RETRY_DELAY);
+        }
+    }
+
+    /// Restart the listener after `begin_rebind`, re-deriving the LAN IP
+    ///
+    /// Returns the new IP and port. If the server was started again in the
+    /// meantime, that instance is kept.
+    pub fn finish_rebind(&mut self, options: ServerOptions) -> Result<(String, u16), StreamError> {
+        if let (true, Some(ip)) = (self.shutdown_tx.is_some(), self.local_ip.clone()) {
+            return Ok((ip, self.port));
+        }
+
+        let result = self.start(options);
+        if result.is_err() {
+            // Nothing is listening any more; don't keep serving stale URLs
+            self.port = 0;
+            self.local_ip = None;
+        }
+        result
+    }
+
     /// Stop the streaming server
     pub fn stop(&mut self) {
         if let Some(tx) = self.shutdown_tx.take() {
@@ -168,6 +1019,8 @@ impl StreamingServer {
             self.state.clear_streams();
             self.port = 0;
             self.local_ip = None;
+            self.loopback_only = false;
+            self.options = None;
             log::info!("Streaming server stopped");
         }
     }
@@ -177,35 +1030,117 @@ impl StreamingServer {
         self.shutdown_tx.is_some()
     }
 
-    /// Get server URL
+    /// Check if the server only accepts connections from this machine
+    pub fn is_loopback_only(&self) -> bool {
+        self.loopback_only
+    }
+
+    /// Port the server is listening on (0 when stopped)
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Get server URL, using the advertised hostname if one is set
     pub fn get_url(&self) -> Option<String> {
         if let (Some(ip), port) = (&self.local_ip, self.port) {
             if port > 0 {
-                return Some(format!("http://{}:{}", ip, port));
+                let host = match &self.hostname {
+                    Some(hostname) if !self.loopback_only => hostname,
+                    _ => ip,
+                };
+                return Some(format_base_url(host, port));
             }
         }
         None
     }
 
+    /// Only serve streams to clients in these ranges (empty = any client)
+    ///
+    /// Applies to new requests right away, without restarting the server.
+    pub fn set_ip_allowlist(&self, ranges: Vec<IpRange>) {
+        *self.state.ip_allowlist.write() = ranges;
+    }
+
     /// Register a file for streaming
-    pub fn register_stream(&self, path: PathBuf) -> String {
-        self.state.register_stream(path)
+    ///
+    /// `chunk_size` overrides the read buffer size picked from the file type.
+    pub fn register_stream(
+        &self,
+        path: PathBuf,
+        chunk_size: Option<usize>,
+    ) -> Result<String, StreamError> {
+        let chunk_size = match chunk_size {
+            Some(size) => validate_chunk_size(size)?,
+            None => chunk_size_for(get_content_type(&path)),
+        };
+        Ok(self.state.register_stream(path, chunk_size))
+    }
+
+    /// Register generated content for streaming
+    pub fn register_bytes_stream(
+        &self,
+        data: Vec<u8>,
+        content_type: &str,
+        filename: &str,
+    ) -> Result<String, StreamError> {
+        self.state.register_bytes_stream(data, content_type, filename)
+    }
+
+    /// Current read buffer memory usage
+    pub fn buffer_usage(&self) -> BufferUsage {
+        self.state.buffer_usage()
+    }
+
+    /// Start a bandwidth test. Returns its ID, payload URL and served-bytes counter.
+    pub fn start_speed_test(&self) -> Option<(String, String, Arc<AtomicU64>)> {
+        let base_url = self.get_url()?;
+        let (id, bytes_served) = self.state.start_speed_test();
+        let url = format!("{}/speedtest/{}", base_url, id);
+        Some((id, url, bytes_served))
+    }
+
+    /// End a bandwidth test
+    pub fn end_speed_test(&self, id: &str) {
+        self.state.end_speed_test(id);
     }
 
     /// Get stream URL for a registered stream
     pub fn get_stream_url(&self, stream_id: &str, filename: Option<&str>) -> Option<String> {
         let base_url = self.get_url()?;
-        if let Some(fname) = filename {
-            Some(format!("{}/stream/{}/{}", base_url, stream_id, fname))
-        } else {
-            Some(format!("{}/stream/{}", base_url, stream_id))
-        }
+        Some(format_stream_url(&base_url, stream_id, filename))
     }
 
     /// Remove a stream
     pub fn remove_stream(&self, id: &str) {
         self.state.remove_stream(id);
     }
+
+    /// Switch a file stream between direct and transcoded serving
+    pub fn set_stream_mode(&self, id: &str, mode: StreamMode) -> Result<(), StreamError> {
+        self.state.set_stream_mode(id, mode)
+    }
+
+    /// Group streams as a queue, returns the queue ID
+    pub fn register_queue(&self, stream_ids: Vec<String>) -> String {
+        self.state.register_queue(stream_ids)
+    }
+
+    /// Remove a queue and its streams (None if the queue doesn't exist)
+    pub fn remove_queue(&self, id: &str) -> Option<usize> {
+        self.state.remove_queue(id)
+    }
+
+    /// List registered streams with their URLs and transfer stats
+    pub fn list_streams(&self) -> Vec<(StreamSummary, Option<String>)> {
+        self.state
+            .list_streams()
+            .into_iter()
+            .map(|summary| {
+                let url = self.get_stream_url(&summary.stream_id, Some(&summary.filename));
+                (summary, url)
+            })
+            .collect()
+    }
 }
 
 impl Default for StreamingServer {
@@ -217,30 +1152,106 @@ impl Default for StreamingServer {
 /// Stream handler with Range request support
 async fn stream_handler(
     State(state): State<StreamingState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     Path(id): Path<String>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    stream_file(state, &id, headers).await
+    stream_file(state, peer, &id, &method, headers).await
 }
 
 /// Stream handler with filename (for better TV compatibility)
+///
+/// The filename segment is ignored; only the stream ID is used for lookup.
 async fn stream_handler_with_filename(
     State(state): State<StreamingState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     Path((id, _filename)): Path<(String, String)>,
+    method: Method,
     headers: HeaderMap,
 ) -> Response {
-    stream_file(state, &id, headers).await
+    stream_file(state, peer, &id, &method, headers).await
+}
+
+/// 403 response for a client outside the IP allowlist (None = allowed)
+fn refuse_client(state: &StreamingState, peer: SocketAddr, id: &str) -> Option<Response> {
+    if state.allows_client(peer.ip()) {
+        return None;
+    }
+    log::info!("Refused {} to {} (not in the IP allowlist)", id, peer.ip());
+    Some((StatusCode::FORBIDDEN, "Client not allowed").into_response())
+}
+
+/// Serve generated data to a bandwidth test client until the test ends
+async fn speed_test_handler(
+    State(state): State<StreamingState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Path(id): Path<String>,
+) -> Response {
+    if let Some(refused) = refuse_client(&state, peer, &id) {
+        return refused;
+    }
+    let Some(bytes_served) = state.speed_tests.read().get(&id).cloned() else {
+        return (StatusCode::NOT_FOUND, "Test not found").into_response();
+    };
+
+    let stream = async_stream::stream! {
+        let mut remaining = SPEED_TEST_BYTES;
+        while remaining > 0 && state.speed_tests.read().contains_key(&id) {
+            let n = std::cmp::min(remaining, CHUNK_SIZE as u64) as usize;
+            remaining -= n as u64;
+            bytes_served.fetch_add(n as u64, Ordering::Relaxed);
+            yield Ok::<_, std::io::Error>(bytes::Bytes::from_static(&SPEED_TEST_CHUNK[..n]));
+        }
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header(header::CONTENT_LENGTH, SPEED_TEST_BYTES)
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from_stream(stream))
+        .unwrap()
 }
 
 /// Core streaming logic with Range support
-async fn stream_file(state: StreamingState, id: &str, headers: HeaderMap) -> Response {
+async fn stream_file(
+    state: StreamingState,
+    peer: SocketAddr,
+    id: &str,
+    method: &Method,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(refused) = refuse_client(&state, peer, id) {
+        return refused;
+    }
+
     // Get file path
-    let path = match state.get_stream_path(id) {
-        Some(p) => p,
-        None => {
-            return (StatusCode::NOT_FOUND, "Stream not found").into_response();
-        }
+    let Some(entry) = state.get_stream(id) else {
+        return (StatusCode::NOT_FOUND, "Stream not found").into_response();
     };
+    let StreamEntry {
+        path,
+        stats,
+        data,
+        content_type,
+        mode,
+        chunk_size,
+        ..
+    } = entry;
+    stats.requests.fetch_add(1, Ordering::Relaxed);
+    stats.last_access.store(unix_now(), Ordering::Relaxed);
+
+    if mode == StreamMode::Transcode {
+        return stream_transcoded(&state, id, &path, method, &headers, stats);
+    }
+
+    // Use the registered content type, else determine it from the extension
+    let content_type = content_type.unwrap_or_else(|| get_content_type(&path).to_string());
+
+    if let Some(data) = data {
+        return stream_memory(data, content_type, headers, &stats);
+    }
 
     // Open file
     let mut file = match File::open(&path).await {
@@ -261,9 +1272,6 @@ async fn stream_file(state: StreamingState, id: &str, headers: HeaderMap) -> Res
     };
     let file_size = metadata.len();
 
-    // Determine content type from extension
-    let content_type = get_content_type(&path);
-
     // Parse Range header
     let range = headers
         .get(header::RANGE)
@@ -282,7 +1290,7 @@ async fn stream_file(state: StreamingState, id: &str, headers: HeaderMap) -> Res
             }
 
             // Create limited reader
-            let stream = create_file_stream(file, length);
+            let stream = create_file_stream(file, length, chunk_size, stats, state.buffers.clone());
 
             Response::builder()
                 .status(StatusCode::PARTIAL_CONTENT)
@@ -298,7 +1306,7 @@ async fn stream_file(state: StreamingState, id: &str, headers: HeaderMap) -> Res
         }
         None => {
             // Full file response
-            let stream = create_file_stream(file, file_size);
+            let stream = create_file_stream(file, file_size, chunk_size, stats, state.buffers.clone());
 
             Response::builder()
                 .status(StatusCode::OK)
@@ -311,25 +1319,185 @@ async fn stream_file(state: StreamingState, id: &str, headers: HeaderMap) -> Res
     }
 }
 
+/// Start ffmpeg re-encoding a file to H.264/AAC MPEG-TS on stdout
+fn spawn_transcoder(path: &std::path::Path) -> std::io::Result<tokio::process::Child> {
+    let mut cmd = tokio::process::Command::new("ffmpeg");
+    cmd.args(["-hide_banner", "-loglevel", "error", "-nostdin", "-i"])
+        .arg(path)
+        .args(["-map", "0:v:0?", "-map", "0:a:0?"])
+        .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "21"])
+        .args(["-pix_fmt", "yuv420p"])
+        .args(["-c:a", "aac", "-b:a", "192k", "-ac", "2"])
+        .args(["-f", "mpegts", "pipe:1"])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    cmd.spawn()
+}
+
+/// Serve a transcoded stream
+///
+/// The stream's ffmpeg is started by the first request after a mode switch
+/// and reused by later ones: each new connection takes over the output from
+/// the previous connection, so a client that reconnects continues where it
+/// was instead of restarting from the beginning. HEAD requests only get the
+/// headers, and Range requests that don't start at 0 get 416.
+fn stream_transcoded(
+    state: &StreamingState,
+    id: &str,
+    path: &std::path::Path,
+    method: &Method,
+    headers: &HeaderMap,
+    stats: Arc<StreamStats>,
+) -> Response {
+    let range = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    if range.is_some_and(|range| range.trim() != "bytes=0-") {
+        return Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::ACCEPT_RANGES, "none")
+            .body(Body::empty())
+            .unwrap();
+    }
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "video/mp2t")
+        .header(header::ACCEPT_RANGES, "none");
+    if method == Method::HEAD {
+        return response.body(Body::empty()).unwrap();
+    }
+
+    let (output, reader) = match state.transcoder_output(id, path) {
+        Ok(output) => output,
+        Err(message) => {
+            errors::report(Subsystem::Streaming, "transcode_failed", message.clone(), true);
+            return (StatusCode::INTERNAL_SERVER_ERROR, message).into_response();
+        }
+    };
+    // Take over from any connection still reading
+    let generation = reader.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let state = state.clone();
+    let id = id.to_string();
+    let buffers = state.buffers.clone();
+    let stream = async_stream::stream! {
+        let Some(_lease) = buffers.lease(CHUNK_SIZE).await else {
+            return;
+        };
+
+        // Ends when ffmpeg finishes, is killed by a mode switch or a newer
+        // connection takes over
+        let mut buffer = bytes::BytesMut::with_capacity(CHUNK_SIZE);
+        loop {
+            let mut stdout = output.lock().await;
+            if reader.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            buffer.reserve(CHUNK_SIZE);
+            let read = stdout.read_buf(&mut buffer).await;
+            drop(stdout);
+            match read {
+                Ok(0) => {
+                    state.finish_transcoder(&id, &output);
+                    break;
+                }
+                Ok(n) => {
+                    stats.bytes_served.fetch_add(n as u64, Ordering::Relaxed);
+                    yield Ok::<_, std::io::Error>(buffer.split().freeze());
+                }
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            }
+        }
+    };
+
+    response.body(Body::from_stream(stream)).unwrap()
+}
+
+/// Serve an in-memory stream with the same Range handling as files
+fn stream_memory(
+    data: bytes::Bytes,
+    content_type: String,
+    headers: HeaderMap,
+    stats: &StreamStats,
+) -> Response {
+    let size = data.len() as u64;
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| parse_range(s, size));
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+    let (builder, body) = match range {
+        Some((start, end)) => (
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size)),
+            data.slice(start as usize..=end as usize),
+        ),
+        None => (builder.status(StatusCode::OK), data),
+    };
+
+    stats.bytes_served.fetch_add(body.len() as u64, Ordering::Relaxed);
+    builder
+        .header(header::CONTENT_LENGTH, body.len())
+        .body(Body::from(body))
+        .unwrap()
+}
+
 /// Create async stream from file
+///
+/// Each connection reserves a buffer of the stream's chunk size for its
+/// lifetime. The buffer is reused between reads once the previous chunk has
+/// been sent.
 fn create_file_stream(
     file: File,
     length: u64,
+    chunk_size: usize,
+    stats: Arc<StreamStats>,
+    buffers: Arc<ReadBuffers>,
 ) -> impl futures_core::Stream<Item = Result<bytes::Bytes, std::io::Error>> {
     async_stream::stream! {
+        // Wait for buffer memory to be available
+        let Some(_lease) = buffers.lease(chunk_size).await else {
+            return;
+        };
+
         let mut file = file;
         let mut remaining = length;
-        let mut buffer = vec![0u8; 64 * 1024]; // 64KB chunks
+        let mut buffer = bytes::BytesMut::with_capacity(chunk_size);
 
         while remaining > 0 {
-            let to_read = std::cmp::min(remaining as usize, buffer.len());
-            match file.read(&mut buffer[..to_read]).await {
+            let to_read = std::cmp::min(remaining, chunk_size as u64) as usize;
+            // Reclaims the allocation if the last chunk was already dropped
+            buffer.reserve(to_read);
+            let mut limited = (&mut file).take(to_read as u64);
+            match limited.read_buf(&mut buffer).await {
                 Ok(0) => break, // EOF
                 Ok(n) => {
                     remaining -= n as u64;
-                    yield Ok(bytes::Bytes::copy_from_slice(&buffer[..n]));
+                    stats.bytes_served.fetch_add(n as u64, Ordering::Relaxed);
+                    yield Ok(buffer.split().freeze());
                 }
                 Err(e) => {
+                    // Usually the file went away, e.g. the rclone mount dropped
+                    errors::report(
+                        Subsystem::Streaming,
+                        "read_failed",
+                        format!("Failed to read stream file: {}", e),
+                        true,
+                    );
                     yield Err(e);
                     break;
                 }
@@ -371,6 +1539,18 @@ fn parse_range(range_header: &str, file_size: u64) -> Option<(u64, u64)> {
     Some((start, end))
 }
 
+/// Check if a path or URL points at an audio file, judging by its extension
+pub fn is_audio_path(path_or_url: &str) -> bool {
+    // Drop query string/fragment so "stream.mp3?api_key=..." still matches
+    let path = path_or_url.split(['?', '#']).next().unwrap_or(path_or_url);
+    get_content_type(&PathBuf::from(path)).starts_with("audio/")
+}
+
+/// Check if a path has a known audio or video extension (case-insensitive)
+pub fn is_media_path(path: &str) -> bool {
+    get_content_type(&PathBuf::from(path.to_lowercase())) != "application/octet-stream"
+}
+
 /// Get content type from file extension
 fn get_content_type(path: &PathBuf) -> &'static str {
     match path.extension().and_then(|e| e.to_str()) {
@@ -389,3 +1569,191 @@ fn get_content_type(path: &PathBuf) -> &'static str {
         _ => "application/octet-stream",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn ip_range_parses_and_normalizes() {
+        assert_eq!(IpRange::parse("192.168.1.77/24").unwrap().to_string(), "192.168.1.0/24");
+        assert_eq!(IpRange::parse(" 10.0.0.7 ").unwrap().to_string(), "10.0.0.7/32");
+        assert_eq!(IpRange::parse("fd00::1/8").unwrap().to_string(), "fd00::/8");
+        assert_eq!(IpRange::parse("0.0.0.0/0").unwrap().to_string(), "0.0.0.0/0");
+    }
+
+    #[test]
+    fn ip_range_rejects_invalid_input() {
+        for input in ["", "abc", "1.2.3.4/", "1.2.3.4/33", "::/129", "1.2.3/24", "1.2.3.4/-1"] {
+            assert!(
+                matches!(IpRange::parse(input), Err(StreamError::InvalidIpRange(_))),
+                "{:?} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn ip_range_contains_peers() {
+        let lan = IpRange::parse("192.168.1.0/24").unwrap();
+        assert!(lan.contains(ip("192.168.1.1")));
+        assert!(lan.contains(ip("192.168.1.255")));
+        assert!(!lan.contains(ip("192.168.2.1")));
+        assert!(!lan.contains(ip("10.0.0.1")));
+        assert!(!lan.contains(ip("fe80::1")));
+
+        let tv = IpRange::parse("192.168.1.50").unwrap();
+        assert!(tv.contains(ip("192.168.1.50")));
+        assert!(!tv.contains(ip("192.168.1.51")));
+
+        let ula = IpRange::parse("fd00::/8").unwrap();
+        assert!(ula.contains(ip("fd12:3456::1")));
+        assert!(!ula.contains(ip("fe80::1")));
+        assert!(!ula.contains(ip("192.168.1.1")));
+    }
+
+    #[test]
+    fn ip_range_matches_ipv4_mapped_peers() {
+        let lan = IpRange::parse("192.168.1.0/24").unwrap();
+        assert!(lan.contains(ip("::ffff:192.168.1.20")));
+        assert!(!lan.contains(ip("::ffff:192.168.2.20")));
+    }
+
+    #[test]
+    fn allowlist_allows_listed_and_local_clients() {
+        let state = StreamingState::new();
+        assert!(state.allows_client(ip("203.0.113.9")), "empty allowlist allows everyone");
+
+        *state.ip_allowlist.write() = vec![IpRange::parse("192.168.1.0/24").unwrap()];
+        assert!(state.allows_client(ip("192.168.1.20")));
+        assert!(state.allows_client(ip("::ffff:192.168.1.20")));
+        assert!(state.allows_client(ip("127.0.0.1")));
+        assert!(state.allows_client(ip("::1")));
+        assert!(!state.allows_client(ip("192.168.2.20")));
+        assert!(!state.allows_client(ip("::ffff:203.0.113.9")));
+    }
+
+    #[test]
+    fn stream_url_encodes_reserved_characters() {
+        let url = format_stream_url("http://host:8765", "abc", Some("My Movie (2021) #1.mkv"));
+        assert_eq!(url, "http://host:8765/stream/abc/My%20Movie%20%282021%29%20%231.mkv");
+    }
+
+    #[test]
+    fn stream_url_encodes_multibyte_characters() {
+        let url = format_stream_url("http://host:8765", "abc", Some("Amélie 千と千尋.mp4"));
+        assert_eq!(
+            url,
+            "http://host:8765/stream/abc/Am%C3%A9lie%20%E5%8D%83%E3%81%A8%E5%8D%83%E5%B0%8B.mp4"
+        );
+        assert!(url.is_ascii());
+    }
+
+    #[test]
+    fn stream_url_without_filename() {
+        let url = format_stream_url("http://host:8765", "abc", None);
+        assert_eq!(url, "http://host:8765/stream/abc");
+    }
+
+    /// Fetch a path from the local server and return the status line and body
+    /// Send a request to a loopback server, returning the response head and body
+    fn http_request_local(port: u16, request_line: &str, headers: &str) -> (String, Vec<u8>) {
+        use std::io::{Read, Write};
+
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let request = format!(
+            "{} HTTP/1.0\r\nHost: 127.0.0.1:{}\r\n{}Connection: close\r\n\r\n",
+            request_line, port, headers
+        );
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let split = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .expect("complete response");
+        let head = String::from_utf8_lossy(&response[..split]).into_owned();
+        (head, response[split + 4..].to_vec())
+    }
+
+    fn http_get_local(port: u16, path: &str) -> (String, Vec<u8>) {
+        let (head, body) = http_request_local(port, &format!("GET {}", path), "");
+        let status = head.lines().next().unwrap_or_default().to_string();
+        (status, body)
+    }
+
+    #[test]
+    fn encoded_stream_urls_round_trip_through_the_server() {
+        let dir = std::env::temp_dir().join(format!("hubremote-url-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut server = StreamingServer::new();
+        let (_, port) = server
+            .start(ServerOptions {
+                port: 0,
+                loopback_only: true,
+                cors_allowed_origins: None,
+            })
+            .unwrap();
+
+        let names = ["My Movie (2021) #1.mkv", "Amélie 千と千尋.mp4"];
+        for (index, name) in names.iter().enumerate() {
+            let contents = format!("contents of file {}", index);
+            let path = dir.join(name);
+            std::fs::write(&path, &contents).unwrap();
+
+            let filename = stream_filename(&path);
+            let id = server.register_stream(path, None).unwrap();
+            let url = server.get_stream_url(&id, Some(&filename)).unwrap();
+            let request_path = url
+                .strip_prefix(&format!("http://127.0.0.1:{}", port))
+                .unwrap()
+                .to_string();
+            assert!(!request_path.contains(' ') && !request_path.contains('#'));
+
+            let (status, body) = http_get_local(port, &request_path);
+            assert!(status.contains(" 200 "), "{}: {}", name, status);
+            assert_eq!(body, contents.as_bytes(), "{}", name);
+        }
+
+        server.stop();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn transcoded_streams_answer_head_and_reject_ranges_without_starting_ffmpeg() {
+        let path = std::env::temp_dir().join(format!("hubremote-ts-test-{}", std::process::id()));
+        std::fs::write(&path, b"not really video").unwrap();
+
+        let mut server = StreamingServer::new();
+        let (_, port) = server
+            .start(ServerOptions {
+                port: 0,
+                loopback_only: true,
+                cors_allowed_origins: None,
+            })
+            .unwrap();
+        let id = server.register_stream(path.clone(), None).unwrap();
+        server.set_stream_mode(&id, StreamMode::Transcode).unwrap();
+
+        let (head, body) = http_request_local(port, &format!("HEAD /stream/{}", id), "");
+        assert!(head.lines().next().unwrap().contains(" 200 "), "{}", head);
+        assert!(head.to_lowercase().contains("accept-ranges: none"), "{}", head);
+        assert!(body.is_empty());
+
+        let (head, _) =
+            http_request_local(port, &format!("GET /stream/{}", id), "Range: bytes=1000-\r\n");
+        assert!(head.lines().next().unwrap().contains(" 416 "), "{}", head);
+
+        assert!(server.state.transcoders.lock().is_empty());
+
+        server.stop();
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,112 @@
+//! Sidecar subtitle discovery
+//!
+//! Finds subtitle files next to a local or mounted media file, e.g.
+//! `Movie.srt`, `Movie.en.srt` or `Movie.eng.forced.ass` for `Movie.mkv`.
+
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+
+/// Subtitle extensions mpv can load
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt", "sub", "sup", "smi"];
+
+/// Subtitle file found next to a media file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarSubtitle {
+    pub path: String,
+    /// Language code taken from the filename suffix (`movie.en.srt` -> "en")
+    pub lang_guess: Option<String>,
+    /// Suffix flags such as "forced" or "sdh"
+    pub flags: Vec<String>,
+    /// Name matches the media file exactly (`movie.srt`)
+    pub exact_match: bool,
+}
+
+/// Find subtitle files sharing the media file's base name, best match first
+pub fn find_sidecars(media_path: &Path) -> Result<Vec<SidecarSubtitle>, String> {
+    let dir = media_path
+        .parent()
+        .ok_or_else(|| format!("No parent directory for {:?}", media_path))?;
+    let stem = media_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| format!("Invalid media file name: {:?}", media_path))?
+        .to_lowercase();
+
+    // Works the same for rclone mounts: they're listed like any directory
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to list {:?}: {}", dir, e))?;
+
+    let mut subtitles: Vec<SidecarSubtitle> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            if !SUBTITLE_EXTENSIONS.contains(&ext.as_str()) {
+                return None;
+            }
+
+            let sub_stem = path.file_stem()?.to_str()?.to_lowercase();
+            let suffix = if sub_stem == stem {
+                ""
+            } else {
+                sub_stem.strip_prefix(&stem)?.strip_prefix('.')?
+            };
+
+            let mut lang_guess = None;
+            let mut flags = Vec::new();
+            for part in suffix.split('.').filter(|p| !p.is_empty()) {
+                let is_lang = (2..=3).contains(&part.len())
+                    && part.chars().all(|c| c.is_ascii_alphabetic())
+                    && part != "sdh";
+                if lang_guess.is_none() && is_lang {
+                    lang_guess = Some(part.to_string());
+                } else {
+                    flags.push(part.to_string());
+                }
+            }
+
+            Some(SidecarSubtitle {
+                path: path.to_string_lossy().into_owned(),
+                lang_guess,
+                flags,
+                exact_match: suffix.is_empty(),
+            })
+        })
+        .collect();
+
+    // Exact match first, then unflagged, then by language
+    subtitles.sort_by(|a, b| {
+        b.exact_match
+            .cmp(&a.exact_match)
+            .then_with(|| a.flags.len().cmp(&b.flags.len()))
+            .then_with(|| a.lang_guess.cmp(&b.lang_guess))
+    });
+
+    Ok(subtitles)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// List subtitle files alongside a media file
+#[tauri::command]
+pub fn find_sidecar_subtitles(media_path: String) -> CommandResult<Vec<SidecarSubtitle>> {
+    match find_sidecars(Path::new(&media_path)) {
+        Ok(subtitles) => CommandResult::ok(subtitles),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
+/// Load an external subtitle file into the current file and select it
+#[tauri::command]
+pub fn add_subtitle_file(state: State<MpvState>, path: String) -> CommandResult<()> {
+    match state.add_subtitle(&path) {
+        Ok(_) => CommandResult::ok_empty(),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
@@ -0,0 +1,439 @@
+//! Preferred audio/subtitle languages
+//!
+//! The preferences are handed to mpv as `alang`/`slang`, which covers the
+//! selection when a file loads. Some files (concatenated or segmented
+//! content) change their track layout mid-playback, and mpv then falls back
+//! to its default pick. The player monitor's poll checks the track list and
+//! remembers the selected tracks. When the layout of the playing file
+//! changes and a selected track is gone, it re-applies any preferences (emitting
+//! `tracks-reapplied`). The first layout of a new file is left alone, so
+//! tracks picked at load (by `alang`/`slang` or per-file `aid`/`sid` options,
+//! as a restored session uses) are kept. Either way `tracks-selected` is
+//! emitted with the audio and subtitle tracks in use.
+//!
+//! Subtitle languages form a fallback chain. If no subtitle track matches any
+//! of them, the first subtitle track can be turned on instead of leaving
+//! subtitles off, which mpv can't express through `slang` alone.
+//!
+//! Tracks flagged as forced hold subtitles for foreign-language dialogue
+//! only. With forced subtitles honored, a forced track in the audio's
+//! language is shown when subtitles would otherwise stay off, like Jellyfin
+//! and Plex do.
+
+use crate::commands::CommandResult;
+use crate::mpv::MpvState;
+use crate::mpv_ipc::{MpvIpc, MpvIpcError, MpvTrack};
+use crate::player_monitor::PlayerSnapshot;
+use crate::settings::{SettingsState, TrackPreferences};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Tracks selected by re-applying the preferences (None = left as it was)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackSelection {
+    pub audio: Option<i64>,
+    pub subtitle: Option<i64>,
+}
+
+impl TrackSelection {
+    fn changed(&self) -> bool {
+        self.audio.is_some() || self.subtitle.is_some()
+    }
+}
+
+/// File the watcher last saw, its track layout and the tracks selected in it
+#[derive(Debug, Default)]
+struct WatchedFile {
+    path: Option<String>,
+    /// Type and id per track
+    layout: Vec<(String, i64)>,
+    audio: Option<i64>,
+    subtitle: Option<i64>,
+}
+
+/// What changed in the track list since the last check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayoutChange {
+    /// The track list of a newly loaded file arrived
+    NewFile,
+    /// The playing file's layout changed; true if a selected track is gone
+    Changed(bool),
+}
+
+/// Track layout watcher state
+pub struct TrackWatchState {
+    current: Mutex<WatchedFile>,
+}
+
+impl TrackWatchState {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(WatchedFile::default()),
+        }
+    }
+}
+
+impl Default for TrackWatchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn track_preferences(app: &AppHandle) -> TrackPreferences {
+    app.try_state::<SettingsState>()
+        .map(|settings| settings.get().track_preferences)
+        .unwrap_or_default()
+}
+
+/// Whether a track's language tag matches a preferred language
+///
+/// Case-insensitive; a two-letter code also matches the three-letter code it
+/// starts ("en" matches "eng"), which covers the common ISO 639 forms.
+pub fn language_matches(track_lang: &str, preferred: &str) -> bool {
+    let track_lang = track_lang.trim().to_lowercase();
+    let preferred = preferred.trim().to_lowercase();
+    if track_lang.is_empty() || preferred.is_empty() {
+        return false;
+    }
+    track_lang == preferred
+        || (preferred.len() == 2 && track_lang.starts_with(&preferred))
+        || (track_lang.len() == 2 && preferred.starts_with(&track_lang))
+}
+
+/// Rank of a track's language in the preference list (None = not preferred)
+fn language_rank(track: &MpvTrack, languages: &[String]) -> Option<usize> {
+    let lang = track.lang.as_deref()?;
+    languages
+        .iter()
+        .position(|preferred| language_matches(lang, preferred))
+}
+
+/// Best track of `kind` for the preferences, or None if the current selection
+/// already has the best available language or nothing matches
+fn preferred_track(tracks: &[MpvTrack], kind: &str, languages: &[String]) -> Option<i64> {
+    let candidates: Vec<(&MpvTrack, usize)> = tracks
+        .iter()
+        .filter(|track| track.kind == kind)
+        .filter_map(|track| language_rank(track, languages).map(|rank| (track, rank)))
+        .collect();
+    let best_rank = candidates.iter().map(|(_, rank)| *rank).min()?;
+
+    // Keep the user's pick among tracks of the same language
+    if candidates
+        .iter()
+        .any(|(track, rank)| track.selected && *rank == best_rank)
+    {
+        return None;
+    }
+
+    candidates
+        .iter()
+        .filter(|(_, rank)| *rank == best_rank)
+        .max_by_key(|(track, _)| track.default)
+        .map(|(track, _)| track.id)
+}
+
+/// First subtitle track, if subtitles are off and none matches the chain
+fn fallback_subtitle(tracks: &[MpvTrack], preferences: &TrackPreferences) -> Option<i64> {
+    let mut subtitles = tracks.iter().filter(|track| track.kind == "sub");
+    if !preferences.enable_any_subtitle
+        || subtitles.clone().any(|track| track.selected)
+        || subtitles
+            .clone()
+            .any(|track| language_rank(track, &preferences.subtitle_languages).is_some())
+    {
+        return None;
+    }
+    subtitles.next().map(|track| track.id)
+}
+
+/// Forced subtitle track for the audio language, if subtitles are off
+///
+/// `audio` is the audio track being switched to (None = the selected one).
+/// Forced tracks without a language tag are used when none matches.
+fn forced_subtitle(tracks: &[MpvTrack], audio: Option<i64>) -> Option<i64> {
+    let subtitles = tracks.iter().filter(|track| track.kind == "sub");
+    if subtitles.clone().any(|track| track.selected) {
+        return None;
+    }
+
+    let audio_lang = tracks
+        .iter()
+        .filter(|track| track.kind == "audio")
+        .find(|track| audio.map_or(track.selected, |id| track.id == id))
+        .and_then(|track| track.lang.as_deref());
+
+    let forced: Vec<&MpvTrack> = subtitles.filter(|track| track.forced).collect();
+    let matching = forced.iter().find(|track| {
+        track
+            .lang
+            .as_deref()
+            .zip(audio_lang)
+            .is_some_and(|(lang, audio_lang)| language_matches(lang, audio_lang))
+    });
+    matching
+        .or_else(|| forced.iter().find(|track| track.lang.is_none()))
+        .map(|track| track.id)
+}
+
+/// Select the preferred audio and subtitle tracks of the loaded file
+pub fn reapply(
+    ipc: &MpvIpc,
+    preferences: &TrackPreferences,
+) -> Result<TrackSelection, MpvIpcError> {
+    let tracks = ipc.get_track_list()?;
+    let mut selection = TrackSelection::default();
+
+    if let Some(id) = preferred_track(&tracks, "audio", &preferences.audio_languages) {
+        ipc.set_audio_track(id)?;
+        selection.audio = Some(id);
+    }
+    let subtitle = preferred_track(&tracks, "sub", &preferences.subtitle_languages)
+        .or_else(|| fallback_subtitle(&tracks, preferences))
+        .or_else(|| {
+            preferences
+                .honor_forced_subtitles
+                .then(|| forced_subtitle(&tracks, selection.audio))
+                .flatten()
+        });
+    if let Some(id) = subtitle {
+        ipc.set_subtitle_track(id)?;
+        selection.subtitle = Some(id);
+    }
+
+    Ok(selection)
+}
+
+/// Audio and subtitle tracks in use (None = off or nothing loaded)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectedTracks {
+    pub audio: Option<MpvTrack>,
+    pub subtitle: Option<MpvTrack>,
+}
+
+/// Resolve `aid`/`sid` against the track list
+fn selected_tracks(ipc: &MpvIpc) -> Result<SelectedTracks, MpvIpcError> {
+    let tracks = ipc.get_track_list()?;
+    // "no" when the track type is off
+    let resolve = |property: &str, kind: &str| {
+        let id = ipc.get_property::<serde_json::Value>(property).ok()?.as_i64()?;
+        tracks
+            .iter()
+            .find(|track| track.kind == kind && track.id == id)
+            .cloned()
+    };
+
+    Ok(SelectedTracks {
+        audio: resolve("aid", "audio"),
+        subtitle: resolve("sid", "sub"),
+    })
+}
+
+/// Check whether a new file's track list arrived or the layout changed
+///
+/// Also records the selected tracks, so manual track changes count as the
+/// selection to keep.
+fn layout_changed(snapshot: &PlayerSnapshot, state: &TrackWatchState) -> Option<LayoutChange> {
+    let tracks = &snapshot.tracks;
+    if tracks.is_empty() {
+        return None;
+    }
+    let layout: Vec<(String, i64)> = tracks
+        .iter()
+        .map(|track| (track.kind.clone(), track.id))
+        .collect();
+    let selected = |kind: &str| {
+        tracks
+            .iter()
+            .find(|track| track.kind == kind && track.selected)
+            .map(|track| track.id)
+    };
+    let watched = WatchedFile {
+        audio: selected("audio"),
+        subtitle: selected("sub"),
+        path: snapshot.path.clone(),
+        layout,
+    };
+
+    // Only once per layout, so manual track changes afterwards are kept
+    let mut current = state.current.lock();
+    let change = if current.path != watched.path {
+        Some(LayoutChange::NewFile)
+    } else if current.layout != watched.layout {
+        let gone = |kind: &str, id: Option<i64>| {
+            id.is_some_and(|id| !watched.layout.contains(&(kind.to_string(), id)))
+        };
+        Some(LayoutChange::Changed(
+            gone("audio", current.audio) || gone("sub", current.subtitle),
+        ))
+    } else {
+        None
+    };
+    *current = watched;
+    change
+}
+
+/// Re-apply the preferences after a layout change. Returns the event to
+/// emit if that switched tracks.
+fn reapply_after_change(mpv: &MpvState, preferences: &TrackPreferences) -> Option<TrackSelection> {
+    let selection = mpv.with_player(|ipc| reapply(ipc, preferences)).ok()?;
+    if !selection.changed() {
+        return None;
+    }
+    log::info!("Track list changed, re-applied preferences: {:?}", selection);
+    Some(selection)
+}
+
+/// Track layout check for the player monitor's poll
+///
+/// Always runs, since `tracks-selected` is emitted for every file whether or
+/// not preferences are set.
+pub(crate) fn check(app: &AppHandle, mpv: &MpvState, snapshot: &PlayerSnapshot) {
+    let Some(change) = layout_changed(snapshot, &app.state::<TrackWatchState>()) else {
+        return;
+    };
+
+    let preferences = track_preferences(app);
+    if change == LayoutChange::Changed(true) && !preferences.is_empty() {
+        if let Some(selection) = reapply_after_change(mpv, &preferences) {
+            let _ = app.emit("tracks-reapplied", selection);
+        }
+    }
+    if let Ok(selected) = mpv.with_player(selected_tracks) {
+        let _ = app.emit("tracks-selected", selected);
+    }
+}
+
+/// Trim and drop empty language codes
+fn clean_languages(languages: Vec<String>) -> Vec<String> {
+    languages
+        .into_iter()
+        .map(|lang| lang.trim().to_string())
+        .filter(|lang| !lang.is_empty())
+        .collect()
+}
+
+/// Save preferences and apply them to the running player
+fn save_and_apply(
+    app: &AppHandle,
+    state: &MpvState,
+    preferences: TrackPreferences,
+) -> CommandResult<TrackPreferences> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available");
+    };
+    if let Err(e) = settings.update(|s| s.track_preferences = preferences.clone()) {
+        return CommandResult::err(e);
+    }
+
+    if state.is_initialized() {
+        let result = state.set_track_languages(&preferences).and_then(|_| {
+            state
+                .with_player(|ipc| reapply(ipc, &preferences))
+                .map(|_| ())
+        });
+        if let Err(e) = result {
+            return CommandResult::err(e.to_string());
+        }
+    }
+
+    CommandResult::ok(preferences)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Save preferred audio/subtitle languages in priority order and apply them
+///
+/// Takes effect for the next file through mpv's `alang`/`slang`, and right
+/// away for the loaded file. Empty lists restore mpv's default selection.
+#[tauri::command]
+pub fn set_track_preferences(
+    app: AppHandle,
+    state: State<MpvState>,
+    audio_languages: Vec<String>,
+    subtitle_languages: Vec<String>,
+) -> CommandResult<TrackPreferences> {
+    let preferences = TrackPreferences {
+        audio_languages: clean_languages(audio_languages),
+        subtitle_languages: clean_languages(subtitle_languages),
+        ..track_preferences(&app)
+    };
+    save_and_apply(&app, &state, preferences)
+}
+
+/// Set the subtitle language fallback chain
+///
+/// Languages are tried in order for every file. If none has a track,
+/// `enable_any_if_none` turns on the first subtitle track instead of leaving
+/// subtitles off; that part applies to the loaded file and whenever
+/// preferences are re-applied, not at load. Audio preferences are kept.
+#[tauri::command]
+pub fn set_subtitle_fallback(
+    app: AppHandle,
+    state: State<MpvState>,
+    chain: Vec<String>,
+    enable_any_if_none: bool,
+) -> CommandResult<TrackPreferences> {
+    let preferences = TrackPreferences {
+        subtitle_languages: clean_languages(chain),
+        enable_any_subtitle: enable_any_if_none,
+        ..track_preferences(&app)
+    };
+    save_and_apply(&app, &state, preferences)
+}
+
+/// Show forced subtitles matching the audio language when subtitles are off
+///
+/// Applied right away to the loaded file and whenever preferences are
+/// re-applied after a layout change. Other preferences are kept.
+#[tauri::command]
+pub fn set_honor_forced_subtitles(
+    app: AppHandle,
+    state: State<MpvState>,
+    enabled: bool,
+) -> CommandResult<TrackPreferences> {
+    let preferences = TrackPreferences {
+        honor_forced_subtitles: enabled,
+        ..track_preferences(&app)
+    };
+    save_and_apply(&app, &state, preferences)
+}
+
+/// Get the saved track language preferences
+#[tauri::command]
+pub fn get_track_preferences(app: AppHandle) -> CommandResult<TrackPreferences> {
+    CommandResult::ok(track_preferences(&app))
+}
+
+/// Get the audio and subtitle tracks in use, with their titles and languages
+///
+/// The same data is emitted as `tracks-selected` once a file's track list is
+/// available.
+#[tauri::command]
+pub fn get_selected_tracks(state: State<MpvState>) -> CommandResult<SelectedTracks> {
+    match state.with_player(selected_tracks) {
+        Ok(selected) => CommandResult::ok(selected),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
+
+/// Select the preferred tracks of the loaded file now
+///
+/// Returns the tracks that were switched; a track already in the best
+/// available language is left alone.
+#[tauri::command]
+pub fn reapply_track_preferences(
+    app: AppHandle,
+    state: State<MpvState>,
+) -> CommandResult<TrackSelection> {
+    let preferences = track_preferences(&app);
+    match state.with_player(|ipc| reapply(ipc, &preferences)) {
+        Ok(selection) => CommandResult::ok(selection),
+        Err(e) => CommandResult::err(e.to_string()),
+    }
+}
@@ -3,6 +3,9 @@
 //! Provides system tray icon with context menu for controlling
 //! the application when minimized.
 
+use crate::jellyfin;
+use crate::mpv::MpvState;
+use crate::settings::{self, SettingsState, TrayClickAction};
 use std::sync::Mutex;
 use tauri::{
     image::Image,
@@ -19,6 +22,19 @@ pub struct TrayPlaybackInfo {
     pub artist: Option<String>,
 }
 
+/// Event payload for `focus-now-playing` (all None when nothing is loaded)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NowPlaying {
+    /// Jellyfin item id, if the playing file is a Jellyfin stream
+    pub item_id: Option<String>,
+    /// Path or URL of the playing file
+    pub path: Option<String>,
+    pub title: Option<String>,
+    pub position: Option<f64>,
+    pub playlist_index: Option<i64>,
+}
+
 /// Global tray state
 pub struct TrayState {
     pub playback_info: Mutex<TrayPlaybackInfo>,
@@ -108,7 +124,8 @@ fn build_tray_menu<R: Runtime>(
             } else {
                 display_text
             };
-            let now_playing = MenuItem::with_id(app, MENU_NOW_PLAYING, &display_text, false, None::<&str>)?;
+            // Clicking it reveals the playing item in the main window
+            let now_playing = MenuItem::with_id(app, MENU_NOW_PLAYING, &display_text, true, None::<&str>)?;
             menu.append(&now_playing)?;
             menu.append(&PredefinedMenuItem::separator(app)?)?;
         }
@@ -138,6 +155,45 @@ fn build_tray_menu<R: Runtime>(
     Ok(menu)
 }
 
+/// Hide the main window if it's visible, otherwise show and focus it
+fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+/// Show and focus the main window and emit `focus-now-playing` with the
+/// current media, so the frontend can navigate to and highlight it
+pub fn reveal_now_playing<R: Runtime>(app: &AppHandle<R>) -> Result<NowPlaying, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Window not found".to_string())?;
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    let mut now_playing = NowPlaying::default();
+    if let Some(mpv) = app.try_state::<MpvState>() {
+        let loaded = mpv.is_initialized() && !mpv.is_idle().unwrap_or(true);
+        if loaded {
+            if let Ok(media) = mpv.get_current_media() {
+                now_playing.item_id = media.path.as_deref().and_then(jellyfin::item_id_from_url);
+                now_playing.path = media.path;
+                now_playing.title = media.media_title;
+                now_playing.playlist_index = media.playlist_index;
+            }
+            now_playing.position = mpv.get_position().ok();
+        }
+    }
+
+    let _ = app.emit("focus-now-playing", &now_playing);
+    Ok(now_playing)
+}
+
 /// Handle tray icon events (clicks)
 fn handle_tray_event<R: Runtime>(tray: &TrayIcon<R>, event: TrayIconEvent) {
     match event {
@@ -146,14 +202,23 @@ fn handle_tray_event<R: Runtime>(tray: &TrayIcon<R>, event: TrayIconEvent) {
             button_state: MouseButtonState::Up,
             ..
         } => {
-            // Left click: toggle window visibility
-            if let Some(window) = tray.app_handle().get_webview_window("main") {
-                if window.is_visible().unwrap_or(false) {
-                    let _ = window.hide();
-                } else {
-                    let _ = window.show();
-                    let _ = window.set_focus();
+            let app = tray.app_handle();
+            let action = app
+                .try_state::<SettingsState>()
+                .map(|settings| settings.get().tray_left_click_action)
+                .unwrap_or_default();
+            match action {
+                TrayClickAction::ToggleWindow => toggle_main_window(app),
+                TrayClickAction::ShowWindow => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+                TrayClickAction::PlayPause => {
+                    let _ = app.emit("tray-command", "playPause");
                 }
+                TrayClickAction::None => {}
             }
         }
         TrayIconEvent::DoubleClick {
@@ -173,14 +238,10 @@ fn handle_tray_event<R: Runtime>(tray: &TrayIcon<R>, event: TrayIconEvent) {
 /// Handle menu item clicks
 fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, menu_id: &str) {
     match menu_id {
-        MENU_SHOW_HIDE => {
-            if let Some(window) = app.get_webview_window("main") {
-                if window.is_visible().unwrap_or(false) {
-                    let _ = window.hide();
-                } else {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+        MENU_SHOW_HIDE => toggle_main_window(app),
+        MENU_NOW_PLAYING => {
+            if let Err(e) = reveal_now_playing(app) {
+                log::warn!("Failed to reveal now playing: {}", e);
             }
         }
         MENU_PLAY_PAUSE => {
@@ -303,12 +364,38 @@ pub fn get_minimize_to_tray(app: AppHandle) -> CommandResult<bool> {
 pub fn set_minimize_to_tray(app: AppHandle, enabled: bool) -> CommandResult<bool> {
     if let Some(state) = app.try_state::<TrayState>() {
         *state.minimize_to_tray.lock().unwrap() = enabled;
+        settings::save_integrations(&app);
         CommandResult::ok(enabled)
     } else {
         CommandResult::err("State not available".to_string())
     }
 }
 
+/// Get what a left click on the tray icon does
+#[tauri::command]
+pub fn get_tray_left_click_action(app: AppHandle) -> CommandResult<TrayClickAction> {
+    let action = app
+        .try_state::<SettingsState>()
+        .map(|settings| settings.get().tray_left_click_action)
+        .unwrap_or_default();
+    CommandResult::ok(action)
+}
+
+/// Set what a left click on the tray icon does (double click always shows the window)
+#[tauri::command]
+pub fn set_tray_left_click_action(
+    app: AppHandle,
+    action: TrayClickAction,
+) -> CommandResult<TrayClickAction> {
+    let Some(settings) = app.try_state::<SettingsState>() else {
+        return CommandResult::err("Settings not available".to_string());
+    };
+    match settings.update(|s| s.tray_left_click_action = action) {
+        Ok(_) => CommandResult::ok(action),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
 /// Show the main window
 #[tauri::command]
 pub fn show_window(app: AppHandle) -> CommandResult<bool> {
@@ -321,6 +408,15 @@ pub fn show_window(app: AppHandle) -> CommandResult<bool> {
     }
 }
 
+/// Show the main window and jump to the playing item (`focus-now-playing`)
+#[tauri::command]
+pub fn focus_now_playing(app: AppHandle) -> CommandResult<NowPlaying> {
+    match reveal_now_playing(&app) {
+        Ok(now_playing) => CommandResult::ok(now_playing),
+        Err(e) => CommandResult::err(e),
+    }
+}
+
 /// Hide the main window
 #[tauri::command]
 pub fn hide_window(app: AppHandle) -> CommandResult<bool> {